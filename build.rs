@@ -0,0 +1,36 @@
+use tonic_build::manual::{Builder, Method, Service};
+
+// Messages are hand-written prost structs (src/commands/grpc/proto.rs) mirroring
+// proto/game_data_scraper.proto and proto/game_record.proto, so only the service glue is
+// generated and no protoc install is needed.
+fn main() {
+    let proto = "crate::commands::grpc::proto";
+    let method = |name: &str, route: &str, input: &str, output: &str| {
+        Method::builder()
+            .name(name)
+            .route_name(route)
+            .input_type(format!("{}::{}", proto, input))
+            .output_type(format!("{}::{}", proto, output))
+            .codec_path("tonic::codec::ProstCodec")
+    };
+
+    let service = Service::builder()
+        .name("ScraperService")
+        .package("game_data_scraper.v1")
+        .method(method("scrape", "Scrape", "ScrapeRequest", "ScrapeResponse").build())
+        .method(method("get_game", "GetGame", "GetGameRequest", "GameRecord").build())
+        .method(
+            method(
+                "stream_new_games",
+                "StreamNewGames",
+                "StreamNewGamesRequest",
+                "GameRecord",
+            )
+            .server_streaming()
+            .build(),
+        )
+        .build();
+
+    Builder::new().build_client(false).compile(&[service]);
+    println!("cargo:rerun-if-changed=build.rs");
+}
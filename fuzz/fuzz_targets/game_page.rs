@@ -0,0 +1,10 @@
+#![no_main]
+
+use game_data_scraper::parsers::itch_game_info_parser::parse_itch_game_page_data;
+use libfuzzer_sys::fuzz_target;
+
+// seeded from the parser corpus: cargo fuzz run game_page corpus/game_page ../tests/fixtures/pages
+// pages come off the network, so invalid UTF-8 is decoded lossily the way reqwest's text() does
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_itch_game_page_data(&String::from_utf8_lossy(data));
+});
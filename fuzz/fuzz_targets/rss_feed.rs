@@ -0,0 +1,9 @@
+#![no_main]
+
+use game_data_scraper::scrapers::itch_rss_scraper::parse_rss_feed;
+use libfuzzer_sys::fuzz_target;
+
+// seeded from the recorded feeds: cargo fuzz run rss_feed corpus/rss_feed ../tests/fixtures/itch
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_rss_feed(&String::from_utf8_lossy(data));
+});
@@ -0,0 +1,47 @@
+#ifndef GAME_DATA_SCRAPER_H
+#define GAME_DATA_SCRAPER_H
+
+/* Generated by cbindgen from src/ffi.rs, regenerate with: cbindgen --config cbindgen.toml --output include/game_data_scraper.h */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Parses an itch.io game page and returns its info table as a JSON string.
+ *
+ * Returns null on failure, with the reason available from `gds_last_error`.
+ * The returned string must be released with `gds_string_free`.
+ *
+ * # Safety
+ *
+ * `html` must be null or point to a NUL-terminated string that stays valid for the call.
+ */
+char *gds_parse_itch_game_page(const char *html);
+
+/**
+ * Returns the error from the last failed call on this thread, or null.
+ *
+ * The string is owned by the library and stays valid until the next call on this thread.
+ */
+const char *gds_last_error(void);
+
+/**
+ * Releases a string returned by this library.
+ *
+ * # Safety
+ *
+ * `string` must be null or a pointer returned by this library that hasn't been freed yet.
+ */
+void gds_string_free(char *string);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* GAME_DATA_SCRAPER_H */
@@ -0,0 +1,528 @@
+use crate::parsers::itch_game_info_parser::{Author, Comment, Genre, Link, Tag};
+use crate::scrapers::images::{StoredImage, Thumbnail};
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::io::Write;
+
+// records per data block, so readers can split large files
+const BLOCK_RECORDS: usize = 1000;
+
+pub fn schema() -> Value {
+    let strings = json!({ "type": "array", "items": "string" });
+    let string_fields = [
+        "title",
+        "plain_title",
+        "link",
+        "create_date",
+        "update_date",
+        "release_date",
+        "pub_date",
+        "price",
+    ];
+
+    let mut fields: Vec<Value> = string_fields
+        .iter()
+        .map(|name| json!({ "name": name, "type": "string" }))
+        .collect();
+    fields.extend([
+        json!({ "name": "price_converted", "type": ["null", "double"], "default": null }),
+        json!({ "name": "description", "type": "string" }),
+        json!({
+            "name": "rating",
+            "type": ["null", {
+                "type": "record",
+                "name": "Rating",
+                "fields": [
+                    { "name": "score", "type": "float" },
+                    { "name": "count", "type": "int" }
+                ]
+            }],
+            "default": null
+        }),
+        json!({
+            "name": "authors",
+            "type": {
+                "type": "array",
+                "items": {
+                    "type": "record",
+                    "name": "Author",
+                    "fields": [
+                        { "name": "name", "type": "string" },
+                        { "name": "url", "type": "string" }
+                    ]
+                }
+            }
+        }),
+        json!({
+            "name": "genres",
+            "type": {
+                "type": "array",
+                "items": {
+                    "type": "record",
+                    "name": "Genre",
+                    "fields": [
+                        { "name": "name", "type": "string" },
+                        { "name": "url", "type": "string" }
+                    ]
+                }
+            }
+        }),
+        json!({ "name": "raw_genres", "type": strings, "default": [] }),
+        json!({ "name": "made_with", "type": strings }),
+        json!({
+            "name": "tags",
+            "type": {
+                "type": "array",
+                "items": {
+                    "type": "record",
+                    "name": "Tag",
+                    "fields": [
+                        { "name": "name", "type": "string" },
+                        { "name": "slug", "type": "string" },
+                        { "name": "url", "type": "string" }
+                    ]
+                }
+            }
+        }),
+        json!({ "name": "raw_tags", "type": strings, "default": [] }),
+        json!({ "name": "average_session", "type": "string" }),
+        json!({ "name": "languages", "type": strings }),
+        json!({ "name": "inputs", "type": strings }),
+        json!({
+            "name": "links",
+            "type": {
+                "type": "array",
+                "items": {
+                    "type": "record",
+                    "name": "Link",
+                    "fields": [
+                        { "name": "name", "type": "string" },
+                        { "name": "url", "type": "string" }
+                    ]
+                }
+            }
+        }),
+        json!({ "name": "status", "type": "string" }),
+        json!({ "name": "platforms", "type": strings }),
+        json!({ "name": "accessibility", "type": strings }),
+        json!({
+            "name": "comments",
+            "type": {
+                "type": "array",
+                "items": {
+                    "type": "record",
+                    "name": "Comment",
+                    "fields": [
+                        { "name": "author", "type": "string" },
+                        { "name": "body", "type": "string" },
+                        { "name": "sentiment", "type": ["null", "float"], "default": null }
+                    ]
+                }
+            },
+            "default": []
+        }),
+        json!({ "name": "sentiment", "type": ["null", "float"], "default": null }),
+        json!({ "name": "updated_date", "type": "string", "default": "" }),
+        json!({ "name": "published_date", "type": "string", "default": "" }),
+        json!({
+            "name": "price_info",
+            "type": ["null", {
+                "type": "record",
+                "name": "PriceInfo",
+                "fields": [
+                    { "name": "amount", "type": ["null", "double"], "default": null },
+                    { "name": "currency", "type": "string" },
+                    { "name": "free", "type": "boolean" },
+                    { "name": "pwyw", "type": "boolean" }
+                ]
+            }],
+            "default": null
+        }),
+        json!({ "name": "cover_image", "type": "string", "default": "" }),
+        json!({ "name": "screenshots", "type": strings, "default": [] }),
+        json!({
+            "name": "images",
+            "type": {
+                "type": "array",
+                "items": {
+                    "type": "record",
+                    "name": "StoredImage",
+                    "fields": [
+                        { "name": "kind", "type": "string" },
+                        { "name": "url", "type": "string" },
+                        { "name": "path", "type": "string" },
+                        {
+                            "name": "thumbnails",
+                            "type": {
+                                "type": "array",
+                                "items": {
+                                    "type": "record",
+                                    "name": "Thumbnail",
+                                    "fields": [
+                                        { "name": "size", "type": "int" },
+                                        { "name": "path", "type": "string" }
+                                    ]
+                                }
+                            },
+                            "default": []
+                        }
+                    ]
+                }
+            },
+            "default": []
+        }),
+        json!({ "name": "warnings", "type": strings, "default": [] }),
+    ]);
+
+    json!({
+        "type": "record",
+        "name": "GameRecord",
+        "namespace": "game_data_scraper.v1",
+        "fields": fields,
+    })
+}
+
+// an Object Container File: header with the schema, then blocks of records each closed by the
+// file's sync marker; no compression codec
+pub fn write_container(out: &mut dyn Write, records: &[ItchData]) -> Result<()> {
+    let sync: [u8; 16] = rand::random();
+
+    let mut header = b"Obj\x01".to_vec();
+    put_long(&mut header, 2);
+    put_bytes(&mut header, b"avro.schema");
+    put_bytes(&mut header, schema().to_string().as_bytes());
+    put_bytes(&mut header, b"avro.codec");
+    put_bytes(&mut header, b"null");
+    put_long(&mut header, 0);
+    header.extend(sync);
+    out.write_all(&header)?;
+
+    for block in records.chunks(BLOCK_RECORDS) {
+        let mut data = Vec::new();
+        for record in block {
+            put_record(&mut data, record);
+        }
+
+        let mut framing = Vec::new();
+        put_long(&mut framing, block.len() as i64);
+        put_long(&mut framing, data.len() as i64);
+        out.write_all(&framing)?;
+        out.write_all(&data)?;
+        out.write_all(&sync)?;
+    }
+
+    Ok(())
+}
+
+// fields in schema order
+fn put_record(buf: &mut Vec<u8>, record: &ItchData) {
+    for value in [
+        &record.title,
+        &record.plain_title,
+        &record.link,
+        &record.create_date,
+        &record.update_date,
+        &record.release_date,
+        &record.pub_date,
+        &record.price,
+    ] {
+        put_bytes(buf, value.as_bytes());
+    }
+    put_optional_double(buf, record.price_converted);
+    put_bytes(buf, record.description.as_bytes());
+    match &record.rating {
+        Some(rating) => {
+            put_long(buf, 1);
+            buf.extend(rating.score.to_le_bytes());
+            put_long(buf, rating.count.into());
+        }
+        None => put_long(buf, 0),
+    }
+    put_array(buf, &record.authors, |buf, author: &Author| {
+        put_bytes(buf, author.name.as_bytes());
+        put_bytes(buf, author.url.as_bytes());
+    });
+    put_array(buf, &record.genres, |buf, genre: &Genre| {
+        put_bytes(buf, genre.name.as_bytes());
+        put_bytes(buf, genre.url.as_bytes());
+    });
+    put_strings(buf, &record.raw_genres);
+    put_strings(buf, &record.made_with);
+    put_array(buf, &record.tags, |buf, tag: &Tag| {
+        put_bytes(buf, tag.name.as_bytes());
+        put_bytes(buf, tag.slug.as_bytes());
+        put_bytes(buf, tag.url.as_bytes());
+    });
+    put_strings(buf, &record.raw_tags);
+    put_bytes(buf, record.average_session.as_bytes());
+    put_strings(buf, &record.languages);
+    put_strings(buf, &record.inputs);
+    put_array(buf, &record.links, |buf, link: &Link| {
+        put_bytes(buf, link.name.as_bytes());
+        put_bytes(buf, link.url.as_bytes());
+    });
+    put_bytes(buf, record.status.as_bytes());
+    put_strings(buf, &record.platforms);
+    put_strings(buf, &record.accessibility);
+    put_array(buf, &record.comments, |buf, comment: &Comment| {
+        put_bytes(buf, comment.author.as_bytes());
+        put_bytes(buf, comment.body.as_bytes());
+        put_optional_float(buf, comment.sentiment);
+    });
+    put_optional_float(buf, record.sentiment);
+    put_bytes(buf, record.updated_date.as_bytes());
+    put_bytes(buf, record.published_date.as_bytes());
+    match &record.price_info {
+        Some(price) => {
+            put_long(buf, 1);
+            put_optional_double(buf, price.amount);
+            put_bytes(buf, price.currency.as_bytes());
+            buf.push(price.free.into());
+            buf.push(price.pwyw.into());
+        }
+        None => put_long(buf, 0),
+    }
+    put_bytes(buf, record.cover_image.as_bytes());
+    put_strings(buf, &record.screenshots);
+    put_array(buf, &record.images, |buf, image: &StoredImage| {
+        put_bytes(buf, image.kind.as_bytes());
+        put_bytes(buf, image.url.as_bytes());
+        put_bytes(buf, image.path.as_bytes());
+        put_array(buf, &image.thumbnails, |buf, thumbnail: &Thumbnail| {
+            put_long(buf, thumbnail.size.into());
+            put_bytes(buf, thumbnail.path.as_bytes());
+        });
+    });
+    put_strings(buf, &record.warnings);
+}
+
+fn put_optional_float(buf: &mut Vec<u8>, value: Option<f32>) {
+    match value {
+        Some(value) => {
+            put_long(buf, 1);
+            buf.extend(value.to_le_bytes());
+        }
+        None => put_long(buf, 0),
+    }
+}
+
+fn put_optional_double(buf: &mut Vec<u8>, value: Option<f64>) {
+    match value {
+        Some(value) => {
+            put_long(buf, 1);
+            buf.extend(value.to_le_bytes());
+        }
+        None => put_long(buf, 0),
+    }
+}
+
+fn put_strings(buf: &mut Vec<u8>, values: &[String]) {
+    put_array(buf, values, |buf, value| put_bytes(buf, value.as_bytes()));
+}
+
+// a single block holding every item, then the empty block that ends the array
+fn put_array<T>(buf: &mut Vec<u8>, items: &[T], put_item: impl Fn(&mut Vec<u8>, &T)) {
+    if !items.is_empty() {
+        put_long(buf, items.len() as i64);
+        for item in items {
+            put_item(buf, item);
+        }
+    }
+    put_long(buf, 0);
+}
+
+fn put_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    put_long(buf, bytes.len() as i64);
+    buf.extend(bytes);
+}
+
+// zig-zag varint, used for both int and long
+fn put_long(buf: &mut Vec<u8>, value: i64) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n >= 0x80 {
+        buf.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    buf.push(n as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::itch_game_info_parser::ItchRating;
+    use crate::parsers::price::Price;
+    use serde_json::Map;
+    use std::collections::HashMap;
+
+    // reads values back by walking the schema, the way any Avro reader resolves a record
+    struct Reader<'a> {
+        bytes: &'a [u8],
+    }
+
+    impl Reader<'_> {
+        fn take(&mut self, len: usize) -> &[u8] {
+            let (taken, rest) = self.bytes.split_at(len);
+            self.bytes = rest;
+            taken
+        }
+
+        fn long(&mut self) -> i64 {
+            let (mut n, mut shift) = (0u64, 0);
+            loop {
+                let byte = self.take(1)[0];
+                n |= u64::from(byte & 0x7f) << shift;
+                shift += 7;
+                if byte & 0x80 == 0 {
+                    return (n >> 1) as i64 ^ -((n & 1) as i64);
+                }
+            }
+        }
+
+        fn bytes(&mut self) -> Vec<u8> {
+            let len = self.long() as usize;
+            self.take(len).to_vec()
+        }
+
+        fn value(&mut self, schema: &Value) -> Value {
+            match schema {
+                Value::String(kind) => match kind.as_str() {
+                    "null" => Value::Null,
+                    "boolean" => json!(self.take(1)[0] == 1),
+                    "int" | "long" => json!(self.long()),
+                    "float" => json!(f32::from_le_bytes(self.take(4).try_into().unwrap())),
+                    "double" => json!(f64::from_le_bytes(self.take(8).try_into().unwrap())),
+                    "string" => json!(String::from_utf8(self.bytes()).unwrap()),
+                    other => panic!("unexpected type {}", other),
+                },
+                Value::Array(branches) => {
+                    let branch = self.long() as usize;
+                    self.value(&branches[branch])
+                }
+                Value::Object(schema) => match schema["type"].as_str().unwrap() {
+                    "record" => {
+                        let mut record = Map::new();
+                        for field in schema["fields"].as_array().unwrap() {
+                            let name = field["name"].as_str().unwrap().to_string();
+                            record.insert(name, self.value(&field["type"]));
+                        }
+                        Value::Object(record)
+                    }
+                    "array" => {
+                        let mut items = Vec::new();
+                        loop {
+                            match self.long() {
+                                0 => return Value::Array(items),
+                                count => {
+                                    for _ in 0..count {
+                                        items.push(self.value(&schema["items"]));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    other => panic!("unexpected type {}", other),
+                },
+                other => panic!("unexpected schema {}", other),
+            }
+        }
+    }
+
+    fn read_container(bytes: &[u8]) -> Vec<Value> {
+        let mut reader = Reader { bytes };
+        assert_eq!(reader.take(4), b"Obj\x01");
+        let mut metadata = HashMap::new();
+        loop {
+            match reader.long() {
+                0 => break,
+                count => {
+                    for _ in 0..count {
+                        let key = String::from_utf8(reader.bytes()).unwrap();
+                        metadata.insert(key, reader.bytes());
+                    }
+                }
+            }
+        }
+        assert_eq!(metadata["avro.codec"], b"null");
+        let schema: Value = serde_json::from_slice(&metadata["avro.schema"]).unwrap();
+        let sync = reader.take(16).to_vec();
+
+        let mut records = Vec::new();
+        while !reader.bytes.is_empty() {
+            let count = reader.long();
+            let size = reader.long() as usize;
+            let mut block = Reader {
+                bytes: reader.take(size),
+            };
+            for _ in 0..count {
+                records.push(block.value(&schema));
+            }
+            assert!(block.bytes.is_empty());
+            assert_eq!(reader.take(16), sync);
+        }
+        records
+    }
+
+    fn record() -> ItchData {
+        ItchData {
+            title: "Cave Run".to_string(),
+            link: "https://alice.itch.io/cave-run".to_string(),
+            price: "$4.99".to_string(),
+            price_info: Some(Price {
+                amount: Some(4.99),
+                currency: "USD".to_string(),
+                free: false,
+                pwyw: false,
+            }),
+            rating: Some(ItchRating {
+                score: 4.5,
+                count: 20,
+            }),
+            authors: vec![Author {
+                name: "alice".to_string(),
+                url: "https://alice.itch.io".to_string(),
+            }],
+            tags: vec![Tag {
+                name: "Roguelike".to_string(),
+                slug: "roguelike".to_string(),
+                url: "https://itch.io/games/tag-roguelike".to_string(),
+            }],
+            platforms: vec!["Windows".to_string(), "Linux".to_string()],
+            comments: vec![Comment {
+                author: "miner42".to_string(),
+                body: "Great digging".to_string(),
+                sentiment: Some(0.5),
+            }],
+            cover_image: "https://img.itch.zone/cover.png".to_string(),
+            screenshots: vec!["https://img.itch.zone/shot.png".to_string()],
+            images: vec![StoredImage {
+                kind: "cover".to_string(),
+                url: "https://img.itch.zone/cover.png".to_string(),
+                path: "images/ab.png".to_string(),
+                thumbnails: vec![Thumbnail {
+                    size: 128,
+                    path: "images/ab-128.png".to_string(),
+                }],
+            }],
+            warnings: vec!["Skipped Status: missing".to_string()],
+            ..ItchData::default()
+        }
+    }
+
+    #[test]
+    fn container_decodes_back_to_the_records_written() {
+        let records: Vec<ItchData> = (0..BLOCK_RECORDS + 1).map(|_| record()).collect();
+        let mut out = Vec::new();
+        write_container(&mut out, &records).unwrap();
+
+        let decoded = read_container(&out);
+        assert_eq!(decoded.len(), records.len());
+        let fields = schema()["fields"].as_array().unwrap().len();
+        assert_eq!(decoded[0].as_object().unwrap().len(), fields);
+        let read_back: ItchData = serde_json::from_value(decoded[BLOCK_RECORDS].clone()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&read_back).unwrap(),
+            serde_json::to_value(record()).unwrap()
+        );
+    }
+}
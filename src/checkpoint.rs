@@ -0,0 +1,173 @@
+use crate::exit::Failure;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use clap::ValueEnum;
+use dialoguer::Confirm;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, ValueEnum, Clone, Copy, Default)]
+pub enum ResumeMode {
+    #[default]
+    Prompt,
+    Auto,
+    Never,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Saved<R> {
+    url: String,
+    start_page: i32,
+    end_page: i32,
+    next_page: i32,
+    updated: String,
+    // where the run that wrote this meant to put its output, templated outfiles expand
+    // differently on every run
+    #[serde(default)]
+    outfile: Option<PathBuf>,
+    records: Vec<R>,
+}
+
+// progress of a single feed scrape, rewritten after every finished page and removed once the
+// output has been written
+#[derive(Debug)]
+pub struct Checkpoint {
+    path: PathBuf,
+    url: String,
+    pages: RangeInclusive<i32>,
+    next_page: i32,
+    outfile: Option<PathBuf>,
+    resumed: Vec<ItchData>,
+}
+
+impl Checkpoint {
+    pub fn open(
+        path: &Path,
+        url: &str,
+        pages: RangeInclusive<i32>,
+        mode: ResumeMode,
+        force: bool,
+        outfile: Option<&Path>,
+    ) -> Result<Checkpoint> {
+        let mut checkpoint = Checkpoint {
+            path: path.to_path_buf(),
+            url: url.to_string(),
+            next_page: *pages.start(),
+            pages,
+            outfile: outfile.map(Path::to_path_buf),
+            resumed: Vec::new(),
+        };
+
+        if !path.exists() {
+            return Ok(checkpoint);
+        }
+
+        let saved = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Saved<ItchData>>(&contents).ok());
+        // checkpoints from before the outfile was recorded are checked against this run's
+        let written_to = saved
+            .as_ref()
+            .and_then(|saved| saved.outfile.clone())
+            .or_else(|| checkpoint.outfile.clone());
+
+        if let Some(saved) = saved.filter(|saved| checkpoint.is_compatible(saved)) {
+            if should_resume(path, &saved, mode)? {
+                checkpoint.next_page = saved.next_page;
+                checkpoint.resumed = saved.records;
+                return Ok(checkpoint);
+            }
+        }
+
+        if !force && is_newer(path, written_to.as_deref()) {
+            return Err(Failure::Config(anyhow!(
+                "Checkpoint {:?} holds progress that was never written out, resume it or pass --force to start over",
+                path
+            ))
+            .into());
+        }
+
+        Ok(checkpoint)
+    }
+
+    fn is_compatible<R>(&self, saved: &Saved<R>) -> bool {
+        saved.url == self.url
+            && saved.start_page == *self.pages.start()
+            && saved.end_page == *self.pages.end()
+            && (saved.start_page..=saved.end_page + 1).contains(&saved.next_page)
+    }
+
+    pub fn remaining_pages(&self) -> RangeInclusive<i32> {
+        self.next_page..=*self.pages.end()
+    }
+
+    pub fn resumed_records(&self) -> &[ItchData] {
+        &self.resumed
+    }
+
+    pub fn save(&self, next_page: i32, records: &[ItchData]) -> Result<()> {
+        let saved = Saved {
+            url: self.url.clone(),
+            start_page: *self.pages.start(),
+            end_page: *self.pages.end(),
+            next_page,
+            updated: Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            outfile: self.outfile.clone(),
+            records: self.resumed.iter().chain(records).collect(),
+        };
+
+        // written aside and renamed so an interrupted write can't corrupt the last checkpoint
+        let partial = self.path.with_extension("partial");
+        fs::write(&partial, serde_json::to_string(&saved)?)
+            .with_context(|| format!("Failed to write checkpoint {:?}", partial))?;
+        fs::rename(&partial, &self.path)
+            .with_context(|| format!("Failed to write checkpoint {:?}", self.path))?;
+
+        Ok(())
+    }
+
+    pub fn finish(&self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn should_resume<R>(path: &Path, saved: &Saved<R>, mode: ResumeMode) -> Result<bool> {
+    match mode {
+        ResumeMode::Auto => Ok(true),
+        ResumeMode::Never => Ok(false),
+        ResumeMode::Prompt if io::stdin().is_terminal() => {
+            let prompt = format!(
+                "Found a checkpoint from {} with {} record(s), stopped before page {}. Resume it?",
+                saved.updated,
+                saved.records.len(),
+                saved.next_page
+            );
+            Ok(Confirm::new()
+                .with_prompt(prompt)
+                .default(true)
+                .interact()?)
+        }
+        ResumeMode::Prompt => Err(Failure::Config(anyhow!(
+            "Found a checkpoint at {:?} but can't ask whether to resume it, pass --resume auto or --resume never",
+            path
+        ))
+        .into()),
+    }
+}
+
+// a checkpoint written after the output file (or with no output yet) still has work in it
+fn is_newer(checkpoint: &Path, outfile: Option<&Path>) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+
+    match (modified(checkpoint), outfile.and_then(modified)) {
+        (Some(checkpoint), Some(outfile)) => checkpoint > outfile,
+        _ => true,
+    }
+}
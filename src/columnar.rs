@@ -0,0 +1,410 @@
+use crate::exit::Failure;
+use crate::scrapers::itch_rss_scraper::ItchData;
+#[cfg(feature = "duckdb")]
+use anyhow::Context;
+use anyhow::{anyhow, Result};
+use arrow::array::{
+    ArrayRef, BooleanArray, Float32Array, Float64Array, Int32Array, ListBuilder, StringArray,
+    StringBuilder,
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use serde_json::Value;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+const TEXT_COLUMNS: [&str; 14] = [
+    "title",
+    "plain_title",
+    "link",
+    "create_date",
+    "update_date",
+    "release_date",
+    "updated_date",
+    "published_date",
+    "pub_date",
+    "price",
+    "price_currency",
+    "description",
+    "average_session",
+    "status",
+];
+
+const LIST_COLUMNS: [&str; 11] = [
+    "authors",
+    "author_urls",
+    "genres",
+    "genre_urls",
+    "made_with",
+    "tags",
+    "tag_slugs",
+    "languages",
+    "inputs",
+    "platforms",
+    "accessibility",
+];
+
+fn text_values(record: &ItchData) -> [&str; 14] {
+    [
+        &record.title,
+        &record.plain_title,
+        &record.link,
+        &record.create_date,
+        &record.update_date,
+        &record.release_date,
+        &record.updated_date,
+        &record.published_date,
+        &record.pub_date,
+        &record.price,
+        record
+            .price_info
+            .as_ref()
+            .map_or("", |price| price.currency.as_str()),
+        &record.description,
+        &record.average_session,
+        &record.status,
+    ]
+}
+
+fn list_values(record: &ItchData) -> [Vec<&str>; 11] {
+    [
+        record
+            .authors
+            .iter()
+            .map(|author| author.name.as_str())
+            .collect(),
+        record
+            .authors
+            .iter()
+            .map(|author| author.url.as_str())
+            .collect(),
+        record.genre_names(),
+        record
+            .genres
+            .iter()
+            .map(|genre| genre.url.as_str())
+            .collect(),
+        strs(&record.made_with),
+        record.tag_names(),
+        record.tags.iter().map(|tag| tag.slug.as_str()).collect(),
+        strs(&record.languages),
+        strs(&record.inputs),
+        strs(&record.platforms),
+        strs(&record.accessibility),
+    ]
+}
+
+// price_info flattened into price_amount, price_currency, price_free and price_pwyw columns
+fn amount(record: &ItchData) -> Option<f64> {
+    record.price_info.as_ref().and_then(|price| price.amount)
+}
+
+fn price_flags(record: &ItchData) -> (bool, bool) {
+    record
+        .price_info
+        .as_ref()
+        .map_or((false, false), |price| (price.free, price.pwyw))
+}
+
+fn strs(values: &[String]) -> Vec<&str> {
+    values.iter().map(String::as_str).collect()
+}
+
+pub fn schema() -> SchemaRef {
+    let mut fields: Vec<Field> = TEXT_COLUMNS
+        .iter()
+        .map(|name| Field::new(*name, DataType::Utf8, false))
+        .collect();
+
+    fields.push(Field::new("price_amount", DataType::Float64, true));
+    fields.push(Field::new("price_free", DataType::Boolean, false));
+    fields.push(Field::new("price_pwyw", DataType::Boolean, false));
+    fields.push(Field::new("rating_score", DataType::Float32, true));
+    fields.push(Field::new("rating_count", DataType::Int32, true));
+
+    for name in LIST_COLUMNS {
+        let item = Field::new("item", DataType::Utf8, true);
+        fields.push(Field::new(name, DataType::List(Arc::new(item)), false));
+    }
+
+    // links are nested name/url pairs, kept as JSON so the schema stays flat
+    fields.push(Field::new("links", DataType::Utf8, false));
+
+    Arc::new(Schema::new(fields))
+}
+
+pub fn record_batch(records: &[ItchData]) -> Result<RecordBatch> {
+    let mut columns: Vec<ArrayRef> = Vec::new();
+
+    for i in 0..TEXT_COLUMNS.len() {
+        let values: StringArray = records.iter().map(|r| Some(text_values(r)[i])).collect();
+        columns.push(Arc::new(values));
+    }
+
+    columns.push(Arc::new(Float64Array::from_iter(
+        records.iter().map(amount),
+    )));
+    columns.push(Arc::new(BooleanArray::from_iter(
+        records.iter().map(|r| Some(price_flags(r).0)),
+    )));
+    columns.push(Arc::new(BooleanArray::from_iter(
+        records.iter().map(|r| Some(price_flags(r).1)),
+    )));
+    columns.push(Arc::new(Float32Array::from_iter(
+        records
+            .iter()
+            .map(|r| r.rating.as_ref().map(|rating| rating.score)),
+    )));
+    columns.push(Arc::new(Int32Array::from_iter(
+        records
+            .iter()
+            .map(|r| r.rating.as_ref().map(|rating| rating.count)),
+    )));
+
+    for i in 0..LIST_COLUMNS.len() {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        for record in records {
+            for value in &list_values(record)[i] {
+                builder.values().append_value(value);
+            }
+            builder.append(true);
+        }
+        columns.push(Arc::new(builder.finish()));
+    }
+
+    let links = records
+        .iter()
+        .map(|r| serde_json::to_string(&r.links))
+        .collect::<Result<Vec<_>, _>>()?;
+    columns.push(Arc::new(StringArray::from(links)));
+
+    Ok(RecordBatch::try_new(schema(), columns)?)
+}
+
+// the same columns as plain text, for sinks that take rows of cells; lists are comma joined
+pub fn header() -> Vec<&'static str> {
+    let mut header = TEXT_COLUMNS.to_vec();
+    header.extend([
+        "price_amount",
+        "price_free",
+        "price_pwyw",
+        "rating_score",
+        "rating_count",
+    ]);
+    header.extend(LIST_COLUMNS);
+    header.push("links");
+    header
+}
+
+pub fn text_row(record: &ItchData) -> Vec<String> {
+    delimited_row(record, ", ")
+}
+
+pub fn delimited_row(record: &ItchData, list_delimiter: &str) -> Vec<String> {
+    let mut row: Vec<String> = text_values(record).map(str::to_string).to_vec();
+    row.push(
+        amount(record)
+            .map(|amount| amount.to_string())
+            .unwrap_or_default(),
+    );
+    let (free, pwyw) = price_flags(record);
+    row.extend([free.to_string(), pwyw.to_string()]);
+    match &record.rating {
+        Some(rating) => row.extend([rating.score.to_string(), rating.count.to_string()]),
+        None => row.extend([String::new(), String::new()]),
+    }
+    row.extend(list_values(record).map(|values| values.join(list_delimiter)));
+    row.push(serde_json::to_string(&record.links).unwrap_or_default());
+    row
+}
+
+// the same columns with numbers and lists kept as JSON numbers and arrays
+pub fn typed_row(record: &ItchData) -> Vec<Value> {
+    let mut row: Vec<Value> = text_values(record).map(Value::from).to_vec();
+    row.push(amount(record).into());
+    let (free, pwyw) = price_flags(record);
+    row.extend([Value::from(free), Value::from(pwyw)]);
+    row.push(record.rating.as_ref().map(|rating| rating.score).into());
+    row.push(record.rating.as_ref().map(|rating| rating.count).into());
+    row.extend(list_values(record).map(Value::from));
+    row.push(
+        serde_json::to_string(&record.links)
+            .unwrap_or_default()
+            .into(),
+    );
+    row
+}
+
+pub fn write_parquet(path: &Path, records: &[ItchData]) -> Result<()> {
+    let batch = record_batch(records)?;
+    let mut writer = ArrowWriter::try_new(File::create(path)?, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    Ok(())
+}
+
+// checked before scraping, so a build without DuckDB doesn't cost a whole run
+pub fn check_duckdb() -> Result<()> {
+    match cfg!(feature = "duckdb") {
+        true => Ok(()),
+        false => Err(Failure::Config(anyhow!(
+            "Built without the duckdb feature, --format duckdb needs it"
+        ))
+        .into()),
+    }
+}
+
+#[cfg(feature = "duckdb")]
+fn duckdb_type(data_type: &DataType) -> &'static str {
+    match data_type {
+        DataType::Float64 => "DOUBLE",
+        DataType::Float32 => "FLOAT",
+        DataType::Int32 => "INTEGER",
+        DataType::Boolean => "BOOLEAN",
+        DataType::List(_) => "VARCHAR[]",
+        _ => "VARCHAR",
+    }
+}
+
+// games that are already in the table are replaced, the same as the sqlite output; columns are
+// named rather than positional, and ones a file written by an older version lacks are added. The
+// staging table only exists inside the transaction, so a failed write leaves nothing behind
+#[cfg(feature = "duckdb")]
+pub fn write_duckdb(path: &Path, records: &[ItchData]) -> Result<()> {
+    let schema = schema();
+    let definitions = schema
+        .fields()
+        .iter()
+        .map(|field| format!("{} {}", field.name(), duckdb_type(field.data_type())))
+        .collect::<Vec<_>>();
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| field.name().as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut conn = duckdb::Connection::open(path)
+        .with_context(|| format!("Failed to open DuckDB database {:?}", path))?;
+    let tx = conn.transaction()?;
+    tx.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS games ({0});
+         CREATE TABLE games_staging ({0});",
+        definitions.join(", ")
+    ))?;
+    for definition in &definitions {
+        tx.execute_batch(&format!(
+            "ALTER TABLE games ADD COLUMN IF NOT EXISTS {}",
+            definition
+        ))?;
+    }
+
+    {
+        let mut appender = tx.appender("games_staging")?;
+        // a DuckDB data chunk holds at most 2048 rows
+        for chunk in records.chunks(2048) {
+            appender.append_record_batch(record_batch(chunk)?)?;
+        }
+        appender.flush()?;
+    }
+    tx.execute_batch(&format!(
+        "DELETE FROM games WHERE link IN (SELECT link FROM games_staging);
+         INSERT INTO games ({0}) SELECT {0} FROM games_staging;
+         DROP TABLE games_staging;",
+        columns
+    ))?;
+    tx.commit()
+        .with_context(|| format!("Failed to write DuckDB database {:?}", path))?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "duckdb"))]
+pub fn write_duckdb(_path: &Path, _records: &[ItchData]) -> Result<()> {
+    check_duckdb()
+}
+
+#[cfg(all(test, feature = "duckdb"))]
+mod tests {
+    use super::*;
+
+    fn database(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "game-data-scraper-columnar-{}-{}.duckdb",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn record(link: &str, title: &str) -> ItchData {
+        ItchData {
+            link: link.to_string(),
+            title: title.to_string(),
+            platforms: vec!["Windows".to_string(), "Linux".to_string()],
+            ..ItchData::default()
+        }
+    }
+
+    fn titles(path: &Path) -> Vec<(String, String)> {
+        let conn = duckdb::Connection::open(path).unwrap();
+        let mut stmt = conn
+            .prepare("SELECT link, title FROM games ORDER BY link")
+            .unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn rewriting_a_game_replaces_its_row() {
+        let path = database("replace");
+        write_duckdb(&path, &[record("a", "First"), record("b", "Second")]).unwrap();
+        write_duckdb(&path, &[record("a", "Renamed")]).unwrap();
+
+        assert_eq!(
+            titles(&path),
+            [
+                ("a".to_string(), "Renamed".to_string()),
+                ("b".to_string(), "Second".to_string())
+            ]
+        );
+        let platform: String = duckdb::Connection::open(&path)
+            .unwrap()
+            .query_row(
+                "SELECT platforms[2] FROM games WHERE link = 'a'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(platform, "Linux");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn appends_to_a_table_with_an_older_column_order() {
+        let path = database("older");
+        duckdb::Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE games (title VARCHAR, link VARCHAR);
+                 INSERT INTO games VALUES ('Old', 'a');",
+            )
+            .unwrap();
+        write_duckdb(&path, &[record("b", "New")]).unwrap();
+
+        assert_eq!(
+            titles(&path),
+            [
+                ("a".to_string(), "Old".to_string()),
+                ("b".to_string(), "New".to_string())
+            ]
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
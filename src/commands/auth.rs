@@ -0,0 +1,71 @@
+use crate::secrets;
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+use dialoguer::Password;
+use std::io::{self, BufRead, IsTerminal};
+
+#[derive(Args, Debug)]
+pub struct AuthArgs {
+    #[command(subcommand)]
+    pub command: AuthCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AuthCommand {
+    /// Store a secret such as an API key or webhook URL in the OS keyring
+    Login(LoginArgs),
+    /// Remove a stored secret from the OS keyring
+    Logout(LogoutArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct LoginArgs {
+    #[arg(value_name = "NAME")]
+    pub name: String,
+}
+
+#[derive(Args, Debug)]
+pub struct LogoutArgs {
+    #[arg(value_name = "NAME")]
+    pub name: String,
+}
+
+pub fn run(args: AuthArgs) -> Result<()> {
+    match args.command {
+        AuthCommand::Login(args) => {
+            let secret = read_secret(&args.name)?;
+            secrets::store(&args.name, &secret)?;
+            eprintln!(
+                "Stored {:?}, use \"keyring:{}\" in place of the value in config files",
+                args.name, args.name
+            );
+        }
+        AuthCommand::Logout(args) => {
+            secrets::delete(&args.name)?;
+            eprintln!("Removed {:?}", args.name);
+        }
+    }
+
+    Ok(())
+}
+
+// piped secrets are read from the first line of stdin so login can be scripted
+fn read_secret(name: &str) -> Result<String> {
+    let secret = if io::stdin().is_terminal() {
+        Password::new()
+            .with_prompt(format!("Secret for {}", name))
+            .interact()?
+    } else {
+        io::stdin()
+            .lock()
+            .lines()
+            .next()
+            .transpose()?
+            .unwrap_or_default()
+    };
+
+    match secret.trim() {
+        "" => Err(anyhow!("No secret given for {:?}", name)),
+        secret => Ok(secret.to_string()),
+    }
+}
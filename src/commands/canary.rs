@@ -0,0 +1,176 @@
+use crate::exit::Failure;
+use crate::notifiers::slack;
+use crate::parsers::itch_game_info_parser::parse_itch_game_page_data;
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// vary from visit to visit, so they say nothing about the layout
+const VOLATILE_FIELDS: &[&str] = &["comments", "warnings"];
+
+#[derive(Args, Debug)]
+pub struct CanaryArgs {
+    #[arg(long, value_name = "FILE PATH", default_value = "canary.json")]
+    pub expectations: PathBuf,
+
+    #[arg(long = "add", value_name = "URL")]
+    pub add: Vec<String>,
+
+    #[arg(long, conflicts_with = "add")]
+    pub update: bool,
+
+    #[arg(long, value_name = "URL")]
+    pub slack_webhook: Option<String>,
+}
+
+// what a page looked like when it was recorded: the fields that were filled, and the warnings
+// it already had, which aren't reported again
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Expectation {
+    fields: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+}
+
+pub async fn run(args: CanaryArgs) -> Result<()> {
+    let client = Client::new();
+    let mut expectations = load(&args.expectations)?;
+
+    if !args.add.is_empty() || args.update {
+        let urls: Vec<String> = match args.update {
+            true => expectations.keys().cloned().collect(),
+            false => args.add.clone(),
+        };
+        for url in urls {
+            let record = extract(&client, &url).await?;
+            let expectation = Expectation {
+                fields: filled_fields(&record),
+                warnings: warnings(&record),
+            };
+            eprintln!(
+                "Recorded {} ({} filled fields)",
+                url,
+                expectation.fields.len()
+            );
+            expectations.insert(url, expectation);
+        }
+        let json = serde_json::to_string_pretty(&expectations)?;
+        return fs::write(&args.expectations, json + "\n")
+            .with_context(|| format!("Failed to write {:?}", args.expectations));
+    }
+
+    if expectations.is_empty() {
+        return Err(Failure::Config(anyhow!(
+            "No canary pages in {:?}, record some with --add URL",
+            args.expectations
+        ))
+        .into());
+    }
+
+    let mut problems = Vec::new();
+    for (url, expected) in &expectations {
+        match extract(&client, url).await {
+            Ok(record) => problems.extend(
+                check(&record, expected)
+                    .into_iter()
+                    .map(|problem| format!("{}: {}", url, problem)),
+            ),
+            Err(err) => problems.push(format!("{}: {:#}", url, err)),
+        }
+    }
+
+    if problems.is_empty() {
+        eprintln!("{} canary page(s) unchanged", expectations.len());
+        return Ok(());
+    }
+
+    for problem in &problems {
+        eprintln!("{}", problem);
+    }
+    if let Some(webhook) = &args.slack_webhook {
+        let text = format!(
+            "Layout drift on {} canary page(s):\n• {}",
+            expectations.len(),
+            problems.join("\n• ")
+        );
+        if let Err(err) = slack::send_text(&client, webhook, &text).await {
+            eprintln!("Error sending Slack notification: {:?}", err);
+        }
+    }
+
+    Err(Failure::LayoutDrift(problems.len()).into())
+}
+
+fn load(path: &Path) -> Result<BTreeMap<String, Expectation>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    serde_json::from_str(&contents).with_context(|| format!("Invalid canary file {:?}", path))
+}
+
+async fn extract(client: &Client, url: &str) -> Result<Value> {
+    let html = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let record = parse_itch_game_page_data(&html)
+        .map_err(|err| anyhow!("No longer parses as an itch game page: {}", err))?;
+    Ok(serde_json::to_value(record)?)
+}
+
+fn filled_fields(record: &Value) -> Vec<String> {
+    record
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(field, value)| !VOLATILE_FIELDS.contains(&field.as_str()) && !is_empty(value))
+        .map(|(field, _)| field.clone())
+        .collect()
+}
+
+fn warnings(record: &Value) -> Vec<String> {
+    record["warnings"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect()
+}
+
+// any new parser warning is a sign of drift, the unknown row ones most of all
+fn check(record: &Value, expected: &Expectation) -> Vec<String> {
+    let mut problems: Vec<String> = warnings(record)
+        .into_iter()
+        .filter(|warning| !expected.warnings.contains(warning))
+        .collect();
+
+    let filled = filled_fields(record);
+    for field in &expected.fields {
+        if !filled.contains(field) {
+            problems.push(format!("{} is now empty", field));
+        }
+    }
+
+    problems
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(value) => value.is_empty(),
+        Value::Array(values) => values.is_empty(),
+        Value::Object(fields) => fields.is_empty(),
+        _ => false,
+    }
+}
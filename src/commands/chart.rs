@@ -0,0 +1,242 @@
+use crate::dataset::load_all;
+use crate::parsers::dates::parse_feed_date;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Duration, NaiveDate};
+use clap::{Args, ValueEnum};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct ChartArgs {
+    #[arg(required = true, value_name = "FILE PATH")]
+    pub inputs: Vec<PathBuf>,
+
+    #[arg(short, long, value_enum, value_name = "KIND")]
+    pub kind: ChartKind,
+
+    #[arg(short, long, value_name = "FILE PATH")]
+    pub outfile: PathBuf,
+
+    #[arg(long, value_name = "INTEGER", default_value_t = 5)]
+    pub top_tags: usize,
+
+    #[arg(long, value_name = "INTEGER", default_value_t = 1280)]
+    pub width: u32,
+
+    #[arg(long, value_name = "INTEGER", default_value_t = 720)]
+    pub height: u32,
+}
+
+#[derive(Debug, ValueEnum, Clone, Copy)]
+pub enum ChartKind {
+    Releases,
+    Tags,
+    Prices,
+}
+
+pub fn run(args: ChartArgs) -> Result<()> {
+    let records = load_all(&args.inputs)?;
+    let size = (args.width, args.height);
+
+    let is_png = args
+        .outfile
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+
+    if is_png {
+        let root = BitMapBackend::new(&args.outfile, size).into_drawing_area();
+        draw(&root, &args, &records)?;
+        root.present()?;
+    } else {
+        let root = SVGBackend::new(&args.outfile, size).into_drawing_area();
+        draw(&root, &args, &records)?;
+        root.present()?;
+    }
+
+    Ok(())
+}
+
+fn draw<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    args: &ChartArgs,
+    records: &[ItchData],
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+
+    match args.kind {
+        ChartKind::Releases => draw_releases(root, records),
+        ChartKind::Tags => draw_tags(root, records, args.top_tags),
+        ChartKind::Prices => draw_prices(root, records),
+    }
+}
+
+fn week_of(record: &ItchData) -> Option<NaiveDate> {
+    let date = parse_feed_date(&record.pub_date)?.date_naive();
+    Some(date - Duration::days(date.weekday().num_days_from_monday().into()))
+}
+
+fn week_range(weeks: impl Iterator<Item = NaiveDate> + Clone) -> Result<(NaiveDate, NaiveDate)> {
+    let first = weeks
+        .clone()
+        .min()
+        .ok_or_else(|| anyhow!("No records with a parseable publish date"))?;
+    let last = weeks.max().unwrap_or(first);
+
+    Ok((first, last + Duration::weeks(1)))
+}
+
+fn draw_releases<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    records: &[ItchData],
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let mut per_week: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    for week in records.iter().filter_map(week_of) {
+        *per_week.entry(week).or_default() += 1;
+    }
+
+    let (first, last) = week_range(per_week.keys().copied())?;
+    let max = per_week.values().copied().max().unwrap_or(0);
+
+    let mut chart = ChartBuilder::on(root)
+        .caption("Releases per week", ("sans-serif", 28))
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d(first..last, 0u32..max + 1)?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Week")
+        .y_desc("Games published")
+        .draw()?;
+
+    chart.draw_series(LineSeries::new(per_week, &BLUE).point_size(3))?;
+
+    Ok(())
+}
+
+fn draw_tags<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    records: &[ItchData],
+    top: usize,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let mut totals: HashMap<&str, u32> = HashMap::new();
+    for tag in records.iter().flat_map(|record| record.tag_names()) {
+        *totals.entry(tag).or_default() += 1;
+    }
+
+    let mut top_tags: Vec<(&str, u32)> = totals.into_iter().collect();
+    top_tags.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    top_tags.truncate(top);
+
+    let mut per_week: HashMap<&str, BTreeMap<NaiveDate, u32>> = HashMap::new();
+    for record in records {
+        let Some(week) = week_of(record) else {
+            continue;
+        };
+
+        for (tag, _) in &top_tags {
+            if record.tags.iter().any(|t| t.name == *tag) {
+                *per_week.entry(tag).or_default().entry(week).or_default() += 1;
+            }
+        }
+    }
+
+    let (first, last) = week_range(per_week.values().flat_map(|weeks| weeks.keys().copied()))?;
+    let max = per_week
+        .values()
+        .flat_map(|weeks| weeks.values().copied())
+        .max()
+        .unwrap_or(0);
+
+    let mut chart = ChartBuilder::on(root)
+        .caption("Tag popularity per week", ("sans-serif", 28))
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d(first..last, 0u32..max + 1)?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Week")
+        .y_desc("Games tagged")
+        .draw()?;
+
+    for (i, (tag, _)) in top_tags.iter().enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        let series = per_week.remove(tag).unwrap_or_default();
+
+        chart
+            .draw_series(LineSeries::new(series, color.stroke_width(2)).point_size(3))?
+            .label(*tag)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    Ok(())
+}
+
+fn draw_prices<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    records: &[ItchData],
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    // one bucket per whole currency unit
+    let buckets: Vec<u32> = records
+        .iter()
+        .filter_map(ItchData::price_amount)
+        .map(|amount| amount.floor() as u32)
+        .collect();
+
+    let max_bucket = *buckets
+        .iter()
+        .max()
+        .ok_or_else(|| anyhow!("No records with a parseable price"))?;
+
+    let mut counts: HashMap<u32, u32> = HashMap::new();
+    for bucket in &buckets {
+        *counts.entry(*bucket).or_default() += 1;
+    }
+    let max_count = counts.values().copied().max().unwrap_or(0);
+
+    let mut chart = ChartBuilder::on(root)
+        .caption("Price distribution", ("sans-serif", 28))
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d((0u32..max_bucket + 1).into_segmented(), 0u32..max_count + 1)?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Price")
+        .y_desc("Games")
+        .draw()?;
+
+    chart.draw_series(
+        Histogram::vertical(&chart)
+            .style(BLUE.filled())
+            .margin(2)
+            .data(buckets.iter().map(|bucket| (*bucket, 1))),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use crate::history;
+use crate::units::{format_size, parse_size};
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use clap::Args;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Args, Debug)]
+pub struct CleanArgs {
+    #[arg(long, value_name = "FILE PATH", required = true)]
+    pub history: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        value_parser = humantime::parse_duration,
+        required_unless_present = "max_size"
+    )]
+    pub older_than: Option<Duration>,
+
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    pub max_size: Option<u64>,
+
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+pub fn run(args: CleanArgs) -> Result<()> {
+    let cutoff = match args.older_than {
+        Some(age) => Some(
+            Utc::now()
+                - chrono::Duration::from_std(age)
+                    .map_err(|_| anyhow!("--older-than {:?} is too long", age))?,
+        ),
+        None => None,
+    };
+
+    for path in &args.history {
+        if !path.exists() {
+            return Err(anyhow!("No history file at {:?}", path));
+        }
+        let pruned = history::prune(path, cutoff, args.max_size, args.dry_run)
+            .with_context(|| format!("Failed to prune {:?}", path))?;
+
+        eprintln!(
+            "{}: {} {} of {} observations (~{})",
+            path.display(),
+            if args.dry_run {
+                "would remove"
+            } else {
+                "removed"
+            },
+            pruned.removed,
+            pruned.total,
+            format_size(pruned.bytes)
+        );
+    }
+
+    Ok(())
+}
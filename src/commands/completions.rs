@@ -0,0 +1,17 @@
+use anyhow::Result;
+use clap::Args;
+use clap_complete::{generate, Shell};
+use std::io;
+
+#[derive(Args, Debug)]
+pub struct CompletionsArgs {
+    #[arg(value_enum, value_name = "SHELL")]
+    pub shell: Shell,
+}
+
+pub fn run(args: CompletionsArgs, mut command: clap::Command) -> Result<()> {
+    let name = command.get_name().to_string();
+    generate(args.shell, &mut command, name, &mut io::stdout());
+
+    Ok(())
+}
@@ -0,0 +1,198 @@
+use super::merge::{newest, normalized_title, shares_author};
+use crate::dataset::{load_all, write_records};
+use crate::parsers::itch_game_info_parser::Author;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, Result};
+use clap::Args;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::{fs, io, io::Write};
+
+// words that distinguish uploads of the same game rather than different games
+const EDITION_WORDS: [&str; 9] = [
+    "demo",
+    "prototype",
+    "alpha",
+    "beta",
+    "full",
+    "version",
+    "edition",
+    "jam",
+    "build",
+];
+
+#[derive(Args, Debug)]
+pub struct DedupeArgs {
+    #[arg(required = true, value_name = "FILE PATH")]
+    pub inputs: Vec<PathBuf>,
+
+    #[arg(long, value_name = "SIMILARITY", default_value_t = 0.92)]
+    pub threshold: f64,
+
+    #[arg(long)]
+    pub merge: bool,
+
+    #[arg(short, long, value_name = "FILE PATH", requires = "merge")]
+    pub outfile: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE PATH")]
+    pub report: Option<PathBuf>,
+}
+
+#[derive(Serialize, Debug)]
+struct Cluster<'a> {
+    similarity: f64,
+    records: Vec<ClusterRecord<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+struct ClusterRecord<'a> {
+    title: &'a str,
+    link: &'a str,
+    authors: &'a [Author],
+    update_date: &'a str,
+}
+
+pub fn run(args: DedupeArgs) -> Result<()> {
+    if !(0.0..=1.0).contains(&args.threshold) {
+        return Err(anyhow!("--threshold must be between 0 and 1"));
+    }
+
+    let records = load_all(&args.inputs)?;
+    let keys: Vec<String> = records.iter().map(comparison_title).collect();
+
+    let mut parents: Vec<usize> = (0..records.len()).collect();
+    let mut matches: Vec<(usize, f64)> = Vec::new();
+
+    for i in 0..records.len() {
+        for j in i + 1..records.len() {
+            if keys[i].is_empty() || !shares_author(&records[i], &records[j]) {
+                continue;
+            }
+
+            let score = similarity(&keys[i], &keys[j]);
+            if score >= args.threshold {
+                union(&mut parents, i, j);
+                matches.push((i, score));
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..records.len() {
+        groups.entry(find(&mut parents, i)).or_default().push(i);
+    }
+
+    let mut similarities: BTreeMap<usize, f64> = BTreeMap::new();
+    for (i, score) in matches {
+        let lowest = similarities.entry(find(&mut parents, i)).or_insert(score);
+        *lowest = lowest.min(score);
+    }
+
+    let clusters: Vec<Cluster> = groups
+        .iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(root, members)| Cluster {
+            similarity: similarities.get(root).copied().unwrap_or(1.0),
+            records: members
+                .iter()
+                .map(|&i| ClusterRecord {
+                    title: &records[i].title,
+                    link: &records[i].link,
+                    authors: &records[i].authors,
+                    update_date: &records[i].update_date,
+                })
+                .collect(),
+        })
+        .collect();
+
+    eprintln!(
+        "Found {} duplicate clusters across {} records",
+        clusters.len(),
+        records.len()
+    );
+
+    let report = serde_json::to_string_pretty(&clusters)?;
+    match (&args.report, args.merge) {
+        (Some(file), _) => fs::write(file, report)?,
+        (None, false) => io::stdout().write_all(report.as_bytes())?,
+        (None, true) => {}
+    }
+
+    if args.merge {
+        let merged: Vec<ItchData> = groups
+            .into_values()
+            .filter_map(|members| {
+                members
+                    .into_iter()
+                    .map(|i| records[i].clone())
+                    .reduce(newest)
+            })
+            .collect();
+
+        write_records(args.outfile.as_deref(), &merged)?;
+    }
+
+    Ok(())
+}
+
+fn comparison_title(record: &ItchData) -> String {
+    normalized_title(record)
+        .split_whitespace()
+        .filter(|word| !EDITION_WORDS.contains(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn similarity(a: &str, b: &str) -> f64 {
+    strsim::jaro_winkler(a, b).max(token_set_ratio(a, b))
+}
+
+// fuzzywuzzy-style token set ratio: compares the shared words against each side's
+// full word set so reordered or padded titles still score highly
+fn token_set_ratio(a: &str, b: &str) -> f64 {
+    let a: BTreeSet<&str> = a.split_whitespace().collect();
+    let b: BTreeSet<&str> = b.split_whitespace().collect();
+
+    let common: Vec<&str> = a.intersection(&b).copied().collect();
+    // a single shared word is too weak a signal on its own
+    if common.len() < 2 {
+        return 0.0;
+    }
+
+    let joined = |extra: Vec<&str>| {
+        common
+            .iter()
+            .copied()
+            .chain(extra)
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let shared = common.join(" ");
+    let with_a = joined(a.difference(&b).copied().collect());
+    let with_b = joined(b.difference(&a).copied().collect());
+
+    [
+        strsim::normalized_levenshtein(&shared, &with_a),
+        strsim::normalized_levenshtein(&shared, &with_b),
+        strsim::normalized_levenshtein(&with_a, &with_b),
+    ]
+    .into_iter()
+    .fold(0.0, f64::max)
+}
+
+fn find(parents: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[i] = root;
+    root
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(parents, a), find(parents, b));
+    parents[a.max(b)] = a.min(b);
+}
@@ -0,0 +1,565 @@
+use crate::scrapers::itch_rss_scraper::ItchData;
+use crate::Site;
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+#[derive(Args, Debug)]
+pub struct ExplainSchemaArgs {
+    #[arg(short, long, value_enum, value_name = "SITE", default_value = "itch")]
+    pub site: Site,
+
+    #[arg(long)]
+    pub json: bool,
+}
+
+struct FieldDoc {
+    name: &'static str,
+    source: &'static str,
+    notes: &'static str,
+}
+
+const ITCH_FIELDS: &[FieldDoc] = &[
+    FieldDoc {
+        name: "title",
+        source: "RSS <title>",
+        notes: "Feed title, which itch suffixes with the price and platforms",
+    },
+    FieldDoc {
+        name: "plain_title",
+        source: "RSS <plainTitle>",
+        notes: "The game name on its own",
+    },
+    FieldDoc {
+        name: "link",
+        source: "RSS <link>",
+        notes: "Game page URL, used as the record's identity when merging and deduplicating",
+    },
+    FieldDoc {
+        name: "create_date",
+        source: "RSS <createDate>",
+        notes: "UTC as \"YYYY-MM-DD HH:MM:SS\", or RFC 3339 in the --timezone zone",
+    },
+    FieldDoc {
+        name: "update_date",
+        source: "RSS <updateDate>",
+        notes: "UTC as \"YYYY-MM-DD HH:MM:SS\", or RFC 3339 in the --timezone zone",
+    },
+    FieldDoc {
+        name: "release_date",
+        source: "page info table, \"Release date\" row",
+        notes: "Day only, e.g. \"Apr 10, 2024\"; empty when the page has no release date row",
+    },
+    FieldDoc {
+        name: "updated_date",
+        source: "page info table, \"Updated\" row <abbr title>",
+        notes: "UTC as \"DD Month YYYY @ HH:MM UTC\", or RFC 3339 in the --timezone zone; empty when the page has no such row",
+    },
+    FieldDoc {
+        name: "published_date",
+        source: "page info table, \"Published\" row <abbr title>",
+        notes: "UTC as \"DD Month YYYY @ HH:MM UTC\", or RFC 3339 in the --timezone zone; empty when the page has no such row",
+    },
+    FieldDoc {
+        name: "pub_date",
+        source: "RSS <pubDate>",
+        notes: "RFC 2822, or RFC 3339 in the --timezone zone",
+    },
+    FieldDoc {
+        name: "price",
+        source: "RSS <price>",
+        notes: "Display price with currency symbol, e.g. \"$4.99\"; \"$0.00\" for free games",
+    },
+    FieldDoc {
+        name: "price_info",
+        source: "RSS <price>, else the page's .buy_row price",
+        notes: "Parsed price; omitted when neither the feed nor the page shows one",
+    },
+    FieldDoc {
+        name: "price_info.amount",
+        source: "price_info",
+        notes: "The price, or the minimum for pay what you want; \"Name your own price\" is 0",
+    },
+    FieldDoc {
+        name: "price_info.currency",
+        source: "price_info",
+        notes: "ISO 4217 code, taken from the page when the feed price names none",
+    },
+    FieldDoc {
+        name: "price_info.free",
+        source: "price_info",
+        notes: "True for a zero price that isn't pay what you want",
+    },
+    FieldDoc {
+        name: "price_info.pwyw",
+        source: "price_info",
+        notes: "Pay what you want, from \"or more\" or \"Name your own price\"",
+    },
+    FieldDoc {
+        name: "price_converted",
+        source: "derived from price",
+        notes: "Price in the --convert-to currency, rounded to cents; only set when converting",
+    },
+    FieldDoc {
+        name: "description",
+        source: "RSS <description>",
+        notes: "HTML snippet with the cover image and short description",
+    },
+    FieldDoc {
+        name: "rating",
+        source: "page info table, \"Rating\" row",
+        notes: "null when the game has no ratings yet",
+    },
+    FieldDoc {
+        name: "rating.score",
+        source: "div[itemprop=ratingValue] content attribute",
+        notes: "Average stars, 0 to 5",
+    },
+    FieldDoc {
+        name: "rating.count",
+        source: "span[itemprop=ratingCount] content attribute",
+        notes: "Number of ratings",
+    },
+    FieldDoc {
+        name: "authors",
+        source: "page info table, \"Author\"/\"Authors\" row links",
+        notes: "One entry per credited creator",
+    },
+    FieldDoc {
+        name: "authors[].name",
+        source: "link text",
+        notes: "Display name, not unique",
+    },
+    FieldDoc {
+        name: "authors[].url",
+        source: "link href",
+        notes: "Creator page, e.g. https://name.itch.io; empty for authors without one",
+    },
+    FieldDoc {
+        name: "genres",
+        source: "page info table, \"Genre\" row links",
+        notes: "One entry per genre, in page order; empty when the page lists none",
+    },
+    FieldDoc {
+        name: "genres[].name",
+        source: "page info table, \"Genre\" row link text",
+        notes: "Canonical names when a --taxonomy is applied, \"Other\" outside its vocabulary",
+    },
+    FieldDoc {
+        name: "genres[].url",
+        source: "link href",
+        notes: "Browse page for the genre, e.g. https://itch.io/games/genre-action",
+    },
+    FieldDoc {
+        name: "raw_genres",
+        source: "page info table, \"Genre\" row links",
+        notes: "Original genres, only present when a taxonomy changed them",
+    },
+    FieldDoc {
+        name: "made_with",
+        source: "page info table, \"Made with\" row links",
+        notes: "Engines and tools, e.g. \"Godot\", \"Unity\"",
+    },
+    FieldDoc {
+        name: "tags",
+        source: "page info table, \"Tags\" row links",
+        notes: "One entry per tag, in page order; empty when the page lists none",
+    },
+    FieldDoc {
+        name: "tags[].name",
+        source: "page info table, \"Tags\" row link text",
+        notes: "Canonical names when a --taxonomy is applied",
+    },
+    FieldDoc {
+        name: "tags[].slug",
+        source: "link href, last path segment without \"tag-\"",
+        notes: "Stable id used by browse urls, e.g. \"pixel-art\"; empty in older records",
+    },
+    FieldDoc {
+        name: "tags[].url",
+        source: "link href",
+        notes: "Browse page for the tag, e.g. https://itch.io/games/tag-pixel-art",
+    },
+    FieldDoc {
+        name: "raw_tags",
+        source: "page info table, \"Tags\" row links",
+        notes: "Original tags, only present when a taxonomy changed them",
+    },
+    FieldDoc {
+        name: "average_session",
+        source: "page info table, \"Average session\" row text",
+        notes: "One of \"A few seconds\", \"A few minutes\", \"About a half-hour\", \"About an hour\", \"A few hours\", \"Days or more\"; empty when unset",
+    },
+    FieldDoc {
+        name: "languages",
+        source: "page info table, \"Languages\" row links",
+        notes: "Language names as shown by itch",
+    },
+    FieldDoc {
+        name: "inputs",
+        source: "page info table, \"Inputs\" row links",
+        notes: "e.g. \"Keyboard\", \"Mouse\", \"Xbox controller\"",
+    },
+    FieldDoc {
+        name: "links",
+        source: "page info table, \"Links\" row anchors",
+        notes: "Community and store links the developer added",
+    },
+    FieldDoc {
+        name: "links[].name",
+        source: "anchor text",
+        notes: "e.g. \"Discord\", \"Steam\"",
+    },
+    FieldDoc {
+        name: "links[].url",
+        source: "anchor href",
+        notes: "Absolute URL",
+    },
+    FieldDoc {
+        name: "cover_image",
+        source: "RSS <imageurl>, else the page's og:image meta tag",
+        notes: "Image URL; empty when the game has no cover",
+    },
+    FieldDoc {
+        name: "screenshots",
+        source: "div.screenshot_list anchors href",
+        notes: "Full-size screenshot URLs in page order",
+    },
+    FieldDoc {
+        name: "images",
+        source: "downloaded with --download-images",
+        notes: "Only present when images were downloaded; failed downloads are warnings and left out",
+    },
+    FieldDoc {
+        name: "images[].kind",
+        source: "--download-images",
+        notes: "\"cover\" or \"screenshot\"",
+    },
+    FieldDoc {
+        name: "images[].url",
+        source: "cover_image or screenshots entry",
+        notes: "URL the image was downloaded from",
+    },
+    FieldDoc {
+        name: "images[].path",
+        source: "--image-dir",
+        notes: "Local file named by the SHA-256 of its contents, shared by every record using the same image",
+    },
+    FieldDoc {
+        name: "images[].thumbnails",
+        source: "--thumbnails",
+        notes: "One per size, smallest first; only present when thumbnails were generated",
+    },
+    FieldDoc {
+        name: "images[].thumbnails[].size",
+        source: "--thumbnails",
+        notes: "Longest side in pixels",
+    },
+    FieldDoc {
+        name: "images[].thumbnails[].path",
+        source: "resized from images[].path",
+        notes: "JPEG for JPEG originals, PNG otherwise; the original's path when it's already no larger",
+    },
+    FieldDoc {
+        name: "status",
+        source: "page info table, \"Status\" row text",
+        notes: "One of \"Released\", \"In development\", \"Prototype\", \"On hold\", \"Canceled\"; empty when unset",
+    },
+    FieldDoc {
+        name: "platforms",
+        source: "page info table, \"Platforms\" row links",
+        notes: "e.g. \"Windows\", \"macOS\", \"Linux\", \"HTML5\", \"Android\"",
+    },
+    FieldDoc {
+        name: "accessibility",
+        source: "page info table, \"Accessibility\" row links",
+        notes: "e.g. \"Color-blind friendly\", \"Subtitles\"",
+    },
+    FieldDoc {
+        name: "comments",
+        source: "div.community_post",
+        notes: "Comments shown on the game page, only read with --comments; omitted when there are none",
+    },
+    FieldDoc {
+        name: "comments[].author",
+        source: ".post_author a",
+        notes: "Commenter display name",
+    },
+    FieldDoc {
+        name: "comments[].body",
+        source: ".post_body text",
+        notes: "Plain text of the comment",
+    },
+    FieldDoc {
+        name: "comments[].sentiment",
+        source: "derived from body",
+        notes: "-1 to 1, only with --comments and the sentiment feature",
+    },
+    FieldDoc {
+        name: "sentiment",
+        source: "derived from comments",
+        notes: "Average comment sentiment, -1 to 1, only with --comments and the sentiment feature",
+    },
+    FieldDoc {
+        name: "warnings",
+        source: "page info table rows and feed dates that couldn't be extracted",
+        notes: "One message per skipped or unknown row and per unrecognized date; omitted when nothing was lost",
+    },
+];
+
+const STEAM_FIELDS: &[FieldDoc] = &[
+    FieldDoc {
+        name: "title",
+        source: "appdetails name",
+        notes: "The app name, same as plain_title",
+    },
+    FieldDoc {
+        name: "plain_title",
+        source: "appdetails name",
+        notes: "The app name",
+    },
+    FieldDoc {
+        name: "link",
+        source: "steam_appid",
+        notes: "Store page URL, https://store.steampowered.com/app/<id>/",
+    },
+    FieldDoc {
+        name: "release_date",
+        source: "appdetails release_date.date",
+        notes: "As the store shows it, e.g. \"21 Aug, 2012\" or \"Coming soon\"",
+    },
+    FieldDoc {
+        name: "pub_date",
+        source: "derived from release_date",
+        notes: "UTC midnight of the release day as \"YYYY-MM-DD HH:MM:SS\"; empty when release_date isn't a day",
+    },
+    FieldDoc {
+        name: "price",
+        source: "appdetails price_overview.final_formatted",
+        notes: "\"Free\" for free games, empty when the app has no price yet",
+    },
+    FieldDoc {
+        name: "price_info",
+        source: "appdetails is_free and price_overview",
+        notes: "Parsed price; omitted when the app has no price yet",
+    },
+    FieldDoc {
+        name: "price_info.amount",
+        source: "appdetails price_overview.final",
+        notes: "Current price after discounts, in US dollars unless the store answered in another currency; 0 for free games",
+    },
+    FieldDoc {
+        name: "price_info.currency",
+        source: "appdetails price_overview.currency",
+        notes: "ISO 4217 code; empty for free games",
+    },
+    FieldDoc {
+        name: "price_info.free",
+        source: "appdetails is_free",
+        notes: "Free to play",
+    },
+    FieldDoc {
+        name: "price_info.pwyw",
+        source: "not in appdetails",
+        notes: "Always false on Steam",
+    },
+    FieldDoc {
+        name: "description",
+        source: "appdetails short_description",
+        notes: "Store page summary, may hold HTML entities",
+    },
+    FieldDoc {
+        name: "rating",
+        source: "appreviews query_summary",
+        notes: "Share of positive reviews scaled to 0-5; null when the app has no reviews",
+    },
+    FieldDoc {
+        name: "rating.count",
+        source: "appreviews query_summary.total_reviews",
+        notes: "Reviews across every language and purchase type",
+    },
+    FieldDoc {
+        name: "authors",
+        source: "appdetails developers",
+        notes: "Developers, each linked to a store search",
+    },
+    FieldDoc {
+        name: "genres",
+        source: "appdetails genres",
+        notes: "e.g. \"Action\", \"Indie\"",
+    },
+    FieldDoc {
+        name: "tags",
+        source: "appdetails categories",
+        notes: "Steam categories, e.g. \"Single-player\"; the slug is the category id",
+    },
+    FieldDoc {
+        name: "languages",
+        source: "appdetails supported_languages",
+        notes: "Language names, without the full audio marker",
+    },
+    FieldDoc {
+        name: "links",
+        source: "appdetails website",
+        notes: "The developer website, when there is one",
+    },
+    FieldDoc {
+        name: "status",
+        source: "appdetails release_date.coming_soon",
+        notes: "\"Released\" or \"Coming soon\"",
+    },
+    FieldDoc {
+        name: "platforms",
+        source: "appdetails platforms",
+        notes: "\"Windows\", \"macOS\" and \"Linux\"",
+    },
+    FieldDoc {
+        name: "cover_image",
+        source: "appdetails header_image",
+        notes: "Header capsule URL",
+    },
+    FieldDoc {
+        name: "screenshots",
+        source: "appdetails screenshots[].path_full",
+        notes: "Full-size screenshot URLs in store order",
+    },
+];
+
+#[derive(Serialize, Debug)]
+struct FieldRow {
+    field: String,
+    #[serde(rename = "type")]
+    kind: String,
+    presence: &'static str,
+    source: &'static str,
+    notes: &'static str,
+}
+
+pub fn run(args: ExplainSchemaArgs) -> Result<()> {
+    let schema = serde_json::to_value(schemars::schema_for!(ItchData))?;
+    let (docs, fallback) = match args.site {
+        Site::Itch | Site::ItchBrowse => (ITCH_FIELDS, "undocumented"),
+        Site::Steam => (STEAM_FIELDS, "not filled for steam"),
+        Site::Custom => (&[][..], "mapped in the --site-def [fields] table"),
+    };
+
+    let definitions = schema["definitions"]
+        .as_object()
+        .cloned()
+        .unwrap_or_default();
+    let mut rows = Vec::new();
+    collect_rows(&schema, &definitions, "", &mut rows);
+
+    // documented order first, then anything the docs haven't caught up with
+    rows.sort_by_key(|row| {
+        docs.iter()
+            .position(|doc| doc.name == row.field)
+            .unwrap_or(usize::MAX)
+    });
+    for row in &mut rows {
+        match docs.iter().find(|doc| doc.name == row.field) {
+            Some(doc) => {
+                row.source = doc.source;
+                row.notes = doc.notes;
+            }
+            None => row.source = fallback,
+        }
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
+
+    for row in rows {
+        println!("{} ({}, {})", row.field, row.kind, row.presence);
+        println!("    source: {}", row.source);
+        println!("    {}", row.notes);
+    }
+
+    Ok(())
+}
+
+fn collect_rows(
+    object: &Value,
+    definitions: &Map<String, Value>,
+    prefix: &str,
+    rows: &mut Vec<FieldRow>,
+) {
+    let required: Vec<&str> = object["required"]
+        .as_array()
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let Some(properties) = object["properties"].as_object() else {
+        return;
+    };
+
+    for (name, property) in properties {
+        let field = format!("{}{}", prefix, name);
+        let types = type_names(property);
+        let presence = if types.contains(&"null".to_string()) {
+            "nullable"
+        } else if required.contains(&name.as_str()) {
+            "always present"
+        } else {
+            "omitted when empty"
+        };
+
+        rows.push(FieldRow {
+            field: field.clone(),
+            kind: describe(property),
+            presence,
+            source: "",
+            notes: "",
+        });
+
+        if let Some(nested) = resolve(property, definitions) {
+            collect_rows(nested, definitions, &format!("{}.", field), rows);
+        }
+        if let Some(nested) = resolve(&property["items"], definitions) {
+            collect_rows(nested, definitions, &format!("{}[].", field), rows);
+        }
+    }
+}
+
+// optional structs are an anyOf of the reference and null
+fn resolve<'a>(property: &Value, definitions: &'a Map<String, Value>) -> Option<&'a Value> {
+    let reference = property["$ref"].as_str().or_else(|| {
+        property["anyOf"]
+            .as_array()?
+            .iter()
+            .find_map(|option| option["$ref"].as_str())
+    })?;
+    definitions.get(reference.rsplit('/').next()?)
+}
+
+fn type_names(property: &Value) -> Vec<String> {
+    match &property["type"] {
+        Value::String(name) => vec![name.clone()],
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn describe(property: &Value) -> String {
+    if let Some(reference) = property["$ref"].as_str() {
+        return format!(
+            "object {}",
+            reference.rsplit('/').next().unwrap_or(reference)
+        );
+    }
+
+    let kind = type_names(property)
+        .into_iter()
+        .find(|name| name != "null")
+        .unwrap_or_else(|| "any".to_string());
+    match kind.as_str() {
+        "array" => format!("array of {}", describe(&property["items"])),
+        _ => kind,
+    }
+}
@@ -0,0 +1,253 @@
+use crate::columnar;
+use crate::dataset::load_all;
+use crate::db;
+use crate::filters::{Filter, FilterArgs};
+use crate::parsers::dates::parse_feed_date;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, Result};
+use atom_syndication::{
+    CategoryBuilder, EntryBuilder, FeedBuilder, FixedDateTime, LinkBuilder, PersonBuilder, Text,
+};
+use chrono::Utc;
+use clap::{Args, ValueEnum};
+use rss::{ChannelBuilder, GuidBuilder, ItemBuilder};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::{fs, io, io::Write};
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    #[arg(required = true, value_name = "FILE PATH")]
+    pub inputs: Vec<PathBuf>,
+
+    #[arg(short, long, value_enum, value_name = "FORMAT")]
+    pub format: ExportFormat,
+
+    #[arg(short, long, value_name = "FILE PATH")]
+    pub outfile: Option<PathBuf>,
+
+    #[arg(long, value_name = "TEXT", default_value = "game-data-scraper export")]
+    pub title: String,
+
+    #[arg(long, value_name = "URL", default_value = "https://itch.io")]
+    pub link: String,
+
+    #[arg(long)]
+    pub datasette: bool,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+#[derive(Debug, ValueEnum, Clone)]
+pub enum ExportFormat {
+    Atom,
+    Duckdb,
+    Rss,
+    Json,
+    Ndjson,
+    Parquet,
+    Sqlite,
+}
+
+pub fn run(args: ExportArgs) -> Result<()> {
+    if args.datasette && !matches!(args.format, ExportFormat::Sqlite) {
+        return Err(anyhow!("--datasette only applies to sqlite exports"));
+    }
+
+    let filter = Filter::from(args.filter);
+    let records: Vec<ItchData> = load_all(&args.inputs)?
+        .into_iter()
+        .filter(|record| filter.matches(record))
+        .collect();
+
+    let output = match args.format {
+        ExportFormat::Atom => atom_feed(&args.title, &args.link, &records),
+        ExportFormat::Rss => rss_feed(&args.title, &args.link, &records),
+        ExportFormat::Json => serde_json::to_string_pretty(&records)?,
+        ExportFormat::Ndjson => ndjson(&records)?,
+        ExportFormat::Parquet => {
+            let path = args
+                .outfile
+                .ok_or_else(|| anyhow!("--outfile is required for parquet exports"))?;
+            return columnar::write_parquet(&path, &records);
+        }
+        ExportFormat::Duckdb => {
+            let path = args
+                .outfile
+                .ok_or_else(|| anyhow!("--outfile is required for duckdb exports"))?;
+            columnar::check_duckdb()?;
+            return columnar::write_duckdb(&path, &records);
+        }
+        ExportFormat::Sqlite => {
+            let path = args
+                .outfile
+                .ok_or_else(|| anyhow!("--outfile is required for sqlite exports"))?;
+            let mut conn = db::open(&path)?;
+            db::insert_records(&mut conn, &records)?;
+            if args.datasette {
+                db::add_datasette_tables(&mut conn)?;
+                write_datasette_metadata(&path, &args.title, &args.link)?;
+            }
+            return Ok(());
+        }
+    };
+
+    match args.outfile {
+        Some(file) => fs::write(file, output)?,
+        None => io::stdout().write_all(output.as_bytes())?,
+    }
+
+    Ok(())
+}
+
+fn ndjson(records: &[ItchData]) -> Result<String> {
+    let mut output = String::new();
+    for record in records {
+        output.push_str(&serde_json::to_string(record)?);
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+// datasette looks metadata up by database name, the file stem
+fn write_datasette_metadata(path: &Path, title: &str, link: &str) -> Result<()> {
+    let database = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let metadata = json!({
+        "title": title,
+        "source": "itch.io",
+        "source_url": link,
+        "databases": {
+            database: {
+                "tables": {
+                    "game_details": {
+                        "label_column": "title",
+                        "sort_desc": "update_date",
+                        "fts_table": "game_details_fts",
+                        "facets": [
+                            "status",
+                            "price",
+                            "average_session",
+                            { "array": "genres" },
+                            { "array": "tags" },
+                            { "array": "platforms" },
+                            { "array": "made_with" }
+                        ]
+                    },
+                    "games": { "hidden": true }
+                }
+            }
+        }
+    });
+
+    let metadata_path = path.with_file_name("metadata.json");
+    fs::write(&metadata_path, serde_json::to_string_pretty(&metadata)?)?;
+    eprintln!(
+        "Wrote {:?}, browse it with: datasette serve {:?} --metadata {:?}",
+        metadata_path, path, metadata_path
+    );
+
+    Ok(())
+}
+
+fn feed_date(s: &str) -> Option<FixedDateTime> {
+    parse_feed_date(s).map(|date| date.fixed_offset())
+}
+
+fn atom_feed(title: &str, link: &str, records: &[ItchData]) -> String {
+    let entries: Vec<_> = records
+        .iter()
+        .map(|record| {
+            let published = feed_date(&record.pub_date);
+            let updated = feed_date(&record.update_date)
+                .or(published)
+                .unwrap_or_else(|| Utc::now().fixed_offset());
+
+            EntryBuilder::default()
+                .id(&record.link)
+                .title(record.plain_title.as_str())
+                .updated(updated)
+                .published(published)
+                .link(LinkBuilder::default().href(&record.link).build())
+                .summary(Some(Text::html(record.description.as_str())))
+                .categories(
+                    record
+                        .tags
+                        .iter()
+                        .map(|tag| CategoryBuilder::default().term(tag.name.as_str()).build())
+                        .collect::<Vec<_>>(),
+                )
+                .authors(
+                    record
+                        .authors
+                        .iter()
+                        .map(|author| {
+                            PersonBuilder::default()
+                                .name(author.name.as_str())
+                                .uri(Some(author.url.clone()).filter(|url| !url.is_empty()))
+                                .build()
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .build()
+        })
+        .collect();
+
+    let updated = entries
+        .iter()
+        .map(|entry| entry.updated)
+        .max()
+        .unwrap_or_else(|| Utc::now().fixed_offset());
+
+    FeedBuilder::default()
+        .id(link)
+        .title(title)
+        .updated(updated)
+        .link(LinkBuilder::default().href(link).build())
+        .entries(entries)
+        .build()
+        .to_string()
+}
+
+fn rss_feed(title: &str, link: &str, records: &[ItchData]) -> String {
+    let items: Vec<_> = records
+        .iter()
+        .map(|record| {
+            ItemBuilder::default()
+                .title(record.plain_title.clone())
+                .link(record.link.clone())
+                .guid(
+                    GuidBuilder::default()
+                        .value(&record.link)
+                        .permalink(true)
+                        .build(),
+                )
+                .pub_date(feed_date(&record.pub_date).map(|date| date.to_rfc2822()))
+                .description(record.description.clone())
+                .categories(
+                    record
+                        .tags
+                        .iter()
+                        .map(|tag| {
+                            rss::CategoryBuilder::default()
+                                .name(tag.name.as_str())
+                                .build()
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .build()
+        })
+        .collect();
+
+    ChannelBuilder::default()
+        .title(title)
+        .link(link)
+        .description(title)
+        .items(items)
+        .build()
+        .to_string()
+}
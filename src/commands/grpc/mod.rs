@@ -0,0 +1,144 @@
+use crate::db;
+use crate::incremental::IncrementalState;
+use crate::scrapers::progress::SilentReporter;
+use crate::{scrape, ScrapeArgs, Site};
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use proto::scraper_service_server::{ScraperService, ScraperServiceServer};
+use proto::{GameRecord, GetGameRequest, ScrapeRequest, ScrapeResponse, StreamNewGamesRequest};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+
+pub mod proto;
+
+#[derive(Args, Debug)]
+pub struct GrpcArgs {
+    #[arg(long, value_name = "FILE PATH")]
+    pub db: Option<PathBuf>,
+
+    #[arg(long, value_name = "ADDRESS", default_value = "127.0.0.1")]
+    pub host: String,
+
+    #[arg(long, value_name = "INTEGER", default_value_t = 50051)]
+    pub port: u16,
+}
+
+struct ScraperServer {
+    db: Option<PathBuf>,
+}
+
+fn scrape_args(site: &str, url: String, page_limit: Option<i32>) -> Option<ScrapeArgs> {
+    Some(ScrapeArgs {
+        site: Site::from_str(site, true).ok()?,
+        url,
+        page_limit,
+        ..ScrapeArgs::default()
+    })
+}
+
+fn unknown_site(site: &str) -> Status {
+    Status::invalid_argument(format!("Unknown site {:?}", site))
+}
+
+#[tonic::async_trait]
+impl ScraperService for ScraperServer {
+    async fn scrape(
+        &self,
+        request: Request<ScrapeRequest>,
+    ) -> Result<Response<ScrapeResponse>, Status> {
+        let request = request.into_inner();
+        let mut args = scrape_args(&request.site, request.url, request.page_limit)
+            .ok_or_else(|| unknown_site(&request.site))?;
+        args.max_retries = request.max_retries;
+
+        let games = scrape(&args, &SilentReporter)
+            .await
+            .map_err(|err| Status::internal(format!("{:?}", err)))?;
+
+        Ok(Response::new(ScrapeResponse {
+            games: games.into_iter().map(GameRecord::from).collect(),
+        }))
+    }
+
+    async fn get_game(
+        &self,
+        request: Request<GetGameRequest>,
+    ) -> Result<Response<GameRecord>, Status> {
+        let Some(db_path) = self.db.clone() else {
+            return Err(Status::failed_precondition(
+                "Server was started without --db",
+            ));
+        };
+        let link = request.into_inner().link;
+
+        let game = tokio::task::spawn_blocking(move || {
+            let conn = db::open(&db_path)?;
+            db::get_game(&conn, &link)
+        })
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?
+        .map_err(|err| Status::internal(format!("{:?}", err)))?;
+
+        match game {
+            Some(game) => Ok(Response::new(game.into())),
+            None => Err(Status::not_found("No game with that link")),
+        }
+    }
+
+    type StreamNewGamesStream = ReceiverStream<Result<GameRecord, Status>>;
+
+    async fn stream_new_games(
+        &self,
+        request: Request<StreamNewGamesRequest>,
+    ) -> Result<Response<Self::StreamNewGamesStream>, Status> {
+        let request = request.into_inner();
+        let args = scrape_args(&request.site, request.url, request.page_limit)
+            .ok_or_else(|| unknown_site(&request.site))?;
+        let interval = Duration::from_secs(request.interval_seconds.max(1));
+        let (tx, rx) = mpsc::channel(64);
+
+        tokio::spawn(async move {
+            let mut state = IncrementalState::load(None).expect("in-memory state never fails");
+
+            loop {
+                match scrape(&args, &SilentReporter).await {
+                    Ok(records) => {
+                        let (changed, _) = state.take_changed(&records);
+                        for record in changed {
+                            if tx.send(Ok(record.clone().into())).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        let _ = tx.send(Err(Status::internal(format!("{:?}", err)))).await;
+                        return;
+                    }
+                }
+
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {}
+                    _ = tx.closed() => return,
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+pub async fn run(args: GrpcArgs) -> Result<()> {
+    let addr = format!("{}:{}", args.host, args.port).parse()?;
+    eprintln!("Serving gRPC on {}", addr);
+
+    Server::builder()
+        .add_service(ScraperServiceServer::new(ScraperServer { db: args.db }))
+        .serve(addr)
+        .await?;
+
+    Ok(())
+}
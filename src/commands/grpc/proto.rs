@@ -0,0 +1,197 @@
+use crate::parsers::itch_game_info_parser::{ItchRating, Link as ItchLink};
+use crate::parsers::price::Price;
+use crate::scrapers::itch_rss_scraper::ItchData;
+
+include!(concat!(
+    env!("OUT_DIR"),
+    "/game_data_scraper.v1.ScraperService.rs"
+));
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ScrapeRequest {
+    #[prost(string, tag = "1")]
+    pub site: String,
+    #[prost(string, tag = "2")]
+    pub url: String,
+    #[prost(int32, optional, tag = "3")]
+    pub page_limit: Option<i32>,
+    #[prost(uint32, optional, tag = "4")]
+    pub max_retries: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ScrapeResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub games: Vec<GameRecord>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetGameRequest {
+    #[prost(string, tag = "1")]
+    pub link: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StreamNewGamesRequest {
+    #[prost(string, tag = "1")]
+    pub site: String,
+    #[prost(string, tag = "2")]
+    pub url: String,
+    #[prost(int32, optional, tag = "3")]
+    pub page_limit: Option<i32>,
+    #[prost(uint64, tag = "4")]
+    pub interval_seconds: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Rating {
+    #[prost(float, tag = "1")]
+    pub score: f32,
+    #[prost(int32, tag = "2")]
+    pub count: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Link {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub url: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PriceInfo {
+    #[prost(double, optional, tag = "1")]
+    pub amount: Option<f64>,
+    #[prost(string, tag = "2")]
+    pub currency: String,
+    #[prost(bool, tag = "3")]
+    pub free: bool,
+    #[prost(bool, tag = "4")]
+    pub pwyw: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GameRecord {
+    #[prost(string, tag = "1")]
+    pub title: String,
+    #[prost(string, tag = "2")]
+    pub plain_title: String,
+    #[prost(string, tag = "3")]
+    pub link: String,
+    #[prost(string, tag = "4")]
+    pub create_date: String,
+    #[prost(string, tag = "5")]
+    pub update_date: String,
+    #[prost(string, tag = "6")]
+    pub release_date: String,
+    #[prost(string, tag = "7")]
+    pub pub_date: String,
+    #[prost(string, tag = "8")]
+    pub price: String,
+    #[prost(string, tag = "9")]
+    pub description: String,
+    #[prost(message, optional, tag = "10")]
+    pub rating: Option<Rating>,
+    #[prost(string, repeated, tag = "11")]
+    pub authors: Vec<String>,
+    #[prost(string, repeated, tag = "12")]
+    pub genres: Vec<String>,
+    #[prost(string, repeated, tag = "13")]
+    pub made_with: Vec<String>,
+    #[prost(string, repeated, tag = "14")]
+    pub tags: Vec<String>,
+    #[prost(string, tag = "15")]
+    pub average_session: String,
+    #[prost(string, repeated, tag = "16")]
+    pub languages: Vec<String>,
+    #[prost(string, repeated, tag = "17")]
+    pub inputs: Vec<String>,
+    #[prost(message, repeated, tag = "18")]
+    pub links: Vec<Link>,
+    #[prost(string, tag = "19")]
+    pub status: String,
+    #[prost(string, repeated, tag = "20")]
+    pub platforms: Vec<String>,
+    #[prost(string, repeated, tag = "21")]
+    pub accessibility: Vec<String>,
+    #[prost(string, tag = "22")]
+    pub updated_date: String,
+    #[prost(string, tag = "23")]
+    pub published_date: String,
+    #[prost(string, repeated, tag = "24")]
+    pub author_urls: Vec<String>,
+    #[prost(string, repeated, tag = "25")]
+    pub tag_slugs: Vec<String>,
+    #[prost(string, repeated, tag = "26")]
+    pub genre_urls: Vec<String>,
+    #[prost(message, optional, tag = "30")]
+    pub price_info: Option<PriceInfo>,
+}
+
+impl From<ItchRating> for Rating {
+    fn from(rating: ItchRating) -> Rating {
+        Rating {
+            score: rating.score,
+            count: rating.count,
+        }
+    }
+}
+
+impl From<ItchLink> for Link {
+    fn from(link: ItchLink) -> Link {
+        Link {
+            name: link.name,
+            url: link.url,
+        }
+    }
+}
+
+impl From<Price> for PriceInfo {
+    fn from(price: Price) -> PriceInfo {
+        PriceInfo {
+            amount: price.amount,
+            currency: price.currency,
+            free: price.free,
+            pwyw: price.pwyw,
+        }
+    }
+}
+
+impl From<ItchData> for GameRecord {
+    fn from(data: ItchData) -> GameRecord {
+        GameRecord {
+            title: data.title,
+            plain_title: data.plain_title,
+            link: data.link,
+            create_date: data.create_date,
+            update_date: data.update_date,
+            release_date: data.release_date,
+            pub_date: data.pub_date,
+            price: data.price,
+            price_info: data.price_info.map(PriceInfo::from),
+            description: data.description,
+            rating: data.rating.map(Rating::from),
+            authors: data
+                .authors
+                .iter()
+                .map(|author| author.name.clone())
+                .collect(),
+            genres: data.genres.iter().map(|genre| genre.name.clone()).collect(),
+            genre_urls: data.genres.iter().map(|genre| genre.url.clone()).collect(),
+            made_with: data.made_with,
+            tags: data.tags.iter().map(|tag| tag.name.clone()).collect(),
+            tag_slugs: data.tags.iter().map(|tag| tag.slug.clone()).collect(),
+            average_session: data.average_session,
+            languages: data.languages,
+            inputs: data.inputs,
+            links: data.links.into_iter().map(Link::from).collect(),
+            status: data.status,
+            platforms: data.platforms,
+            accessibility: data.accessibility,
+            author_urls: data.authors.into_iter().map(|author| author.url).collect(),
+            updated_date: data.updated_date,
+            published_date: data.published_date,
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use crate::history::{self, Observation};
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+use serde_json::{json, Value};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    #[command(subcommand)]
+    pub command: HistoryCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HistoryCommand {
+    /// Show how fields of one game changed across the scrapes recorded with --history
+    Query(QueryArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct QueryArgs {
+    #[arg(value_name = "FILE PATH")]
+    pub file: PathBuf,
+
+    // a link, or a title when no link matches
+    #[arg(value_name = "GAME")]
+    pub game: String,
+
+    #[arg(
+        long = "field",
+        value_name = "FIELD",
+        default_values = ["rating.score", "rating.count", "price"]
+    )]
+    pub fields: Vec<String>,
+
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run(args: HistoryArgs) -> Result<()> {
+    match args.command {
+        HistoryCommand::Query(args) => query(args),
+    }
+}
+
+fn query(args: QueryArgs) -> Result<()> {
+    if !args.file.exists() {
+        return Err(anyhow!("No history file at {:?}", args.file));
+    }
+    let conn = history::open(&args.file)?;
+    let observations = history::observations(&conn, &args.game)?;
+    if observations.is_empty() {
+        return Err(anyhow!(
+            "No observations of {:?} in {:?}",
+            args.game,
+            args.file
+        ));
+    }
+
+    let mut links: Vec<&str> = observations.iter().map(|o| o.link.as_str()).collect();
+    links.sort_unstable();
+    links.dedup();
+    if links.len() > 1 {
+        return Err(anyhow!(
+            "{:?} matches {} games, pass one of their links instead: {}",
+            args.game,
+            links.len(),
+            links.join(", ")
+        ));
+    }
+
+    let mut stdout = io::stdout().lock();
+    if args.json {
+        for observation in &observations {
+            let mut line = json!({ "scraped_at": observation.scraped_at });
+            for field in &args.fields {
+                line[field] = field_value(observation, field).clone();
+            }
+            writeln!(stdout, "{}", line)?;
+        }
+        return Ok(());
+    }
+
+    writeln!(stdout, "scraped_at\t{}", args.fields.join("\t"))?;
+    for observation in &observations {
+        let values: Vec<String> = args
+            .fields
+            .iter()
+            .map(|field| match field_value(observation, field) {
+                Value::String(value) => value.clone(),
+                Value::Null => String::new(),
+                value => value.to_string(),
+            })
+            .collect();
+        writeln!(stdout, "{}\t{}", observation.scraped_at, values.join("\t"))?;
+    }
+
+    Ok(())
+}
+
+// dotted paths into the stored record, e.g. rating.score or authors.0.name
+fn field_value<'a>(observation: &'a Observation, field: &str) -> &'a Value {
+    let pointer = format!("/{}", field.replace('.', "/"));
+    observation.record.pointer(&pointer).unwrap_or(&Value::Null)
+}
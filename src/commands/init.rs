@@ -0,0 +1,232 @@
+use crate::config::DEFAULT_CONFIG_FILE;
+use crate::Site;
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, ValueEnum};
+use cron::Schedule;
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Select};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use toml::{Table, Value};
+
+const OUTPUT_FORMATS: [(&str, &str); 2] = [
+    (
+        "ndjson",
+        "one JSON record per line, appended as new games show up",
+    ),
+    ("json", "a single JSON array, rewritten on every run"),
+];
+
+const SCHEDULES: [(&str, &str); 4] = [
+    ("0 0 * * * *", "every hour"),
+    ("0 0 6 * * *", "every day at 06:00 UTC"),
+    ("0 0 6 * * Mon", "every Monday at 06:00 UTC"),
+    ("", "custom cron expression"),
+];
+
+#[derive(Args, Debug)]
+pub struct InitArgs {
+    #[arg(short, long, value_name = "FILE PATH", default_value = DEFAULT_CONFIG_FILE)]
+    pub outfile: PathBuf,
+
+    #[arg(long)]
+    pub force: bool,
+}
+
+pub fn run(args: InitArgs) -> Result<()> {
+    let theme = ColorfulTheme::default();
+
+    if args.outfile.exists() && !args.force {
+        let overwrite = Confirm::with_theme(&theme)
+            .with_prompt(format!("{:?} already exists, overwrite it?", args.outfile))
+            .default(false)
+            .interact()?;
+        if !overwrite {
+            return Err(anyhow!("Not overwriting {:?}", args.outfile));
+        }
+    }
+
+    // custom sites need a hand-written --site-def, which the wizard can't produce
+    let sites: Vec<&Site> = Site::value_variants()
+        .iter()
+        .filter(|site| !matches!(site, Site::Custom))
+        .collect();
+    let site = &sites[Select::with_theme(&theme)
+        .with_prompt("Which site do you want to scrape?")
+        .items(&sites.iter().map(|site| site.name()).collect::<Vec<_>>())
+        .default(0)
+        .interact()?];
+
+    let (prompt, default_url) = match site {
+        Site::ItchBrowse => ("Browse URL", "https://itch.io/games/newest"),
+        Site::Steam => (
+            "App list URL",
+            "https://store.steampowered.com/api/featuredcategories",
+        ),
+        _ => ("Feed URL", "https://itch.io/games/newest.xml"),
+    };
+    let url: String = Input::with_theme(&theme)
+        .with_prompt(prompt)
+        .default(default_url.to_string())
+        .validate_with(|url: &String| {
+            reqwest::Url::parse(url)
+                .map(|_| ())
+                .map_err(|err| format!("Not a valid URL: {}", err))
+        })
+        .interact_text()?;
+
+    let page_limit: i32 = Input::with_theme(&theme)
+        .with_prompt("How many feed pages should each run read?")
+        .default(5)
+        .interact_text()?;
+
+    let format = OUTPUT_FORMATS[Select::with_theme(&theme)
+        .with_prompt("Output format")
+        .items(&described(&OUTPUT_FORMATS))
+        .default(0)
+        .interact()?]
+    .0;
+    let outfile: String = Input::with_theme(&theme)
+        .with_prompt("Output file")
+        .default(format!(
+            "games.{}",
+            if format == "json" { "json" } else { "jsonl" }
+        ))
+        .interact_text()?;
+
+    let mut schedule = SCHEDULES[Select::with_theme(&theme)
+        .with_prompt("How often should it run?")
+        .items(&described(&SCHEDULES))
+        .default(1)
+        .interact()?]
+    .0
+    .to_string();
+    if schedule.is_empty() {
+        schedule = Input::with_theme(&theme)
+            .with_prompt("Cron expression (sec min hour day month weekday)")
+            .validate_with(|expr: &String| {
+                Schedule::from_str(expr)
+                    .map(|_| ())
+                    .map_err(|err| err.to_string())
+            })
+            .interact_text()?;
+    }
+
+    let mut job = Table::new();
+    job.insert("name".into(), Value::from(job_name(&url)));
+    job.insert("schedule".into(), Value::from(schedule));
+    job.insert("site".into(), Value::from(site.name()));
+    job.insert("url".into(), Value::from(url));
+    job.insert("outfile".into(), Value::from(outfile));
+    job.insert("page_limit".into(), Value::from(page_limit));
+    job.insert("quiet".into(), Value::from(true));
+
+    let mut config = Table::new();
+    config.insert("jobs".into(), Value::Array(vec![Value::Table(job)]));
+    ask_sinks(&theme, &mut config)?;
+
+    fs::write(&args.outfile, toml::to_string_pretty(&config)?)
+        .with_context(|| format!("Failed to write {:?}", args.outfile))?;
+
+    eprintln!(
+        "Wrote {:?}, start scraping with: game-data-scraper watch --config {:?}",
+        args.outfile, args.outfile
+    );
+
+    Ok(())
+}
+
+fn ask_sinks(theme: &ColorfulTheme, config: &mut Table) -> Result<()> {
+    let sinks = ["Discord webhook", "Slack webhook", "Email"];
+    let chosen = MultiSelect::with_theme(theme)
+        .with_prompt("Where should new games be announced? (space to select, enter to confirm)")
+        .items(&sinks)
+        .interact()?;
+
+    for sink in chosen {
+        match sink {
+            0 => {
+                let webhook: String = Input::with_theme(theme)
+                    .with_prompt("Discord webhook URL")
+                    .interact_text()?;
+                let mut rule = Table::new();
+                rule.insert("name".into(), Value::from("all new games"));
+                rule.insert("discord_webhook".into(), Value::from(webhook));
+                config.insert(
+                    "notifications".into(),
+                    Value::Array(vec![Value::Table(rule)]),
+                );
+            }
+            1 => {
+                let webhook: String = Input::with_theme(theme)
+                    .with_prompt("Slack webhook URL")
+                    .interact_text()?;
+                let mut slack = Table::new();
+                slack.insert("webhook".into(), Value::from(webhook));
+                slack.insert("run_summaries".into(), Value::from(true));
+                slack.insert("scrape_failures".into(), Value::from(true));
+                slack.insert("price_drops".into(), Value::from(true));
+                config.insert("slack".into(), Value::Table(slack));
+            }
+            _ => {
+                let smtp_host: String = Input::with_theme(theme)
+                    .with_prompt("SMTP host")
+                    .interact_text()?;
+                let from: String = Input::with_theme(theme)
+                    .with_prompt("Send from address")
+                    .interact_text()?;
+                let to: String = Input::with_theme(theme)
+                    .with_prompt("Send to addresses (comma separated)")
+                    .interact_text()?;
+                let mut email = Table::new();
+                email.insert("smtp_host".into(), Value::from(smtp_host));
+                email.insert("from".into(), Value::from(from));
+                email.insert(
+                    "to".into(),
+                    Value::Array(
+                        to.split(',')
+                            .map(str::trim)
+                            .filter(|address| !address.is_empty())
+                            .map(Value::from)
+                            .collect(),
+                    ),
+                );
+                email.insert("run_summaries".into(), Value::from(true));
+                email.insert("scrape_failures".into(), Value::from(true));
+                config.insert("email".into(), Value::Table(email));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn described(choices: &[(&str, &str)]) -> Vec<String> {
+    choices
+        .iter()
+        .map(|(value, description)| match value {
+            &"" => description.to_string(),
+            value => format!("{} ({})", value, description),
+        })
+        .collect()
+}
+
+// e.g. https://itch.io/games/newest/tag-horror.xml becomes "newest-tag-horror"
+fn job_name(url: &str) -> String {
+    let path = reqwest::Url::parse(url)
+        .map(|url| url.path().to_string())
+        .unwrap_or_default();
+
+    let name = path
+        .trim_end_matches(".xml")
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != "games")
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if name.is_empty() {
+        "default".to_string()
+    } else {
+        name
+    }
+}
@@ -0,0 +1,231 @@
+use crate::db::{self, GameQuery};
+use crate::scrapers::progress::SilentReporter;
+use crate::{scrape, ScrapeArgs, Site};
+use anyhow::{anyhow, Result};
+use clap::Args;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use tokio::io::{self, AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+#[derive(Args, Debug)]
+pub struct McpArgs {
+    #[arg(long, value_name = "FILE PATH")]
+    pub db: PathBuf,
+}
+
+#[derive(Deserialize, Debug)]
+struct RpcRequest {
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize, Debug)]
+struct ScrapeFeedParams {
+    url: String,
+    #[serde(default = "default_scrape_pages")]
+    page_limit: i32,
+}
+
+fn default_scrape_pages() -> i32 {
+    1
+}
+
+#[derive(Deserialize, Debug)]
+struct GetGameParams {
+    link: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct SearchParams {
+    query: Option<String>,
+    tags: Vec<String>,
+    platforms: Vec<String>,
+    max_price: Option<f64>,
+    limit: Option<u32>,
+}
+
+pub async fn run(args: McpArgs) -> Result<()> {
+    db::open(&args.db)?;
+
+    let mut lines = BufReader::new(io::stdin()).lines();
+    let mut stdout = io::stdout();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<RpcRequest>(&line) {
+            Ok(request) => handle(&args, request).await,
+            Err(err) => Some(json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": -32700, "message": err.to_string() },
+            })),
+        };
+
+        if let Some(response) = response {
+            stdout.write_all(response.to_string().as_bytes()).await?;
+            stdout.write_all(b"\n").await?;
+            stdout.flush().await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle(args: &McpArgs, request: RpcRequest) -> Option<Value> {
+    // notifications carry no id and expect no response
+    let id = request.id?;
+
+    let result = match request.method.as_str() {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => Ok(call_tool(args, request.params).await),
+        method => Err(json!({ "code": -32601, "message": format!("Unknown method {}", method) })),
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    })
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "scrape_itch_feed",
+            "description": "Scrape an itch.io RSS feed, store the results in the local dataset and return them",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "description": "itch.io RSS feed URL" },
+                    "page_limit": { "type": "integer", "description": "Number of feed pages to scrape", "default": 1 },
+                },
+                "required": ["url"],
+            },
+        },
+        {
+            "name": "get_game",
+            "description": "Look up a single scraped game by its itch.io page URL",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "link": { "type": "string", "description": "itch.io game page URL" },
+                },
+                "required": ["link"],
+            },
+        },
+        {
+            "name": "search_scraped_data",
+            "description": "Search scraped games by text, tags, platforms and price",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Text to match in titles and descriptions" },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "platforms": { "type": "array", "items": { "type": "string" } },
+                    "max_price": { "type": "number" },
+                    "limit": { "type": "integer", "default": 20 },
+                },
+            },
+        },
+    ])
+}
+
+async fn call_tool(args: &McpArgs, params: Value) -> Value {
+    let name = params["name"].as_str().unwrap_or_default().to_string();
+    let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+
+    let result = match name.as_str() {
+        "scrape_itch_feed" => scrape_feed(args, arguments).await,
+        "get_game" => get_game(args, arguments).await,
+        "search_scraped_data" => search(args, arguments).await,
+        _ => Err(anyhow!("Unknown tool {:?}", name)),
+    };
+
+    match result {
+        Ok(value) => json!({
+            "content": [{ "type": "text", "text": value.to_string() }],
+            "isError": false,
+        }),
+        Err(err) => json!({
+            "content": [{ "type": "text", "text": format!("{:#}", err) }],
+            "isError": true,
+        }),
+    }
+}
+
+async fn scrape_feed(args: &McpArgs, arguments: Value) -> Result<Value> {
+    let params: ScrapeFeedParams = serde_json::from_value(arguments)?;
+    let scrape_args = ScrapeArgs {
+        site: Site::Itch,
+        url: params.url,
+        page_limit: Some(params.page_limit),
+        ..ScrapeArgs::default()
+    };
+
+    let records = scrape(&scrape_args, &SilentReporter).await?;
+
+    let db_path = args.db.clone();
+    let stored = records.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut conn = db::open(&db_path)?;
+        db::insert_records(&mut conn, &stored)
+    })
+    .await??;
+
+    Ok(serde_json::to_value(records)?)
+}
+
+async fn get_game(args: &McpArgs, arguments: Value) -> Result<Value> {
+    let params: GetGameParams = serde_json::from_value(arguments)?;
+    let db_path = args.db.clone();
+
+    let game = tokio::task::spawn_blocking(move || {
+        let conn = db::open(&db_path)?;
+        db::get_game(&conn, &params.link)
+    })
+    .await??;
+
+    match game {
+        Some(game) => Ok(serde_json::to_value(game)?),
+        None => Err(anyhow!("No scraped game with that link")),
+    }
+}
+
+async fn search(args: &McpArgs, arguments: Value) -> Result<Value> {
+    let params: SearchParams = serde_json::from_value(arguments)?;
+    let query = GameQuery {
+        tags: params.tags,
+        platforms: params.platforms,
+        max_price: params.max_price,
+        search: params.query,
+        descending: true,
+        limit: params.limit.unwrap_or(20).min(200),
+        ..GameQuery::default()
+    };
+    let db_path = args.db.clone();
+
+    let (total, games) = tokio::task::spawn_blocking(move || {
+        let conn = db::open(&db_path)?;
+        db::query_games(&conn, &query)
+    })
+    .await??;
+
+    Ok(json!({ "total": total, "games": games }))
+}
@@ -0,0 +1,295 @@
+use crate::dataset::{load_records, write_records};
+use crate::parsers::dates::parse_feed_date;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::Args;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct MergeArgs {
+    #[arg(required = true, value_name = "FILE PATH")]
+    pub inputs: Vec<PathBuf>,
+
+    #[arg(short, long, value_name = "FILE PATH")]
+    pub outfile: Option<PathBuf>,
+
+    #[arg(long)]
+    pub fuzzy: bool,
+
+    #[arg(
+        long,
+        value_name = "SIMILARITY",
+        default_value_t = 0.9,
+        requires = "fuzzy"
+    )]
+    pub fuzzy_threshold: f64,
+}
+
+// records don't keep the feed's guid, so they're deduplicated by link instead; unlike the guid
+// it's the same for a game scraped from the feed, a browse listing or its own page
+pub fn run(args: MergeArgs) -> Result<()> {
+    let mut by_link: HashMap<String, usize> = HashMap::new();
+    let mut merged: Vec<ItchData> = Vec::new();
+
+    for path in &args.inputs {
+        for record in load_records(path)? {
+            match by_link.get(&record.link) {
+                Some(&i) => merged[i] = newest(merged[i].clone(), record),
+                None => {
+                    by_link.insert(record.link.clone(), merged.len());
+                    merged.push(record);
+                }
+            }
+        }
+    }
+
+    if args.fuzzy {
+        merged = fuzzy_merge(merged, args.fuzzy_threshold);
+    }
+
+    eprintln!(
+        "Merged {} input files into {} records",
+        args.inputs.len(),
+        merged.len()
+    );
+    write_records(args.outfile.as_deref(), &merged)
+}
+
+fn updated_at(record: &ItchData) -> Option<DateTime<Utc>> {
+    [&record.update_date, &record.pub_date, &record.create_date]
+        .into_iter()
+        .find_map(|date| parse_feed_date(date))
+}
+
+pub fn newest(a: ItchData, b: ItchData) -> ItchData {
+    if updated_at(&b) >= updated_at(&a) {
+        absorb(b, a)
+    } else {
+        absorb(a, b)
+    }
+}
+
+// keep the newer record, but don't lose anything the older one knew about
+fn absorb(mut newer: ItchData, older: ItchData) -> ItchData {
+    for (field, old) in [
+        (&mut newer.platforms, older.platforms),
+        (&mut newer.languages, older.languages),
+    ] {
+        for value in old {
+            if !field.iter().any(|v| v.eq_ignore_ascii_case(&value)) {
+                field.push(value);
+            }
+        }
+    }
+    for genre in older.genres {
+        if !newer
+            .genres
+            .iter()
+            .any(|known| known.name.eq_ignore_ascii_case(&genre.name))
+        {
+            newer.genres.push(genre);
+        }
+    }
+    for tag in older.tags {
+        if !newer
+            .tags
+            .iter()
+            .any(|known| known.name.eq_ignore_ascii_case(&tag.name))
+        {
+            newer.tags.push(tag);
+        }
+    }
+    for author in older.authors {
+        match newer
+            .authors
+            .iter_mut()
+            .find(|known| known.is_same(&author))
+        {
+            Some(known) if known.url.is_empty() => known.url = author.url,
+            Some(_) => {}
+            None => newer.authors.push(author),
+        }
+    }
+
+    if newer.description.is_empty() {
+        newer.description = older.description;
+    }
+    if newer.release_date.is_empty() {
+        newer.release_date = older.release_date;
+    }
+    if newer.updated_date.is_empty() {
+        newer.updated_date = older.updated_date;
+    }
+    if newer.published_date.is_empty() {
+        newer.published_date = older.published_date;
+    }
+
+    newer
+}
+
+pub fn normalized_title(record: &ItchData) -> String {
+    let title = if record.plain_title.is_empty() {
+        &record.title
+    } else {
+        &record.plain_title
+    };
+
+    title
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+pub fn shares_author(a: &ItchData, b: &ItchData) -> bool {
+    a.authors.is_empty()
+        || b.authors.is_empty()
+        || a.authors
+            .iter()
+            .any(|x| b.authors.iter().any(|y| x.is_same(y)))
+}
+
+fn same_game(a: &ItchData, a_title: &str, b: &ItchData, b_title: &str, threshold: f64) -> bool {
+    shares_author(a, b) && strsim::normalized_levenshtein(a_title, b_title) >= threshold
+}
+
+fn fuzzy_merge(records: Vec<ItchData>, threshold: f64) -> Vec<ItchData> {
+    // similarity tops out at 1, nothing could ever match
+    if threshold > 1.0 {
+        return records;
+    }
+
+    let mut merged: Vec<(String, ItchData)> = Vec::new();
+    // merged records by normalized title and by its length; a record is only compared against
+    // the same title and the titles close enough in length to reach the threshold
+    let mut by_title: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut by_length: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+
+    for record in records {
+        let title = normalized_title(&record);
+        let is_match = |&i: &usize| {
+            let (t, r) = &merged[i];
+            same_game(r, t, &record, &title, threshold)
+        };
+        let existing = by_title
+            .get(&title)
+            .and_then(|candidates| candidates.iter().copied().find(is_match))
+            .or_else(|| {
+                by_length
+                    .range(comparable_lengths(title.chars().count(), threshold))
+                    .flat_map(|(_, candidates)| candidates.iter().copied())
+                    .filter(is_match)
+                    .min()
+            });
+
+        let i = match existing {
+            Some(i) => {
+                let (title, current) = std::mem::take(&mut merged[i]);
+                remove_index(&mut by_title, &mut by_length, &title, i);
+                let combined = newest(current, record);
+                merged[i] = (normalized_title(&combined), combined);
+                i
+            }
+            None => {
+                merged.push((title, record));
+                merged.len() - 1
+            }
+        };
+        let title = &merged[i].0;
+        by_title.entry(title.clone()).or_default().push(i);
+        by_length.entry(title.chars().count()).or_default().push(i);
+    }
+
+    merged.into_iter().map(|(_, record)| record).collect()
+}
+
+// the edit distance between two titles is at least the difference in their lengths, so a title
+// this much shorter or longer can't be similar enough
+fn comparable_lengths(length: usize, threshold: f64) -> RangeInclusive<usize> {
+    if threshold <= 0.0 {
+        return 0..=usize::MAX;
+    }
+
+    let length = length as f64;
+    let shortest = (length * threshold - 1e-9).ceil().max(0.0) as usize;
+    let longest = (length / threshold + 1e-9).floor().min(usize::MAX as f64) as usize;
+    shortest..=longest
+}
+
+fn remove_index(
+    by_title: &mut HashMap<String, Vec<usize>>,
+    by_length: &mut BTreeMap<usize, Vec<usize>>,
+    title: &str,
+    i: usize,
+) {
+    if let Some(candidates) = by_title.get_mut(title) {
+        candidates.retain(|&candidate| candidate != i);
+    }
+    if let Some(candidates) = by_length.get_mut(&title.chars().count()) {
+        candidates.retain(|&candidate| candidate != i);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::itch_game_info_parser::Author;
+
+    fn record(title: &str, author: &str, update_date: &str) -> ItchData {
+        ItchData {
+            plain_title: title.to_string(),
+            link: format!("https://{}.itch.io/{}", author, title.len()),
+            update_date: update_date.to_string(),
+            authors: vec![Author {
+                name: author.to_string(),
+                url: String::new(),
+            }],
+            ..ItchData::default()
+        }
+    }
+
+    #[test]
+    fn fuzzy_merge_joins_close_titles_by_the_same_author() {
+        let records = vec![
+            record("Cave Run", "alice", "2024-04-01 10:00:00"),
+            record("Sky Bound", "bob", "2024-04-01 10:00:00"),
+            record("Cave Run!", "alice", "2024-04-03 10:00:00"),
+            record("Cave Runs", "alice", "2024-04-02 10:00:00"),
+            record("Cave Run", "carol", "2024-04-01 10:00:00"),
+            record("Cave Run: The Deep Caverns", "alice", "2024-04-01 10:00:00"),
+        ];
+
+        let merged = fuzzy_merge(records, 0.85);
+        let titles: Vec<&str> = merged
+            .iter()
+            .map(|record| record.plain_title.as_str())
+            .collect();
+        assert_eq!(
+            titles,
+            [
+                "Cave Run!",
+                "Sky Bound",
+                "Cave Run",
+                "Cave Run: The Deep Caverns"
+            ]
+        );
+        assert_eq!(merged[2].authors[0].name, "carol");
+        assert_eq!(
+            fuzzy_merge(vec![record("Cave Run", "alice", "")], 1.5).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn only_titles_close_in_length_are_compared() {
+        assert_eq!(comparable_lengths(10, 0.9), 9..=11);
+        assert_eq!(comparable_lengths(0, 0.9), 0..=0);
+        assert_eq!(comparable_lengths(8, 0.0), 0..=usize::MAX);
+    }
+}
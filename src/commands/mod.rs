@@ -0,0 +1,21 @@
+pub mod auth;
+pub mod canary;
+pub mod chart;
+pub mod clean;
+pub mod completions;
+pub mod dedupe;
+pub mod explain_schema;
+pub mod export;
+pub mod grpc;
+pub mod history;
+pub mod init;
+pub mod mcp;
+pub mod merge;
+pub mod parse;
+pub mod proto;
+pub mod search;
+pub mod serve;
+pub mod serve_jobs;
+pub mod test_fixtures;
+pub mod validate;
+pub mod watch;
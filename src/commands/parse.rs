@@ -0,0 +1,62 @@
+use crate::diagnostics::ParseDiagnostic;
+use crate::parsers::itch_game_info_parser::parse_itch_game_page_data;
+use anyhow::{anyhow, Context, Result};
+use clap::{ArgGroup, Args, ValueEnum};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("source").required(true).args(["file", "stdin"])))]
+pub struct ParseArgs {
+    #[arg(value_name = "FILE PATH")]
+    pub file: Option<PathBuf>,
+
+    #[arg(long)]
+    pub stdin: bool,
+
+    #[arg(short, long, value_enum, value_name = "PARSER")]
+    pub parser: ParserKind,
+
+    #[arg(long)]
+    pub pretty: bool,
+}
+
+#[derive(Debug, ValueEnum, Clone)]
+pub enum ParserKind {
+    ItchGame,
+}
+
+pub fn run(args: ParseArgs) -> Result<()> {
+    let (source, html) = match &args.file {
+        Some(file) => (
+            file.display().to_string(),
+            fs::read_to_string(file).with_context(|| format!("Failed to read {:?}", file))?,
+        ),
+        None => {
+            let mut html = String::new();
+            io::stdin()
+                .read_to_string(&mut html)
+                .context("Failed to read HTML from stdin")?;
+            ("<stdin>".to_string(), html)
+        }
+    };
+
+    let record = match args.parser {
+        ParserKind::ItchGame => parse_itch_game_page_data(&html).map_err(|err| {
+            let diagnostic = ParseDiagnostic::from_html_error(&source, &html, &err);
+            eprintln!("{}", diagnostic.render());
+            anyhow!("Failed to parse {} as an itch game page", source)
+        })?,
+    };
+
+    let json = match args.pretty {
+        true => serde_json::to_string_pretty(&record)?,
+        false => serde_json::to_string(&record)?,
+    };
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(json.as_bytes())?;
+    stdout.write_all(b"\n")?;
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use anyhow::Result;
+use clap::Args;
+
+const RECORDS: &str = include_str!("../../proto/game_record.proto");
+const SERVICE: &str = include_str!("../../proto/game_data_scraper.proto");
+
+#[derive(Args, Debug)]
+pub struct ProtoArgs {
+    #[arg(long)]
+    pub service: bool,
+}
+
+// --format protobuf only needs the record messages, --service prints the gRPC definition, which
+// imports them as game_record.proto
+pub fn run(args: ProtoArgs) -> Result<()> {
+    match args.service {
+        true => print!("{}", SERVICE),
+        false => print!("{}", RECORDS),
+    }
+
+    Ok(())
+}
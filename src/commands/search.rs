@@ -0,0 +1,173 @@
+use crate::dataset::load_all;
+use crate::exit::Failure;
+use crate::filters::{Filter, FilterArgs};
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, Result};
+use clap::Args;
+use scraper::Html;
+use std::path::{Path, PathBuf};
+use std::{fs, io, io::Write};
+use tantivy::collector::TopDocs;
+use tantivy::query::QueryParser;
+use tantivy::schema::{Field, Schema, Value, STORED, TEXT};
+use tantivy::{doc, Index, TantivyDocument};
+
+const WRITER_MEMORY: usize = 50_000_000;
+
+#[derive(Args, Debug)]
+pub struct SearchArgs {
+    pub query: String,
+
+    #[arg(short, long = "input", value_name = "FILE PATH")]
+    pub inputs: Vec<PathBuf>,
+
+    #[arg(long, value_name = "DIR PATH", required_unless_present = "inputs")]
+    pub index: Option<PathBuf>,
+
+    #[arg(short, long, value_name = "COUNT", default_value_t = 20)]
+    pub limit: usize,
+
+    #[arg(short, long, value_name = "FILE PATH")]
+    pub outfile: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+struct Fields {
+    title: Field,
+    description: Field,
+    tags: Field,
+    record: Field,
+}
+
+fn schema() -> (Schema, Fields) {
+    let mut builder = Schema::builder();
+    let fields = Fields {
+        title: builder.add_text_field("title", TEXT),
+        description: builder.add_text_field("description", TEXT),
+        tags: builder.add_text_field("tags", TEXT),
+        record: builder.add_text_field("record", STORED),
+    };
+
+    (builder.build(), fields)
+}
+
+pub fn run(args: SearchArgs) -> Result<()> {
+    let (schema, fields) = schema();
+
+    let index = match (&args.index, args.inputs.is_empty()) {
+        (Some(dir), true) => Index::open_in_dir(dir)
+            .map_err(|err| anyhow!("Failed to open search index {:?}: {}", dir, err))?,
+        (Some(dir), false) => {
+            let index = create_dir_index(dir, schema)?;
+            index_records(&index, &fields, &load_all(&args.inputs)?)?;
+            index
+        }
+        (None, _) => {
+            let index = Index::create_in_ram(schema);
+            index_records(&index, &fields, &load_all(&args.inputs)?)?;
+            index
+        }
+    };
+
+    let records = search(
+        &index,
+        &fields,
+        &args.query,
+        &Filter::from(args.filter),
+        args.limit,
+    )?;
+    let output = serde_json::to_string_pretty(&records)?;
+
+    match args.outfile {
+        Some(file) => fs::write(file, output)?,
+        None => io::stdout().write_all(output.as_bytes())?,
+    }
+
+    Ok(())
+}
+
+// only a previous index is cleared, --index pointed at any other non-empty directory is an
+// error rather than a recursive delete
+fn create_dir_index(dir: &Path, schema: Schema) -> Result<Index> {
+    if dir.join("meta.json").is_file() {
+        fs::remove_dir_all(dir)?;
+    } else if dir.exists() && (!dir.is_dir() || fs::read_dir(dir)?.next().is_some()) {
+        return Err(Failure::Config(anyhow!(
+            "{:?} exists and isn't a search index, pass an empty or new directory to --index",
+            dir
+        ))
+        .into());
+    }
+    fs::create_dir_all(dir)?;
+
+    Ok(Index::create_in_dir(dir, schema)?)
+}
+
+fn index_records(index: &Index, fields: &Fields, records: &[ItchData]) -> Result<()> {
+    let mut writer = index.writer(WRITER_MEMORY)?;
+
+    for record in records {
+        writer.add_document(doc!(
+            fields.title => record.title.as_str(),
+            fields.description => html_text(&record.description),
+            fields.tags => record.tag_names().join(" "),
+            fields.record => serde_json::to_string(record)?,
+        ))?;
+    }
+
+    writer.commit()?;
+    Ok(())
+}
+
+fn html_text(html: &str) -> String {
+    Html::parse_fragment(html)
+        .root_element()
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn search(
+    index: &Index,
+    fields: &Fields,
+    query: &str,
+    filter: &Filter,
+    limit: usize,
+) -> Result<Vec<ItchData>> {
+    let searcher = index.reader()?.searcher();
+
+    let mut parser =
+        QueryParser::for_index(index, vec![fields.title, fields.description, fields.tags]);
+    parser.set_field_boost(fields.title, 3.0);
+    parser.set_field_boost(fields.tags, 2.0);
+    let query = parser.parse_query(query)?;
+
+    // filters are applied after ranking, so consider every hit before truncating
+    let total = (searcher.num_docs() as usize).max(1);
+    let hits = searcher.search(&query, &TopDocs::with_limit(total))?;
+
+    let mut records = Vec::new();
+    for (_, address) in hits {
+        let document: TantivyDocument = searcher.doc(address)?;
+        let Some(json) = document
+            .get_first(fields.record)
+            .and_then(|value| value.as_str())
+        else {
+            continue;
+        };
+
+        let mut record: ItchData = serde_json::from_str(json)?;
+        // documents indexed before price_info was scraped
+        record.upgrade();
+        if filter.matches(&record) {
+            records.push(record);
+        }
+        if records.len() == limit {
+            break;
+        }
+    }
+
+    Ok(records)
+}
@@ -0,0 +1,73 @@
+use crate::db::{self, GameQuery, SortField};
+use crate::scrapers::itch_rss_scraper::ItchData;
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, InputObject, Object, Schema, SimpleObject,
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub type GameSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+pub fn schema(db_path: Arc<PathBuf>) -> GameSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(db_path)
+        .finish()
+}
+
+#[derive(InputObject, Debug, Default)]
+pub struct GameFilter {
+    tags: Option<Vec<String>>,
+    genres: Option<Vec<String>>,
+    platforms: Option<Vec<String>>,
+    min_price: Option<f64>,
+    max_price: Option<f64>,
+    published_after: Option<String>,
+    published_before: Option<String>,
+    search: Option<String>,
+}
+
+#[derive(SimpleObject, Debug)]
+pub struct GamePage {
+    total: u64,
+    games: Vec<ItchData>,
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn games(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<GameFilter>,
+        sort: Option<SortField>,
+        #[graphql(default = true)] descending: bool,
+        #[graphql(default = 50, validator(minimum = 1, maximum = 200))] limit: u32,
+        #[graphql(default = 0)] offset: u32,
+    ) -> async_graphql::Result<GamePage> {
+        let db_path = ctx.data::<Arc<PathBuf>>()?.clone();
+        let filter = filter.unwrap_or_default();
+        let query = GameQuery {
+            tags: filter.tags.unwrap_or_default(),
+            genres: filter.genres.unwrap_or_default(),
+            platforms: filter.platforms.unwrap_or_default(),
+            min_price: filter.min_price,
+            max_price: filter.max_price,
+            published_after: filter.published_after,
+            published_before: filter.published_before,
+            search: filter.search,
+            sort: sort.unwrap_or_default(),
+            descending,
+            limit,
+            offset,
+        };
+
+        let (total, games) = tokio::task::spawn_blocking(move || {
+            let conn = db::open(&db_path)?;
+            db::query_games(&conn, &query)
+        })
+        .await??;
+
+        Ok(GamePage { total, games })
+    }
+}
@@ -0,0 +1,195 @@
+use crate::db::{self, GameQuery, SortField};
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::Result;
+use async_graphql::http::GraphiQLSource;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::Html;
+use axum::routing::get;
+use axum::{Json, Router};
+use clap::Args;
+use graphql::GameSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+mod graphql;
+
+const MAX_PER_PAGE: u32 = 200;
+
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    #[arg(long, value_name = "FILE PATH")]
+    pub db: PathBuf,
+
+    #[arg(long, value_name = "ADDRESS", default_value = "127.0.0.1")]
+    pub host: String,
+
+    #[arg(long, value_name = "INTEGER", default_value_t = 8080)]
+    pub port: u16,
+}
+
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct GamesParams {
+    tag: Option<String>,
+    genre: Option<String>,
+    platform: Option<String>,
+    min_price: Option<f64>,
+    max_price: Option<f64>,
+    published_after: Option<String>,
+    published_before: Option<String>,
+    q: Option<String>,
+    #[serde(default)]
+    sort: SortField,
+    #[serde(default)]
+    #[param(pattern = "^(asc|desc)$")]
+    order: Option<String>,
+    #[serde(default = "default_page")]
+    page: u32,
+    #[serde(default = "default_per_page")]
+    per_page: u32,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_per_page() -> u32 {
+    50
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "game-data-scraper",
+        description = "Query scraped game records"
+    ),
+    paths(list_games, openapi_document),
+    components(schemas(SortField))
+)]
+struct ApiDoc;
+
+#[derive(Clone)]
+struct AppState {
+    db_path: Arc<PathBuf>,
+    schema: GameSchema,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+struct GamesPage {
+    page: u32,
+    per_page: u32,
+    total: u64,
+    games: Vec<ItchData>,
+}
+
+pub async fn run(args: ServeArgs) -> Result<()> {
+    // create the schema up front so an empty database can still be served
+    db::open(&args.db)?;
+
+    let listener = tokio::net::TcpListener::bind((args.host.as_str(), args.port)).await?;
+    eprintln!("Serving {:?} on http://{}", args.db, listener.local_addr()?);
+
+    let db_path = Arc::new(args.db);
+    let state = AppState {
+        schema: graphql::schema(db_path.clone()),
+        db_path,
+    };
+
+    let app = Router::new()
+        .route("/games", get(list_games))
+        .route("/openapi.json", get(openapi_document))
+        .route("/graphql", get(graphiql).post(graphql_handler))
+        .with_state(state);
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+fn split_list(value: Option<String>) -> Vec<String> {
+    value
+        .map(|v| {
+            v.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+async fn graphql_handler(
+    State(state): State<AppState>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(state.schema.execute(request).await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    responses((status = 200, description = "This OpenAPI document", content_type = "application/json"))
+)]
+async fn openapi_document() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+// list values such as tag=horror,pixel-art are comma separated, and all of them have to match
+#[utoipa::path(
+    get,
+    path = "/games",
+    params(GamesParams),
+    responses(
+        (status = 200, description = "A page of matching games", body = GamesPage),
+        (status = 400, description = "The page is out of range", body = String, content_type = "text/plain"),
+        (status = 500, description = "The database could not be queried", body = String, content_type = "text/plain")
+    )
+)]
+async fn list_games(
+    State(state): State<AppState>,
+    Query(params): Query<GamesParams>,
+) -> Result<Json<GamesPage>, (StatusCode, String)> {
+    let page = params.page.max(1);
+    let per_page = params.per_page.clamp(1, MAX_PER_PAGE);
+    let offset = (page - 1).checked_mul(per_page).ok_or((
+        StatusCode::BAD_REQUEST,
+        format!("page {} is out of range", page),
+    ))?;
+    let query = GameQuery {
+        tags: split_list(params.tag),
+        genres: split_list(params.genre),
+        platforms: split_list(params.platform),
+        min_price: params.min_price,
+        max_price: params.max_price,
+        published_after: params.published_after,
+        published_before: params.published_before,
+        search: params.q,
+        sort: params.sort,
+        descending: params.order.as_deref() != Some("asc"),
+        limit: per_page,
+        offset,
+    };
+
+    let db_path = state.db_path.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let conn = db::open(&db_path)?;
+        db::query_games(&conn, &query)
+    })
+    .await;
+
+    match result {
+        Ok(Ok((total, games))) => Ok(Json(GamesPage {
+            page,
+            per_page,
+            total,
+            games,
+        })),
+        Ok(Err(err)) => Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+        Err(err) => Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    }
+}
@@ -0,0 +1,444 @@
+use crate::exit::Failure;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use crate::scrapers::progress::{ProgressReporter, ScrapeEvent};
+use crate::sinks;
+use crate::{scrape, ScrapeArgs, Site};
+use anyhow::{anyhow, Result};
+use axum::body::Body;
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use clap::{Args, ValueEnum};
+use futures_util::stream;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::task::AbortHandle;
+
+#[derive(Args, Debug)]
+pub struct ServeJobsArgs {
+    #[arg(long, value_name = "ADDRESS", default_value = "127.0.0.1")]
+    pub host: String,
+
+    #[arg(long, value_name = "INTEGER", default_value_t = 8081)]
+    pub port: u16,
+
+    // the sinks jobs may send to; a request picks them by name and never passes a URI, which
+    // would let any client point the server's credentials at a host of its choosing
+    #[arg(long = "sink", value_name = "NAME=URI", value_parser = parse_named_sink)]
+    pub sinks: Vec<(String, String)>,
+
+    // how long a finished job and its records are kept for GET /jobs/:id/results
+    #[arg(
+        long,
+        value_name = "DURATION",
+        value_parser = humantime::parse_duration,
+        default_value = "1h"
+    )]
+    pub keep_finished: Duration,
+}
+
+fn parse_named_sink(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((name, uri)) if !name.is_empty() => Ok((name.to_string(), uri.to_string())),
+        _ => Err(format!("expected NAME=URI, got {:?}", value)),
+    }
+}
+
+// only what's needed to describe a scrape, nothing that reads files, the environment or secrets on
+// the server; sinks are names from --sink
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct JobRequest {
+    #[serde(default)]
+    site: Option<String>,
+    url: String,
+    page_limit: Option<i32>,
+    max_retries: Option<u32>,
+    since: Option<String>,
+    until: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    genres: Vec<String>,
+    #[serde(default)]
+    platforms: Vec<String>,
+    #[serde(default)]
+    free_only: bool,
+    min_price: Option<f64>,
+    max_price: Option<f64>,
+    min_rating: Option<f32>,
+    min_ratings: Option<i32>,
+    #[serde(default)]
+    sinks: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum JobStatus {
+    Running,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct JobView {
+    id: u64,
+    url: String,
+    status: JobStatus,
+    pages: u64,
+    pages_done: u64,
+    scraped: u64,
+    errors: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    records: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+struct Job {
+    view: Mutex<JobView>,
+    records: Mutex<Vec<ItchData>>,
+    // flips to true once the job stops for any reason, results requests wait on it
+    done: watch::Sender<bool>,
+    abort: Mutex<Option<AbortHandle>>,
+    finished_at: Mutex<Option<Instant>>,
+}
+
+impl Job {
+    fn new(id: u64, url: String) -> Job {
+        Job {
+            view: Mutex::new(JobView {
+                id,
+                url,
+                status: JobStatus::Running,
+                pages: 0,
+                pages_done: 0,
+                scraped: 0,
+                errors: 0,
+                records: None,
+                error: None,
+            }),
+            records: Mutex::new(Vec::new()),
+            done: watch::channel(false).0,
+            abort: Mutex::new(None),
+            finished_at: Mutex::new(None),
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut JobView)) {
+        f(&mut self.view.lock().unwrap());
+    }
+
+    fn finish(&self, status: JobStatus, error: Option<String>) {
+        self.update(|view| {
+            // a cancel that lost the race against the scrape finishing doesn't count
+            if view.status == JobStatus::Running {
+                view.status = status;
+                view.error = error;
+            }
+        });
+        self.finished_at
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Instant::now);
+        self.done.send_replace(true);
+    }
+
+    fn expired(&self, keep: Duration) -> bool {
+        self.finished_at
+            .lock()
+            .unwrap()
+            .is_some_and(|at| at.elapsed() > keep)
+    }
+}
+
+struct JobReporter(Arc<Job>);
+
+impl ProgressReporter for JobReporter {
+    fn report(&self, event: ScrapeEvent) {
+        self.0.update(|view| match event {
+            ScrapeEvent::Started { pages } => view.pages = pages,
+            ScrapeEvent::PageFinished { .. } => view.pages_done += 1,
+            ScrapeEvent::Item { .. } => view.scraped += 1,
+            ScrapeEvent::Error { .. } => view.errors += 1,
+            _ => {}
+        });
+    }
+}
+
+#[derive(Clone, Default)]
+struct AppState {
+    jobs: Arc<Mutex<BTreeMap<u64, Arc<Job>>>>,
+    next_id: Arc<AtomicU64>,
+    // sink URIs by the name requests refer to them with
+    sinks: Arc<BTreeMap<String, String>>,
+    keep_finished: Duration,
+}
+
+impl AppState {
+    fn job(&self, id: u64) -> Result<Arc<Job>, (StatusCode, String)> {
+        self.evict_finished();
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| (StatusCode::NOT_FOUND, format!("No job {}", id)))
+    }
+
+    // finished jobs are dropped once they've been kept long enough, so the registry only grows
+    // with jobs that are still running; a results request already waiting keeps its own copy
+    fn evict_finished(&self) {
+        self.jobs
+            .lock()
+            .unwrap()
+            .retain(|_, job| !job.expired(self.keep_finished));
+    }
+
+    fn sink(&self, name: &str) -> Result<String, (StatusCode, String)> {
+        self.sinks.get(name).cloned().ok_or_else(|| {
+            let names: Vec<&str> = self.sinks.keys().map(String::as_str).collect();
+            (
+                StatusCode::BAD_REQUEST,
+                match names.is_empty() {
+                    true => format!("Unknown sink {:?}, the server has none", name),
+                    false => format!(
+                        "Unknown sink {:?}, the server has: {}",
+                        name,
+                        names.join(", ")
+                    ),
+                },
+            )
+        })
+    }
+}
+
+pub async fn run(args: ServeJobsArgs) -> Result<()> {
+    let mut sinks = BTreeMap::new();
+    for (name, uri) in args.sinks {
+        // checked up front so a bad URI stops the server rather than failing every job
+        sinks::parse_all(std::slice::from_ref(&uri))?;
+        if sinks.insert(name.clone(), uri).is_some() {
+            return Err(Failure::Config(anyhow!("--sink {:?} is given twice", name)).into());
+        }
+    }
+    let state = AppState {
+        sinks: Arc::new(sinks),
+        keep_finished: args.keep_finished,
+        ..AppState::default()
+    };
+
+    let listener = tokio::net::TcpListener::bind((args.host.as_str(), args.port)).await?;
+    eprintln!("Accepting scrape jobs on http://{}", listener.local_addr()?);
+
+    let app = Router::new()
+        .route("/jobs", get(list_jobs).post(submit_job))
+        .route("/jobs/:id", get(get_job).delete(cancel_job))
+        .route("/jobs/:id/results", get(job_results))
+        .with_state(state);
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+fn scrape_args(state: &AppState, request: JobRequest) -> Result<ScrapeArgs, (StatusCode, String)> {
+    let site = match request.site.as_deref() {
+        Some(site) => Site::from_str(site, true)
+            .map_err(|_| (StatusCode::BAD_REQUEST, format!("Unknown site {:?}", site)))?,
+        None => Site::Itch,
+    };
+    let sinks = request
+        .sinks
+        .iter()
+        .map(|name| state.sink(name))
+        .collect::<Result<_, _>>()?;
+    if let Site::Custom = site {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Custom sites need a --site-def file and can't be submitted as jobs".to_string(),
+        ));
+    }
+
+    Ok(ScrapeArgs {
+        site,
+        url: request.url,
+        page_limit: request.page_limit,
+        max_retries: request.max_retries,
+        since: request.since,
+        until: request.until,
+        tags: request.tags,
+        genres: request.genres,
+        platforms: request.platforms,
+        free_only: request.free_only,
+        min_price: request.min_price,
+        max_price: request.max_price,
+        min_rating: request.min_rating,
+        min_ratings: request.min_ratings,
+        sinks,
+        quiet: true,
+        ..ScrapeArgs::default()
+    })
+}
+
+async fn submit_job(
+    State(state): State<AppState>,
+    Json(request): Json<JobRequest>,
+) -> Result<(StatusCode, Json<JobView>), (StatusCode, String)> {
+    let args = scrape_args(&state, request)?;
+    state.evict_finished();
+
+    let id = state.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+    let job = Arc::new(Job::new(id, args.url.clone()));
+    state.jobs.lock().unwrap().insert(id, job.clone());
+
+    let task = tokio::spawn({
+        let job = job.clone();
+        async move {
+            let reporter = JobReporter(job.clone());
+            let result = async {
+                let records = scrape(&args, &reporter).await?;
+                sinks::send_all(&sinks::parse_all(&args.sinks)?, &records).await?;
+                anyhow::Ok(records)
+            }
+            .await;
+
+            match result {
+                Ok(records) => {
+                    job.update(|view| view.records = Some(records.len()));
+                    *job.records.lock().unwrap() = records;
+                    job.finish(JobStatus::Finished, None);
+                }
+                Err(err) => job.finish(JobStatus::Failed, Some(format!("{:#}", err))),
+            }
+        }
+    });
+    *job.abort.lock().unwrap() = Some(task.abort_handle());
+
+    let view = job.view.lock().unwrap().clone();
+    Ok((StatusCode::ACCEPTED, Json(view)))
+}
+
+async fn list_jobs(State(state): State<AppState>) -> Json<Vec<JobView>> {
+    state.evict_finished();
+    let jobs = state.jobs.lock().unwrap();
+    Json(
+        jobs.values()
+            .map(|job| job.view.lock().unwrap().clone())
+            .collect(),
+    )
+}
+
+async fn get_job(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Json<JobView>, (StatusCode, String)> {
+    let job = state.job(id)?;
+    let view = job.view.lock().unwrap().clone();
+    Ok(Json(view))
+}
+
+async fn cancel_job(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Json<JobView>, (StatusCode, String)> {
+    let job = state.job(id)?;
+    if let Some(abort) = job.abort.lock().unwrap().take() {
+        abort.abort();
+    }
+    job.finish(JobStatus::Cancelled, None);
+    let view = job.view.lock().unwrap().clone();
+    Ok(Json(view))
+}
+
+// newline-delimited JSON, sent once the job is done; records only exist after filtering and
+// transforms have run over the whole scrape
+async fn job_results(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Response, (StatusCode, String)> {
+    let job = state.job(id)?;
+    let mut done = job.done.subscribe();
+    let _ = done.wait_for(|done| *done).await;
+
+    let view = job.view.lock().unwrap().clone();
+    if view.status != JobStatus::Finished {
+        // only failed jobs have an error
+        let message = view
+            .error
+            .unwrap_or_else(|| format!("Job {} was cancelled", id));
+        return Err((StatusCode::CONFLICT, message));
+    }
+
+    let lines: Vec<Result<String, Infallible>> = job
+        .records
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|record| {
+            Ok(format!(
+                "{}\n",
+                serde_json::to_string(record).unwrap_or_default()
+            ))
+        })
+        .collect();
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(stream::iter(lines)),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(json: &str) -> JobRequest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn sinks_are_only_picked_by_name() {
+        let state = AppState {
+            sinks: Arc::new(BTreeMap::from([(
+                "games".to_string(),
+                "nats://localhost/games".to_string(),
+            )])),
+            ..AppState::default()
+        };
+
+        let args = scrape_args(&state, request(r#"{"url": "u", "sinks": ["games"]}"#)).unwrap();
+        assert_eq!(args.sinks, ["nats://localhost/games"]);
+
+        let (status, _) = scrape_args(
+            &state,
+            request(r#"{"url": "u", "sinks": ["postgrest://attacker.example/t"]}"#),
+        )
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn finished_jobs_are_evicted_once_kept_long_enough() {
+        let state = AppState::default();
+        let running = Arc::new(Job::new(1, String::new()));
+        let finished = Arc::new(Job::new(2, String::new()));
+        finished.finish(JobStatus::Finished, None);
+        state
+            .jobs
+            .lock()
+            .unwrap()
+            .extend([(1, running), (2, finished)]);
+
+        std::thread::sleep(Duration::from_millis(5));
+        state.evict_finished();
+        assert_eq!(state.jobs.lock().unwrap().keys().collect::<Vec<_>>(), [&1]);
+    }
+}
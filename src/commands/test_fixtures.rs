@@ -0,0 +1,51 @@
+use crate::parsers::corpus::{self, CORPUS_DIR};
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct TestFixturesArgs {
+    #[command(subcommand)]
+    pub command: TestFixturesCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TestFixturesCommand {
+    /// Rewrite the expected JSON of every corpus page from the current parser output
+    Update(UpdateArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct UpdateArgs {
+    #[arg(long, value_name = "DIR", default_value = CORPUS_DIR)]
+    pub dir: PathBuf,
+}
+
+pub fn run(args: TestFixturesArgs) -> Result<()> {
+    match args.command {
+        TestFixturesCommand::Update(args) => update(args),
+    }
+}
+
+// only intentional parser changes should be followed by an update, the diff of the .json files
+// is what gets reviewed
+fn update(args: UpdateArgs) -> Result<()> {
+    let mut changed = 0;
+    let cases = corpus::cases(&args.dir)?;
+
+    for case in &cases {
+        let rendered = case.render()?;
+        if fs::read_to_string(&case.golden).ok().as_deref() == Some(rendered.as_str()) {
+            continue;
+        }
+
+        fs::write(&case.golden, rendered)
+            .with_context(|| format!("Failed to write {:?}", case.golden))?;
+        eprintln!("Updated {}", case.golden.display());
+        changed += 1;
+    }
+    eprintln!("{} of {} golden files changed", changed, cases.len());
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use crate::dataset::load_values;
+use crate::parsers::dates::parse_feed_date;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, Result};
+use clap::Args;
+use jsonschema::JSONSchema;
+use serde_json::Value;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    #[arg(required = true, value_name = "FILE PATH")]
+    pub inputs: Vec<PathBuf>,
+
+    #[arg(long)]
+    pub print_schema: bool,
+}
+
+pub fn run(args: ValidateArgs) -> Result<()> {
+    let schema = serde_json::to_value(schemars::schema_for!(ItchData))?;
+    if args.print_schema {
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+    }
+
+    let validator =
+        JSONSchema::compile(&schema).map_err(|err| anyhow!("Invalid record schema: {}", err))?;
+
+    let mut checked = 0;
+    let mut violations = 0;
+    for path in &args.inputs {
+        for (i, value) in load_values(path)?.iter().enumerate() {
+            checked += 1;
+            for problem in check_record(&validator, value) {
+                violations += 1;
+                eprintln!("{}: record {}: {}", path.display(), i + 1, problem);
+            }
+        }
+    }
+
+    if violations > 0 {
+        return Err(anyhow!(
+            "{} violations found in {} records",
+            violations,
+            checked
+        ));
+    }
+
+    eprintln!("{} records valid", checked);
+    Ok(())
+}
+
+fn check_record(validator: &JSONSchema, value: &Value) -> Vec<String> {
+    let mut problems: Vec<String> = match validator.validate(value) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors
+            .map(|err| match err.instance_path.to_string() {
+                path if path.is_empty() => err.to_string(),
+                path => format!("{} at {}", err, path),
+            })
+            .collect(),
+    };
+
+    // schema violations can still leave enough of the record to check its values
+    let mut value = value.clone();
+    if let Some(object) = value.as_object_mut() {
+        for (key, default) in default_record() {
+            object.entry(key).or_insert(default);
+        }
+    }
+    let Ok(record) = serde_json::from_value::<ItchData>(value) else {
+        return problems;
+    };
+
+    if let Some(rating) = &record.rating {
+        if !(0.0..=5.0).contains(&rating.score) {
+            problems.push(format!("rating score {} is outside 0-5", rating.score));
+        }
+        if rating.count < 0 {
+            problems.push(format!("rating count {} is negative", rating.count));
+        }
+        if rating.count == 0 && rating.score > 0.0 {
+            problems.push("rating score is set but rating count is 0".to_string());
+        }
+    }
+
+    for (field, date) in [
+        ("create_date", &record.create_date),
+        ("update_date", &record.update_date),
+        ("pub_date", &record.pub_date),
+    ] {
+        if !date.is_empty() && parse_feed_date(date).is_none() {
+            problems.push(format!("{} {:?} is not a parseable date", field, date));
+        }
+    }
+
+    if record.link.is_empty() {
+        problems.push("link is empty".to_string());
+    }
+
+    problems
+}
+
+fn default_record() -> serde_json::Map<String, Value> {
+    match serde_json::to_value(ItchData::default()) {
+        Ok(Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    }
+}
@@ -0,0 +1,267 @@
+use crate::config::{Config, JobConfig};
+use crate::dataset::{self, create_parent_dirs, load_records};
+use crate::exit::Failure;
+use crate::incremental::IncrementalState;
+use crate::notifiers::Notifier;
+use crate::output::OutputFormat;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use crate::sinks;
+use crate::{scrape, ScrapeArgs};
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use clap::{Args, ValueEnum};
+use cron::Schedule;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
+use tokio::time::{interval, sleep, MissedTickBehavior};
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    #[arg(
+        short,
+        long,
+        value_name = "DURATION",
+        value_parser = humantime::parse_duration,
+        required_unless_present = "config",
+        requires = "site"
+    )]
+    pub every: Option<Duration>,
+
+    #[arg(short, long, value_name = "FILE PATH", conflicts_with_all = ["every", "state"])]
+    pub config: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE PATH")]
+    pub state: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub scrape: Option<ScrapeArgs>,
+}
+
+pub async fn run(args: WatchArgs) -> Result<()> {
+    if let Some(config_path) = args.config {
+        let config = Config::from_file(&config_path)
+            .with_context(|| format!("Failed to load config file {:?}", config_path))
+            .map_err(Failure::Config)?;
+        let limiter = config.rate_limiter()?;
+        let mut jobs = config.jobs;
+        for job in &mut jobs {
+            check_format(&job.scrape)?;
+            job.scrape.resolve_rules(&config.rules)?;
+            job.scrape.limiter = limiter.clone();
+        }
+        let notifier = Notifier::new(config.notifications, config.slack, config.email);
+        return run_jobs(jobs, notifier).await;
+    }
+
+    let every = args.every.expect("clap requires --every without --config");
+    let mut scrape_args = args.scrape.expect("clap requires scrape args with --every");
+    check_format(&scrape_args)?;
+    if scrape_args.rules.is_some() {
+        scrape_args.resolve_rules(&Config::load(None)?.rules)?;
+    }
+    let state = Mutex::new(IncrementalState::load(args.state)?);
+    let notifier = Notifier::default();
+
+    let mut ticker = interval(every);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        emit_changes(&scrape_args.url, &scrape_args, &state, &notifier).await?;
+    }
+}
+
+async fn run_jobs(jobs: Vec<JobConfig>, notifier: Notifier) -> Result<()> {
+    if jobs.is_empty() {
+        return Err(Failure::Config(anyhow!("Config file does not define any jobs")).into());
+    }
+
+    let notifier = Arc::new(notifier);
+    let mut tasks = JoinSet::new();
+    for job in jobs {
+        let Some(schedule) = &job.schedule else {
+            return Err(
+                Failure::Config(anyhow!("Job {:?} has no schedule to watch on", job.name)).into(),
+            );
+        };
+        let schedule = parse_schedule(schedule)
+            .with_context(|| format!("Invalid cron expression for job {:?}", job.name))
+            .map_err(Failure::Config)?;
+        tasks.spawn(run_job(job, schedule, notifier.clone()));
+    }
+
+    while let Some(result) = tasks.join_next().await {
+        result??;
+    }
+
+    Ok(())
+}
+
+// each run's changes are appended to the outfile, which only json and ndjson can take
+fn check_format(args: &ScrapeArgs) -> Result<()> {
+    match args.format {
+        OutputFormat::Json | OutputFormat::Ndjson => Ok(()),
+        format => Err(Failure::Config(anyhow!(
+            "watch only writes json or ndjson, not {}",
+            format
+                .to_possible_value()
+                .map_or(String::new(), |value| value.get_name().to_string())
+        ))
+        .into()),
+    }
+}
+
+// the cron crate wants a leading seconds field, a standard 5 field expression runs at second 0
+fn parse_schedule(expression: &str) -> Result<Schedule> {
+    let expression = match expression.split_whitespace().count() {
+        5 => format!("0 {}", expression.trim()),
+        _ => expression.to_string(),
+    };
+    Ok(Schedule::from_str(&expression)?)
+}
+
+async fn run_job(job: JobConfig, schedule: Schedule, notifier: Arc<Notifier>) -> Result<()> {
+    let state = Arc::new(Mutex::new(IncrementalState::load(job.state)?));
+    let concurrency = job.concurrency.unwrap_or(1).max(1);
+    let running = Arc::new(Semaphore::new(concurrency));
+    let scrape_args = Arc::new(job.scrape);
+
+    for next_run in schedule.upcoming(Utc) {
+        sleep((next_run - Utc::now()).to_std().unwrap_or_default()).await;
+
+        let Ok(permit) = running.clone().try_acquire_owned() else {
+            eprintln!(
+                "Skipping scheduled run of job {:?}: {} run(s) already in progress",
+                job.name, concurrency
+            );
+            continue;
+        };
+
+        let state = state.clone();
+        let scrape_args = scrape_args.clone();
+        let notifier = notifier.clone();
+        let name = job.name.clone();
+        tokio::spawn(async move {
+            if let Err(err) = emit_changes(&name, &scrape_args, &state, &notifier).await {
+                eprintln!("Error running job {:?}: {:?}", name, err);
+            }
+            drop(permit);
+        });
+    }
+
+    Ok(())
+}
+
+async fn emit_changes(
+    job: &str,
+    args: &ScrapeArgs,
+    state: &Mutex<IncrementalState>,
+    notifier: &Notifier,
+) -> Result<()> {
+    let records = match scrape(args, args.reporter().as_ref()).await {
+        Ok(records) => records,
+        Err(err) => {
+            eprintln!("Error scraping {:?}: {:?}", args.url, err);
+            notifier.scrape_failure(job, &err).await;
+            return Ok(());
+        }
+    };
+
+    let sinks = sinks::parse_all(&args.sinks)?;
+    let mut state = state.lock().await;
+    let new = state.unseen(&records);
+    let (changed, price_drops) = state.take_changed(&records);
+    write_records(args.format, &args.outfile_path()?, &changed)?;
+    sinks::send_all(
+        &sinks,
+        &changed
+            .iter()
+            .map(|&record| record.clone())
+            .collect::<Vec<_>>(),
+    )
+    .await?;
+    state.save()?;
+    drop(state);
+
+    notifier.new_games(&new).await;
+    notifier.price_drops(&price_drops).await;
+    notifier
+        .run_summary(job, records.len(), changed.len())
+        .await;
+
+    Ok(())
+}
+
+fn write_records(
+    format: OutputFormat,
+    outfile: &Option<PathBuf>,
+    records: &[&ItchData],
+) -> Result<()> {
+    if let Some(file) = outfile {
+        create_parent_dirs(file)?;
+    }
+
+    // a .json outfile holds a single array, so it has to be rewritten rather than appended to;
+    // anything else, including stdout, gets one record per line
+    if let Some(file) = outfile.as_deref().filter(|file| {
+        matches!(format, OutputFormat::Json) && file.extension().is_some_and(|ext| ext == "json")
+    }) {
+        let mut all = match file.exists() {
+            true => load_records(file)?,
+            false => Vec::new(),
+        };
+        all.extend(records.iter().map(|&record| record.clone()));
+        return dataset::write_records(Some(file), &all);
+    }
+
+    let mut out: Box<dyn Write> = match outfile {
+        Some(file) => Box::new(OpenOptions::new().create(true).append(true).open(file)?),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    for record in records {
+        serde_json::to_writer(&mut out, record)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn five_field_schedules_run_at_second_zero() {
+        let standard = parse_schedule("*/15 * * * *").unwrap();
+        let seconds = parse_schedule("0 */15 * * * *").unwrap();
+
+        assert_eq!(
+            standard.upcoming(Utc).take(3).collect::<Vec<_>>(),
+            seconds.upcoming(Utc).take(3).collect::<Vec<_>>()
+        );
+        assert!(parse_schedule("* * *").is_err());
+    }
+
+    #[test]
+    fn only_appendable_formats_are_watched() {
+        let args = |format| ScrapeArgs {
+            format,
+            ..ScrapeArgs::default()
+        };
+
+        assert!(check_format(&args(OutputFormat::Ndjson)).is_ok());
+        let err = check_format(&args(OutputFormat::Csv)).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Failure>(),
+            Some(Failure::Config(_))
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::exit::Failure;
+use crate::filters::Rule;
+use crate::notifiers::{EmailConfig, NotificationRule, SlackConfig};
+use crate::scrapers::rate_limit::{HostLimit, RateLimiter};
+use crate::secrets;
+use crate::ScrapeArgs;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Deserialize, Debug)]
+pub struct Config {
+    #[serde(default)]
+    pub jobs: Vec<JobConfig>,
+    // whether `--config` on its own runs the jobs side by side instead of one after another
+    #[serde(default)]
+    pub parallel: bool,
+    #[serde(default)]
+    pub notifications: Vec<NotificationRule>,
+    pub slack: Option<SlackConfig>,
+    pub email: Option<EmailConfig>,
+    #[serde(default)]
+    pub rules: HashMap<String, Rule>,
+    #[serde(default)]
+    pub rate_limits: HashMap<String, HostLimit>,
+    #[serde(default, rename = "profile")]
+    pub profiles: HashMap<String, Profile>,
+}
+
+// scrape settings are kept as raw TOML and replayed as command line flags, so explicit
+// flags still override whatever the profile sets
+#[derive(Deserialize, Debug, Default)]
+pub struct Profile {
+    #[serde(default)]
+    pub notifications: Vec<NotificationRule>,
+    pub slack: Option<SlackConfig>,
+    pub email: Option<EmailConfig>,
+    // links already notified about, so a run only announces what is new since the last one
+    pub state: Option<PathBuf>,
+    #[serde(flatten)]
+    pub settings: toml::Table,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JobConfig {
+    pub name: String,
+    // only watch runs jobs on a schedule, `--config` on its own runs each once; a cron expression
+    // with 5 fields (minute first) or 6 to 7 (seconds first, optional year)
+    pub schedule: Option<String>,
+    // under watch, how many scheduled runs may overlap (1 when unset); run once by `--config`,
+    // the scrape's --jobs unless the job sets jobs itself
+    pub concurrency: Option<usize>,
+    pub state: Option<PathBuf>,
+    #[serde(flatten)]
+    pub scrape: ScrapeArgs,
+}
+
+impl JobConfig {
+    pub fn into_scrape_args(self) -> Result<ScrapeArgs> {
+        let mut args = self.scrape;
+        if let Some(concurrency) = self.concurrency {
+            let concurrency = u32::try_from(concurrency)
+                .ok()
+                .filter(|concurrency| *concurrency > 0)
+                .ok_or_else(|| {
+                    Failure::Config(anyhow!("Job {:?} needs a concurrency above 0", self.name))
+                })?;
+            args.jobs = args.jobs.or(Some(concurrency));
+        }
+
+        Ok(args)
+    }
+}
+
+// picked up from the working directory when --rules or --profile is given without --config
+pub const DEFAULT_CONFIG_FILE: &str = "game-data-scraper.toml";
+
+impl Config {
+    pub fn from_file(path: &Path) -> Result<Config> {
+        let mut value: toml::Value = toml::from_str(&fs::read_to_string(path)?)?;
+        secrets::resolve(&mut value)?;
+        Ok(value.try_into()?)
+    }
+
+    pub fn load(path: Option<&Path>) -> Result<Config> {
+        let path = path.unwrap_or(Path::new(DEFAULT_CONFIG_FILE));
+        let config = Config::from_file(path)
+            .with_context(|| format!("Failed to load config file {:?}", path))
+            .map_err(Failure::Config)?;
+
+        Ok(config)
+    }
+
+    pub fn rate_limiter(&self) -> Result<Arc<RateLimiter>> {
+        let limiter = RateLimiter::new(self.rate_limits.clone()).map_err(Failure::Config)?;
+        Ok(Arc::new(limiter))
+    }
+
+    pub fn profile_from(path: Option<&Path>, name: &str) -> Result<Profile> {
+        let mut config = Config::load(path)?;
+
+        config.profiles.remove(name).ok_or_else(|| {
+            let mut known: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+            known.sort();
+            Failure::Config(anyhow!(
+                "Unknown profile {:?}, the config file defines: {}",
+                name,
+                known.join(", ")
+            ))
+            .into()
+        })
+    }
+}
@@ -0,0 +1,186 @@
+use crate::exit::Failure;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const ECB_DAILY_RATES: &str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml";
+
+// exchange rates against a base currency, where one unit of base buys `rates[code]` of code
+#[derive(Deserialize, Debug)]
+pub struct Rates {
+    pub base: String,
+    pub rates: HashMap<String, f64>,
+}
+
+#[derive(Deserialize, Debug)]
+struct EcbEnvelope {
+    #[serde(rename = "Cube")]
+    cube: EcbCube,
+}
+
+#[derive(Deserialize, Debug)]
+struct EcbCube {
+    #[serde(rename = "Cube")]
+    day: EcbDay,
+}
+
+#[derive(Deserialize, Debug)]
+struct EcbDay {
+    #[serde(rename = "Cube")]
+    rates: Vec<EcbRate>,
+}
+
+#[derive(Deserialize, Debug)]
+struct EcbRate {
+    #[serde(rename = "@currency")]
+    currency: String,
+    #[serde(rename = "@rate")]
+    rate: f64,
+}
+
+impl Rates {
+    pub fn from_file(path: &Path) -> Result<Rates> {
+        let contents =
+            fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let rates: Rates = toml::from_str(&contents)
+            .with_context(|| format!("Invalid exchange rates file {:?}", path))?;
+
+        Ok(rates.normalized())
+    }
+
+    pub async fn fetch_ecb(client: &Client) -> Result<Rates> {
+        let response = client.get(ECB_DAILY_RATES).send().await;
+        let xml = match response.and_then(|response| response.error_for_status()) {
+            Ok(response) => response.text().await,
+            Err(err) => Err(err),
+        }
+        .context("Failed to fetch ECB exchange rates")?;
+
+        Rates::from_ecb_xml(&xml)
+    }
+
+    fn from_ecb_xml(xml: &str) -> Result<Rates> {
+        let envelope: EcbEnvelope =
+            quick_xml::de::from_str(xml).context("Invalid ECB exchange rates XML")?;
+
+        let rates = Rates {
+            base: "EUR".to_string(),
+            rates: envelope
+                .cube
+                .day
+                .rates
+                .into_iter()
+                .map(|rate| (rate.currency, rate.rate))
+                .collect(),
+        };
+        Ok(rates.normalized())
+    }
+
+    fn normalized(self) -> Rates {
+        let base = self.base.to_uppercase();
+        let mut rates: HashMap<String, f64> = self
+            .rates
+            .into_iter()
+            .map(|(code, rate)| (code.to_uppercase(), rate))
+            .collect();
+        rates.insert(base.clone(), 1.0);
+
+        Rates { base, rates }
+    }
+
+    pub fn supports(&self, currency: &str) -> bool {
+        self.rates.contains_key(&currency.to_uppercase())
+    }
+
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Option<f64> {
+        let from = self.rates.get(&from.to_uppercase()).filter(|r| **r > 0.0)?;
+        let to = self.rates.get(&to.to_uppercase())?;
+
+        Some((amount / from * to * 100.0).round() / 100.0)
+    }
+
+    pub fn apply(&self, record: &mut ItchData, target: &str) {
+        // nothing to pay is nothing in any currency, even when the price names none
+        record.price_converted = record.price_info.as_ref().and_then(|price| {
+            price.amount.and_then(|amount| match amount == 0.0 {
+                true => Some(0.0),
+                false => self.convert(amount, &price.currency, target),
+            })
+        });
+    }
+}
+
+pub async fn load_rates(client: &Client, file: Option<&Path>, target: &str) -> Result<Rates> {
+    let rates = match file {
+        Some(path) => Rates::from_file(path).map_err(Failure::Config)?,
+        None => Rates::fetch_ecb(client).await?,
+    };
+
+    if !rates.supports(target) {
+        return Err(Failure::Config(anyhow!(
+            "No exchange rate for {:?}, the rates cover: {}",
+            target,
+            {
+                let mut codes: Vec<&str> = rates.rates.keys().map(String::as_str).collect();
+                codes.sort();
+                codes.join(", ")
+            }
+        ))
+        .into());
+    }
+
+    Ok(rates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::price::Price;
+
+    fn converted(rates: &Rates, price: &str) -> Option<f64> {
+        let mut record = ItchData {
+            price: price.to_string(),
+            price_info: Price::parse(price),
+            ..ItchData::default()
+        };
+        rates.apply(&mut record, "EUR");
+        record.price_converted
+    }
+
+    #[test]
+    fn converts_every_separator_style() {
+        let rates = Rates {
+            base: "EUR".to_string(),
+            rates: HashMap::from([("USD".to_string(), 2.0), ("GBP".to_string(), 0.5)]),
+        }
+        .normalized();
+
+        assert_eq!(converted(&rates, "$4.99 USD"), Some(2.5));
+        assert_eq!(converted(&rates, "$1,299.00"), Some(649.5));
+        assert_eq!(converted(&rates, "4,99 €"), Some(4.99));
+        assert_eq!(converted(&rates, "1.299,00 €"), Some(1299.0));
+        assert_eq!(converted(&rates, "£1,000"), Some(2000.0));
+        assert_eq!(converted(&rates, "Free"), Some(0.0));
+        assert_eq!(converted(&rates, ""), None);
+        assert_eq!(converted(&rates, "N/A"), None);
+    }
+
+    #[test]
+    fn converts_a_price_only_the_game_page_had() {
+        let rates = Rates {
+            base: "EUR".to_string(),
+            rates: HashMap::from([("USD".to_string(), 2.0)]),
+        }
+        .normalized();
+        let mut record = ItchData {
+            price_info: Price::from_sources("", "$3.00 USD"),
+            ..ItchData::default()
+        };
+        rates.apply(&mut record, "EUR");
+        assert_eq!(record.price_converted, Some(1.5));
+    }
+}
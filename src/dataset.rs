@@ -0,0 +1,159 @@
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+pub fn load_records(path: &Path) -> Result<Vec<ItchData>> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+
+    let mut records: Vec<ItchData> = if contents.trim_start().starts_with('[') {
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid JSON records in {:?}", path))?
+    } else {
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("Invalid JSON record on line {} of {:?}", i + 1, path))
+            })
+            .collect::<Result<_>>()?
+    };
+    for record in &mut records {
+        record.upgrade();
+    }
+
+    Ok(records)
+}
+
+pub fn load_values(path: &Path) -> Result<Vec<Value>> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+
+    if contents.trim_start().starts_with('[') {
+        return serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid JSON array in {:?}", path));
+    }
+
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("Invalid JSON on line {} of {:?}", i + 1, path))
+        })
+        .collect()
+}
+
+pub fn load_all(paths: &[impl AsRef<Path>]) -> Result<Vec<ItchData>> {
+    let mut records = Vec::new();
+    for path in paths {
+        records.extend(load_records(path.as_ref())?);
+    }
+
+    Ok(records)
+}
+
+// fills {name} placeholders in an output path, e.g. itch-{date}-{page_start}-{page_end}.jsonl
+pub fn expand_path(template: &Path, values: &[(&str, String)]) -> Result<PathBuf> {
+    let template = template.to_string_lossy();
+    let mut expanded = String::new();
+    let mut rest = template.as_ref();
+
+    while let Some(open) = rest.find('{') {
+        let close = rest[open..]
+            .find('}')
+            .map(|close| open + close)
+            .ok_or_else(|| anyhow!("Unclosed placeholder in output path {:?}", template))?;
+        let name = &rest[open + 1..close];
+        let value = values
+            .iter()
+            .find(|(placeholder, _)| *placeholder == name)
+            .map(|(_, value)| value)
+            .ok_or_else(|| {
+                let known: Vec<String> = values.iter().map(|(p, _)| format!("{{{}}}", p)).collect();
+                anyhow!(
+                    "Unknown placeholder {{{}}} in output path {:?}, expected one of {}",
+                    name,
+                    template,
+                    known.join(", ")
+                )
+            })?;
+
+        expanded.push_str(&rest[..open]);
+        expanded.push_str(value);
+        rest = &rest[close + 1..];
+    }
+    expanded.push_str(rest);
+
+    Ok(PathBuf::from(expanded))
+}
+
+pub fn create_parent_dirs(path: &Path) -> Result<()> {
+    match path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        Some(parent) => fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {:?}", parent)),
+        None => Ok(()),
+    }
+}
+
+// serializes batches on separate threads, producing the same output as serde_json::to_string
+pub fn to_json_batched(records: &[ItchData], jobs: usize) -> Result<String> {
+    if jobs <= 1 || records.len() < 2 {
+        return Ok(serde_json::to_string(records)?);
+    }
+
+    let batch_size = records.len().div_ceil(jobs);
+    let batches = std::thread::scope(|scope| {
+        let handles: Vec<_> = records
+            .chunks(batch_size)
+            .map(|batch| {
+                scope.spawn(move || {
+                    batch
+                        .iter()
+                        .map(serde_json::to_string)
+                        .collect::<serde_json::Result<Vec<_>>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("serializer thread panicked"))
+            .collect::<serde_json::Result<Vec<_>>>()
+    })?;
+
+    Ok(format!("[{}]", batches.concat().join(",")))
+}
+
+pub fn write_records(path: Option<&Path>, records: &[ItchData]) -> Result<()> {
+    let ndjson = path
+        .and_then(|path| path.extension())
+        .is_some_and(|ext| ext == "jsonl" || ext == "ndjson");
+
+    let mut out: Box<dyn Write> = match path {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).with_context(|| format!("Failed to create {:?}", path))?,
+        )),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    if ndjson {
+        for record in records {
+            serde_json::to_writer(&mut out, record)?;
+            out.write_all(b"\n")?;
+        }
+    } else {
+        serde_json::to_writer_pretty(&mut out, records)?;
+    }
+    out.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,545 @@
+use crate::parsers::dates::parse_feed_date;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::Result;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection};
+use scraper::Html;
+use serde::Deserialize;
+use std::path::Path;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS games (
+    link TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    price TEXT NOT NULL,
+    price_amount REAL,
+    rating REAL,
+    rating_count INTEGER,
+    pub_date TEXT NOT NULL,
+    update_date TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS game_tags (
+    link TEXT NOT NULL REFERENCES games (link),
+    name TEXT NOT NULL,
+    slug TEXT NOT NULL,
+    PRIMARY KEY (link, name)
+);
+CREATE INDEX IF NOT EXISTS game_tags_name ON game_tags (name);
+
+CREATE TABLE IF NOT EXISTS game_genres (
+    link TEXT NOT NULL REFERENCES games (link),
+    name TEXT NOT NULL,
+    PRIMARY KEY (link, name)
+);
+CREATE INDEX IF NOT EXISTS game_genres_name ON game_genres (name);
+
+CREATE TABLE IF NOT EXISTS game_platforms (
+    link TEXT NOT NULL REFERENCES games (link),
+    platform TEXT NOT NULL,
+    PRIMARY KEY (link, platform)
+);
+CREATE INDEX IF NOT EXISTS game_platforms_platform ON game_platforms (platform);
+
+CREATE TABLE IF NOT EXISTS game_authors (
+    link TEXT NOT NULL REFERENCES games (link),
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    PRIMARY KEY (link, name)
+);
+
+CREATE TABLE IF NOT EXISTS game_links (
+    link TEXT NOT NULL REFERENCES games (link),
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    PRIMARY KEY (link, url)
+);
+";
+
+// the tables holding one row per list entry, keyed by the game's link
+const CHILD_TABLES: [&str; 5] = [
+    "game_tags",
+    "game_genres",
+    "game_platforms",
+    "game_authors",
+    "game_links",
+];
+
+pub fn open(path: &Path) -> Result<Connection> {
+    let mut conn = Connection::open(path)?;
+    let normalized = table_exists(&conn, "game_tags")?;
+    conn.execute_batch(SCHEMA)?;
+    nullable_ratings(&conn)?;
+    if !normalized {
+        fill_child_tables(&mut conn)?;
+    }
+    Ok(conn)
+}
+
+fn table_exists(conn: &Connection, name: &str) -> Result<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [name],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+// databases created before the list tables existed only have games, whose data column holds
+// everything needed to fill them
+fn fill_child_tables(conn: &mut Connection) -> Result<()> {
+    let tx = conn.transaction()?;
+    let records = tx
+        .prepare("SELECT data FROM games")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .map(|data| Ok(serde_json::from_str(&data?)?))
+        .collect::<Result<Vec<ItchData>>>()?;
+    for record in &records {
+        insert_children(&tx, record)?;
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+// databases created before ratings were optional have NOT NULL rating columns holding 0 for
+// unrated games; sqlite can't drop the constraint in place, so the table is copied over
+fn nullable_ratings(conn: &Connection) -> Result<()> {
+    let not_null: bool = conn.query_row(
+        "SELECT \"notnull\" FROM pragma_table_info('games') WHERE name = 'rating'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !not_null {
+        return Ok(());
+    }
+
+    conn.execute_batch(&format!(
+        "BEGIN;
+         ALTER TABLE games RENAME TO games_before_nullable_ratings;
+         {}
+         INSERT INTO games
+             SELECT link, title, price, price_amount,
+                    CASE WHEN rating_count = 0 THEN NULL ELSE rating END,
+                    NULLIF(rating_count, 0),
+                    pub_date, update_date, data
+             FROM games_before_nullable_ratings;
+         DROP TABLE games_before_nullable_ratings;
+         COMMIT;",
+        SCHEMA
+    ))?;
+    Ok(())
+}
+
+pub fn insert_records(conn: &mut Connection, records: &[ItchData]) -> Result<()> {
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT OR REPLACE INTO games
+                (link, title, price, price_amount, rating, rating_count, pub_date, update_date, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        )?;
+
+        for record in records {
+            stmt.execute(params![
+                record.link,
+                record.plain_title,
+                record.price,
+                record.price_amount(),
+                record.rating.as_ref().map(|rating| rating.score),
+                record.rating.as_ref().map(|rating| rating.count),
+                sortable_date(&record.pub_date),
+                sortable_date(&record.update_date),
+                serde_json::to_string(record)?,
+            ])?;
+            insert_children(&tx, record)?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+// replaces whatever an earlier scrape of the same game left, so re-scrapes are upserts
+fn insert_children(conn: &Connection, record: &ItchData) -> Result<()> {
+    for table in CHILD_TABLES {
+        conn.execute(
+            &format!("DELETE FROM {} WHERE link = ?1", table),
+            [&record.link],
+        )?;
+    }
+
+    let link = &record.link;
+    for tag in &record.tags {
+        conn.execute(
+            "INSERT OR IGNORE INTO game_tags (link, name, slug) VALUES (?1, ?2, ?3)",
+            params![link, tag.name, tag.slug],
+        )?;
+    }
+    for genre in &record.genres {
+        conn.execute(
+            "INSERT OR IGNORE INTO game_genres (link, name) VALUES (?1, ?2)",
+            params![link, genre.name],
+        )?;
+    }
+    for platform in &record.platforms {
+        conn.execute(
+            "INSERT OR IGNORE INTO game_platforms (link, platform) VALUES (?1, ?2)",
+            params![link, platform],
+        )?;
+    }
+    for author in &record.authors {
+        conn.execute(
+            "INSERT OR IGNORE INTO game_authors (link, name, url) VALUES (?1, ?2, ?3)",
+            params![link, author.name, author.url],
+        )?;
+    }
+    for game_link in &record.links {
+        conn.execute(
+            "INSERT OR IGNORE INTO game_links (link, name, url) VALUES (?1, ?2, ?3)",
+            params![link, game_link.name, game_link.url],
+        )?;
+    }
+
+    Ok(())
+}
+
+// a flat copy of games with list columns as JSON arrays and an FTS5 index over title, description
+// and tags, the layout datasette facets and searches on; rebuilt from games on every export
+pub fn add_datasette_tables(conn: &mut Connection) -> Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute_batch(
+        "DROP TABLE IF EXISTS game_details_fts;
+         DROP TABLE IF EXISTS game_details;
+         CREATE TABLE game_details (
+             link TEXT PRIMARY KEY,
+             title TEXT NOT NULL,
+             description TEXT NOT NULL,
+             price TEXT NOT NULL,
+             price_amount REAL,
+             rating REAL,
+             rating_count INTEGER,
+             status TEXT NOT NULL,
+             average_session TEXT NOT NULL,
+             authors TEXT NOT NULL,
+             genres TEXT NOT NULL,
+             tags TEXT NOT NULL,
+             platforms TEXT NOT NULL,
+             made_with TEXT NOT NULL,
+             pub_date TEXT NOT NULL,
+             update_date TEXT NOT NULL
+         );",
+    )?;
+    {
+        let mut select = tx.prepare("SELECT data FROM games")?;
+        let mut insert = tx.prepare(
+            "INSERT INTO game_details VALUES
+                (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+        )?;
+
+        let records = select
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|data| Ok(serde_json::from_str(&data?)?))
+            .collect::<Result<Vec<ItchData>>>()?;
+        for mut record in records {
+            // rows stored before price_info was scraped get it parsed from the price text
+            record.upgrade();
+            insert.execute(params![
+                record.link,
+                record.plain_title,
+                plain_text(&record.description),
+                record.price,
+                record.price_amount(),
+                record.rating.as_ref().map(|rating| rating.score),
+                record.rating.as_ref().map(|rating| rating.count),
+                record.status,
+                record.average_session,
+                serde_json::to_string(
+                    &record
+                        .authors
+                        .iter()
+                        .map(|author| &author.name)
+                        .collect::<Vec<_>>()
+                )?,
+                serde_json::to_string(&record.genre_names())?,
+                serde_json::to_string(&record.tag_names())?,
+                serde_json::to_string(&record.platforms)?,
+                serde_json::to_string(&record.made_with)?,
+                sortable_date(&record.pub_date),
+                sortable_date(&record.update_date),
+            ])?;
+        }
+    }
+    tx.execute_batch(
+        "CREATE VIRTUAL TABLE game_details_fts USING fts5(
+             title, description, tags, content = 'game_details'
+         );
+         INSERT INTO game_details_fts (game_details_fts) VALUES ('rebuild');",
+    )?;
+    tx.commit()?;
+
+    Ok(())
+}
+
+fn plain_text(html: &str) -> String {
+    Html::parse_fragment(html)
+        .root_element()
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn sortable_date(date: &str) -> String {
+    parse_feed_date(date)
+        .map(|date| date.to_rfc3339())
+        .unwrap_or_else(|| date.to_string())
+}
+
+fn like_pattern(search: &str) -> String {
+    let escaped = search
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+#[derive(
+    Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, async_graphql::Enum, utoipa::ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SortField {
+    #[default]
+    Updated,
+    Published,
+    Rating,
+    Title,
+}
+
+#[derive(Debug, Default)]
+pub struct GameQuery {
+    pub tags: Vec<String>,
+    pub genres: Vec<String>,
+    pub platforms: Vec<String>,
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    pub published_after: Option<String>,
+    pub published_before: Option<String>,
+    pub search: Option<String>,
+    pub sort: SortField,
+    pub descending: bool,
+    pub limit: u32,
+    pub offset: u32,
+}
+
+pub fn query_games(conn: &Connection, query: &GameQuery) -> Result<(u64, Vec<ItchData>)> {
+    let mut conditions = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    for value in &query.platforms {
+        conditions.push(
+            "EXISTS (SELECT 1 FROM json_each(games.data, '$.platforms') WHERE value = ? COLLATE NOCASE)"
+                .to_string(),
+        );
+        values.push(Value::Text(value.clone()));
+    }
+    for value in &query.genres {
+        conditions.push(
+            "EXISTS (SELECT 1 FROM json_each(games.data, '$.genres') WHERE
+                 (CASE type WHEN 'object' THEN json_extract(value, '$.name') ELSE value END) = ? COLLATE NOCASE)"
+                .to_string(),
+        );
+        values.push(Value::Text(value.clone()));
+    }
+    // tags are {name, slug, url} objects, or bare names in rows stored before slugs were kept
+    for value in &query.tags {
+        conditions.push(
+            "EXISTS (SELECT 1 FROM json_each(games.data, '$.tags') WHERE
+                 (CASE type WHEN 'object' THEN json_extract(value, '$.name') ELSE value END) = ? COLLATE NOCASE
+                 OR (type = 'object' AND json_extract(value, '$.slug') = ? COLLATE NOCASE))"
+                .to_string(),
+        );
+        values.push(Value::Text(value.clone()));
+        values.push(Value::Text(value.clone()));
+    }
+
+    if let Some(min) = query.min_price {
+        conditions.push("price_amount >= ?".to_string());
+        values.push(Value::Real(min));
+    }
+    if let Some(max) = query.max_price {
+        conditions.push("price_amount <= ?".to_string());
+        values.push(Value::Real(max));
+    }
+    if let Some(after) = &query.published_after {
+        conditions.push("pub_date >= ?".to_string());
+        values.push(Value::Text(sortable_date(after)));
+    }
+    if let Some(before) = &query.published_before {
+        conditions.push("pub_date < ?".to_string());
+        values.push(Value::Text(sortable_date(before)));
+    }
+    if let Some(search) = &query.search {
+        conditions.push(
+            "(title LIKE ? ESCAPE '\\' OR json_extract(data, '$.description') LIKE ? ESCAPE '\\')"
+                .to_string(),
+        );
+        values.push(Value::Text(like_pattern(search)));
+        values.push(Value::Text(like_pattern(search)));
+    }
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
+
+    let total: u64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM games {}", where_clause),
+        params_from_iter(&values),
+        |row| row.get(0),
+    )?;
+
+    let order_column = match query.sort {
+        SortField::Updated => "update_date",
+        SortField::Published => "pub_date",
+        SortField::Rating => "rating",
+        SortField::Title => "title",
+    };
+    let direction = if query.descending { "DESC" } else { "ASC" };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT data FROM games {} ORDER BY {} {}, link LIMIT {} OFFSET {}",
+        where_clause, order_column, direction, query.limit, query.offset
+    ))?;
+    let games = stmt
+        .query_map(params_from_iter(&values), |row| row.get::<_, String>(0))?
+        .map(|data| Ok(serde_json::from_str(&data?)?))
+        .collect::<Result<Vec<ItchData>>>()?;
+
+    Ok((total, games))
+}
+
+pub fn get_game(conn: &Connection, link: &str) -> Result<Option<ItchData>> {
+    let mut stmt = conn.prepare("SELECT data FROM games WHERE link = ?1")?;
+    let mut rows = stmt.query([link])?;
+
+    match rows.next()? {
+        Some(row) => Ok(Some(serde_json::from_str(&row.get::<_, String>(0)?)?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::itch_game_info_parser::Tag;
+    use crate::parsers::price::Price;
+
+    fn game(link: &str, tags: &[&str], platforms: &[&str]) -> ItchData {
+        ItchData {
+            link: link.to_string(),
+            plain_title: link.to_string(),
+            tags: tags
+                .iter()
+                .map(|name| Tag {
+                    name: name.to_string(),
+                    slug: name.to_lowercase().replace(' ', "-"),
+                    ..Tag::default()
+                })
+                .collect(),
+            platforms: platforms.iter().map(|name| name.to_string()).collect(),
+            ..ItchData::default()
+        }
+    }
+
+    fn links(conn: &Connection, query: GameQuery) -> Vec<String> {
+        let (_, games) = query_games(
+            conn,
+            &GameQuery {
+                limit: 10,
+                sort: SortField::Title,
+                ..query
+            },
+        )
+        .unwrap();
+        games.into_iter().map(|game| game.link).collect()
+    }
+
+    fn database() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        insert_records(
+            &mut conn,
+            &[
+                game("a", &["Horror", "Pixel Art"], &["Linux"]),
+                game("b", &["Horror"], &["Windows"]),
+                game("c", &["Pixel Art"], &["Linux"]),
+            ],
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn filters_on_every_tag_given() {
+        let conn = database();
+        let query = GameQuery {
+            tags: vec!["horror".to_string(), "pixel-art".to_string()],
+            ..GameQuery::default()
+        };
+        assert_eq!(links(&conn, query), ["a"]);
+    }
+
+    #[test]
+    fn filters_on_a_tag_and_a_platform() {
+        let conn = database();
+        let query = GameQuery {
+            tags: vec!["horror".to_string()],
+            platforms: vec!["linux".to_string()],
+            ..GameQuery::default()
+        };
+        assert_eq!(links(&conn, query), ["a"]);
+    }
+
+    #[test]
+    fn max_price_includes_free_games() {
+        let mut conn = database();
+        let priced = |link: &str, price: &str| ItchData {
+            price: price.to_string(),
+            price_info: Price::parse(price),
+            ..game(link, &[], &[])
+        };
+        insert_records(&mut conn, &[priced("d", "Free"), priced("e", "$10.00")]).unwrap();
+
+        let query = GameQuery {
+            max_price: Some(5.0),
+            ..GameQuery::default()
+        };
+        assert_eq!(links(&conn, query), ["d"]);
+    }
+
+    #[test]
+    fn datasette_table_keeps_free_prices() {
+        let mut conn = database();
+        let free = ItchData {
+            price: "Free".to_string(),
+            price_info: Price::parse("Free"),
+            ..game("d", &[], &[])
+        };
+        insert_records(&mut conn, &[free]).unwrap();
+        add_datasette_tables(&mut conn).unwrap();
+
+        let amount: Option<f64> = conn
+            .query_row(
+                "SELECT price_amount FROM game_details WHERE link = 'd'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(amount, Some(0.0));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::parsers::itch_game_info_parser::ItchHTMLDataFormatError;
+use miette::{Diagnostic, GraphicalReportHandler, NamedSource, SourceSpan};
+use thiserror::Error;
+
+const SNIPPET_CONTEXT: usize = 240;
+
+#[derive(Error, Debug, Diagnostic)]
+#[error("Failed to parse {field} on {url}")]
+#[diagnostic(code(game_data_scraper::parse))]
+pub struct ParseDiagnostic {
+    pub url: String,
+    pub field: String,
+    reason: String,
+    #[source_code]
+    snippet: NamedSource<String>,
+    #[label("{reason}")]
+    span: Option<SourceSpan>,
+    #[help]
+    help: Option<String>,
+}
+
+impl ParseDiagnostic {
+    pub fn from_html_error(
+        url: &str,
+        html: &str,
+        err: &ItchHTMLDataFormatError,
+    ) -> ParseDiagnostic {
+        let (field, needle, help) = match err {
+            ItchHTMLDataFormatError::UnknownDataType { data } => (
+                "more info table".to_string(),
+                data.clone(),
+                Some(format!("{:?} is not a known row label", data)),
+            ),
+            ItchHTMLDataFormatError::MissingElements => (
+                "more info table".to_string(),
+                "game_info_panel_widget".to_string(),
+                Some("expected every table row to contain exactly two cells".to_string()),
+            ),
+            ItchHTMLDataFormatError::MissingData { data_type } => (
+                data_type.label().to_string(),
+                format!(">{}<", data_type.label()),
+                Some("the page layout may have changed".to_string()),
+            ),
+            ItchHTMLDataFormatError::InvalidData { data_type, found } => {
+                (data_type.label().to_string(), found.clone(), None)
+            }
+        };
+
+        let (snippet, span) = snippet_around(html, &needle);
+
+        ParseDiagnostic {
+            url: url.to_string(),
+            field,
+            reason: err.to_string(),
+            snippet: NamedSource::new(url, snippet),
+            span,
+            help,
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        match GraphicalReportHandler::new().render_report(&mut out, self) {
+            Ok(()) => out,
+            Err(_) => self.to_string(),
+        }
+    }
+}
+
+// pages are often minified onto a handful of lines, so only keep a window of html around the match
+fn snippet_around(html: &str, needle: &str) -> (String, Option<SourceSpan>) {
+    let Some(offset) = html.find(needle).filter(|_| !needle.is_empty()) else {
+        return (String::new(), None);
+    };
+
+    let start = floor_char_boundary(html, offset.saturating_sub(SNIPPET_CONTEXT));
+    let end = floor_char_boundary(
+        html,
+        (offset + needle.len() + SNIPPET_CONTEXT).min(html.len()),
+    );
+
+    (
+        html[start..end].to_string(),
+        Some((offset - start, needle.len()).into()),
+    )
+}
+
+fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
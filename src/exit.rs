@@ -0,0 +1,49 @@
+use reqwest::StatusCode;
+use std::process::ExitCode;
+use thiserror::Error;
+
+pub const FATAL: u8 = 1;
+pub const ITEM_ERRORS: u8 = 3;
+pub const ABORTED: u8 = 4;
+pub const CONFIG: u8 = 5;
+pub const LAYOUT_DRIFT: u8 = 6;
+pub const FAILED_ON_ERROR: u8 = 7;
+
+#[derive(Error, Debug)]
+pub enum Failure {
+    #[error("Scrape completed with {0} item-level errors")]
+    ItemErrors(usize),
+
+    #[error("Scrape aborted: {0}")]
+    Aborted(String),
+
+    #[error("Invalid configuration: {0:#}")]
+    Config(anyhow::Error),
+
+    #[error("Page layout drifted, {0} problem(s) found")]
+    LayoutDrift(usize),
+
+    // item errors escalated by --fail-on-error, once every record has been written
+    #[error("Scrape failed with {0} item-level errors (--fail-on-error)")]
+    FailedOnError(usize),
+}
+
+pub fn exit_code(err: &anyhow::Error) -> ExitCode {
+    let code = match err.downcast_ref::<Failure>() {
+        Some(Failure::ItemErrors(_)) => ITEM_ERRORS,
+        Some(Failure::Aborted(_)) => ABORTED,
+        Some(Failure::Config(_)) => CONFIG,
+        Some(Failure::LayoutDrift(_)) => LAYOUT_DRIFT,
+        Some(Failure::FailedOnError(_)) => FAILED_ON_ERROR,
+        None if rate_limited(err) => ABORTED,
+        None => FATAL,
+    };
+
+    ExitCode::from(code)
+}
+
+fn rate_limited(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .any(|cause| cause.status() == Some(StatusCode::TOO_MANY_REQUESTS))
+}
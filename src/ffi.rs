@@ -0,0 +1,86 @@
+use crate::parsers::itch_game_info_parser::parse_itch_game_page_data;
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Parses an itch.io game page and returns its info table as a JSON string.
+///
+/// Returns null on failure, with the reason available from `gds_last_error`.
+/// The returned string must be released with `gds_string_free`.
+///
+/// # Safety
+///
+/// `html` must be null or point to a NUL-terminated string that stays valid for the call.
+#[no_mangle]
+pub unsafe extern "C" fn gds_parse_itch_game_page(html: *const c_char) -> *mut c_char {
+    if html.is_null() {
+        set_last_error("html is null".to_string());
+        return ptr::null_mut();
+    }
+
+    let html = match CStr::from_ptr(html).to_str() {
+        Ok(html) => html,
+        Err(err) => {
+            set_last_error(format!("html is not valid UTF-8: {}", err));
+            return ptr::null_mut();
+        }
+    };
+
+    let json = parse_itch_game_page_data(html)
+        .map_err(|err| err.to_string())
+        .and_then(|data| serde_json::to_string(&data).map_err(|err| err.to_string()));
+
+    match json.map(CString::new) {
+        Ok(Ok(json)) => {
+            clear_last_error();
+            json.into_raw()
+        }
+        Ok(Err(err)) => {
+            set_last_error(err.to_string());
+            ptr::null_mut()
+        }
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the error from the last failed call on this thread, or null.
+///
+/// The string is owned by the library and stays valid until the next call on this thread.
+#[no_mangle]
+pub extern "C" fn gds_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| {
+        error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |error| error.as_ptr())
+    })
+}
+
+/// Releases a string returned by this library.
+///
+/// # Safety
+///
+/// `string` must be null or a pointer returned by this library that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn gds_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+fn set_last_error(message: String) {
+    // interior NULs can't be represented, so the message is cut there
+    let message = message.split('\0').next().unwrap_or_default().to_string();
+    LAST_ERROR.with(|error| *error.borrow_mut() = CString::new(message).ok());
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|error| *error.borrow_mut() = None);
+}
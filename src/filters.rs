@@ -0,0 +1,231 @@
+use crate::parsers::dates::parse_feed_date;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use crate::scrapers::options::{DateField, DateWindow};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::Args;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct Filter {
+    pub tags: Vec<String>,
+    pub genres: Vec<String>,
+    pub platforms: Vec<String>,
+    pub free: Option<bool>,
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    pub min_rating: Option<f32>,
+    pub min_ratings: Option<i32>,
+}
+
+#[derive(Args, Debug, Default, Clone)]
+pub struct FilterArgs {
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    #[arg(long = "genre", value_name = "GENRE")]
+    pub genres: Vec<String>,
+
+    #[arg(long = "platform", value_name = "PLATFORM")]
+    pub platforms: Vec<String>,
+
+    #[arg(long)]
+    pub free_only: bool,
+
+    #[arg(long, value_name = "AMOUNT")]
+    pub min_price: Option<f64>,
+
+    #[arg(long, value_name = "AMOUNT")]
+    pub max_price: Option<f64>,
+
+    #[arg(long, value_name = "SCORE")]
+    pub min_rating: Option<f32>,
+
+    #[arg(long, value_name = "COUNT")]
+    pub min_ratings: Option<i32>,
+}
+
+impl From<FilterArgs> for Filter {
+    fn from(args: FilterArgs) -> Filter {
+        Filter {
+            tags: args.tags,
+            genres: args.genres,
+            platforms: args.platforms,
+            free: args.free_only.then_some(true),
+            min_price: args.min_price,
+            max_price: args.max_price,
+            min_rating: args.min_rating,
+            min_ratings: args.min_ratings,
+        }
+    }
+}
+
+impl Filter {
+    pub fn matches(&self, record: &ItchData) -> bool {
+        self.tags
+            .iter()
+            .all(|wanted| record.tags.iter().any(|tag| tag.matches(wanted)))
+            && self.genres.iter().all(|wanted| {
+                record
+                    .genres
+                    .iter()
+                    .any(|genre| genre.name.eq_ignore_ascii_case(wanted))
+            })
+            && self.platforms.iter().all(|wanted| {
+                record
+                    .platforms
+                    .iter()
+                    .any(|platform| same_platform(platform, wanted))
+            })
+            && self.free.is_none_or(|free| {
+                record.price_info.as_ref().is_some_and(|price| price.free) == free
+            })
+            && self.price_in_range(record.price_amount())
+            && self.min_rating.is_none_or(|min| {
+                record
+                    .rating
+                    .as_ref()
+                    .is_some_and(|rating| rating.score >= min)
+            })
+            && self
+                .min_ratings
+                .is_none_or(|min| record.rating.as_ref().map_or(0, |rating| rating.count) >= min)
+    }
+
+    fn price_in_range(&self, amount: Option<f64>) -> bool {
+        if self.min_price.is_none() && self.max_price.is_none() {
+            return true;
+        }
+
+        amount.is_some_and(|amount| {
+            self.min_price.is_none_or(|min| amount >= min)
+                && self.max_price.is_none_or(|max| amount <= max)
+        })
+    }
+}
+
+// a named rule set from the config file; its own conditions and every nested `all` rule must
+// match, at least one `any` rule must match, and the `not` rule must not
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct Rule {
+    #[serde(flatten)]
+    pub filter: Filter,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub date_field: Option<DateField>,
+    pub all: Vec<Rule>,
+    pub any: Vec<Rule>,
+    pub not: Option<Box<Rule>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Condition {
+    Filter(Filter),
+    Window(DateWindow),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Rule {
+    pub fn compile(&self) -> Result<Condition> {
+        let mut conditions = vec![Condition::Filter(self.filter.clone())];
+
+        let window = DateWindow::parse(
+            self.date_field.unwrap_or_default(),
+            self.since.as_deref(),
+            self.until.as_deref(),
+        )?;
+        if window.is_bounded() {
+            conditions.push(Condition::Window(window));
+        }
+
+        for rule in &self.all {
+            conditions.push(rule.compile()?);
+        }
+        if !self.any.is_empty() {
+            let any = self.any.iter().map(Rule::compile).collect::<Result<_>>()?;
+            conditions.push(Condition::Any(any));
+        }
+        if let Some(rule) = &self.not {
+            conditions.push(Condition::Not(Box::new(rule.compile()?)));
+        }
+
+        Ok(Condition::All(conditions))
+    }
+}
+
+impl Condition {
+    pub fn matches(&self, record: &ItchData) -> bool {
+        match self {
+            Condition::Filter(filter) => filter.matches(record),
+            Condition::Window(window) => window.contains(record_date(record, window.field)),
+            Condition::All(conditions) => conditions.iter().all(|c| c.matches(record)),
+            Condition::Any(conditions) => conditions.iter().any(|c| c.matches(record)),
+            Condition::Not(condition) => !condition.matches(record),
+        }
+    }
+}
+
+pub(crate) fn record_date(record: &ItchData, field: DateField) -> Option<DateTime<Utc>> {
+    let date = match field {
+        DateField::Published => &record.pub_date,
+        DateField::Created => &record.create_date,
+        DateField::Updated => &record.update_date,
+    };
+
+    parse_feed_date(date)
+}
+
+// itch's name for each platform, the other names accepted for it and its browse path segment;
+// the local filter and the feed url pushdown both read this so `--platform mac` means the same
+// thing on each side
+struct Platform {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    segment: &'static str,
+}
+
+const PLATFORMS: [Platform; 4] = [
+    Platform {
+        name: "Windows",
+        aliases: &["windows"],
+        segment: "platform-windows",
+    },
+    Platform {
+        name: "macOS",
+        aliases: &["macos", "osx", "mac"],
+        segment: "platform-osx",
+    },
+    Platform {
+        name: "Linux",
+        aliases: &["linux"],
+        segment: "platform-linux",
+    },
+    Platform {
+        name: "Android",
+        aliases: &["android"],
+        segment: "platform-android",
+    },
+];
+
+fn platform(name: &str) -> Option<&'static Platform> {
+    let name = name.trim().to_lowercase();
+    PLATFORMS
+        .iter()
+        .find(|platform| platform.aliases.contains(&name.as_str()))
+}
+
+fn same_platform(a: &str, b: &str) -> bool {
+    match (platform(a), platform(b)) {
+        (Some(a), Some(b)) => a.name == b.name,
+        _ => a.trim().eq_ignore_ascii_case(b.trim()),
+    }
+}
+
+// the /games/platform-* segment itch filters a listing by, None for platforms it has no page for
+pub fn platform_segment(name: &str) -> Option<&'static str> {
+    platform(name).map(|platform| platform.segment)
+}
@@ -0,0 +1,138 @@
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+// one row per record per scrape, never updated and only deleted by clean, so a game's past
+// states stay queryable
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS observations (
+    id INTEGER PRIMARY KEY,
+    link TEXT NOT NULL,
+    title TEXT NOT NULL,
+    scraped_at TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS observations_by_link ON observations (link, scraped_at);
+";
+
+pub struct Observation {
+    pub link: String,
+    pub scraped_at: String,
+    pub record: Value,
+}
+
+pub fn open(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path)?;
+    conn.execute_batch(SCHEMA)?;
+    Ok(conn)
+}
+
+pub fn append(path: &Path, records: &[ItchData], scraped_at: DateTime<Utc>) -> Result<()> {
+    // utc with a fixed precision, so the text column sorts chronologically
+    let scraped_at = scraped_at.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let mut conn = open(path)?;
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO observations (link, title, scraped_at, data) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for record in records {
+            stmt.execute(params![
+                record.link,
+                record.plain_title,
+                scraped_at,
+                serde_json::to_string(record)?,
+            ])?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+// a game is looked up by link, or by title when no link matches
+pub fn observations(conn: &Connection, game: &str) -> Result<Vec<Observation>> {
+    let mut stmt = conn.prepare(
+        "SELECT link, scraped_at, data FROM observations
+         WHERE link = ?1
+            OR (title = ?1 COLLATE NOCASE
+                AND NOT EXISTS (SELECT 1 FROM observations WHERE link = ?1))
+         ORDER BY scraped_at, id",
+    )?;
+    let rows = stmt.query_map([game], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+
+    let mut observations = Vec::new();
+    for row in rows {
+        let (link, scraped_at, data) = row?;
+        observations.push(Observation {
+            link,
+            scraped_at,
+            record: serde_json::from_str(&data)?,
+        });
+    }
+
+    Ok(observations)
+}
+
+pub struct Pruned {
+    pub removed: usize,
+    pub total: usize,
+    pub bytes: u64,
+}
+
+// the oldest observations go first: everything scraped before the cutoff, then as many more as
+// it takes to bring the file under max_bytes, estimated from the average row size
+pub fn prune(
+    path: &Path,
+    cutoff: Option<DateTime<Utc>>,
+    max_bytes: Option<u64>,
+    dry_run: bool,
+) -> Result<Pruned> {
+    let conn = open(path)?;
+    let size = fs::metadata(path)?.len();
+    let total: usize = conn.query_row("SELECT COUNT(*) FROM observations", [], |row| row.get(0))?;
+
+    let mut removed: usize = match cutoff {
+        Some(cutoff) => conn.query_row(
+            "SELECT COUNT(*) FROM observations WHERE scraped_at < ?1",
+            [cutoff.to_rfc3339_opts(SecondsFormat::Secs, true)],
+            |row| row.get(0),
+        )?,
+        None => 0,
+    };
+    if let Some(max_bytes) = max_bytes.filter(|max_bytes| size > *max_bytes) {
+        let over = (size - max_bytes) as f64 / size as f64 * total as f64;
+        removed = removed.max((over.ceil() as usize).min(total));
+    }
+    let bytes = if total == 0 {
+        0
+    } else {
+        size * removed as u64 / total as u64
+    };
+
+    if !dry_run && removed > 0 {
+        conn.execute(
+            "DELETE FROM observations WHERE id IN
+                 (SELECT id FROM observations ORDER BY scraped_at, id LIMIT ?1)",
+            [removed],
+        )?;
+        // deleted rows only free pages inside the file until it's rebuilt
+        conn.execute_batch("VACUUM")?;
+    }
+
+    Ok(Pruned {
+        removed,
+        total,
+        bytes,
+    })
+}
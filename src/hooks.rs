@@ -0,0 +1,176 @@
+use crate::exit::Failure;
+use anyhow::{anyhow, Context, Result};
+use serde_json::json;
+use std::path::Path;
+use std::process::Command;
+use tempfile::NamedTempFile;
+
+const PLACEHOLDERS: &[&str] = &["outfile", "count", "errors", "url", "site", "report"];
+
+pub struct RunSummary {
+    pub records: usize,
+    pub errors: usize,
+}
+
+pub struct Hooks<'a> {
+    pub on_complete: Option<&'a str>,
+    pub on_error: Option<&'a str>,
+    pub url: String,
+    pub site: &'static str,
+    pub outfile: Option<&'a Path>,
+}
+
+impl Hooks<'_> {
+    // templates are checked before scraping, a typo shouldn't cost a whole run
+    pub fn check(&self) -> Result<()> {
+        for (flag, template) in [
+            ("--on-complete", self.on_complete),
+            ("--on-error", self.on_error),
+        ] {
+            let Some(template) = template else {
+                continue;
+            };
+            for name in placeholders(template)? {
+                if !PLACEHOLDERS.contains(&name) || (name == "report" && flag == "--on-complete") {
+                    return Err(Failure::Config(anyhow!(
+                        "Unknown placeholder {{{}}} in {} {:?}",
+                        name,
+                        flag,
+                        template
+                    ))
+                    .into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // on-complete runs once the output is written, on-error whenever the run exits non-zero,
+    // so a run with item errors gets both
+    pub fn run(&self, result: &Result<RunSummary>) -> Result<()> {
+        let (records, errors) = match result {
+            Ok(summary) => (summary.records.to_string(), summary.errors),
+            Err(_) => (String::new(), 0),
+        };
+        let mut values = vec![
+            (
+                "outfile",
+                self.outfile
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default(),
+            ),
+            ("count", records),
+            ("errors", errors.to_string()),
+            ("url", self.url.clone()),
+            ("site", self.site.to_string()),
+        ];
+
+        if let (Ok(_), Some(template)) = (result, self.on_complete) {
+            execute("--on-complete", template, &values)?;
+        }
+
+        let failed = match result {
+            Ok(summary) => summary.errors > 0,
+            Err(_) => true,
+        };
+        if let (true, Some(template)) = (failed, self.on_error) {
+            let message = match result {
+                Ok(summary) => Failure::ItemErrors(summary.errors).to_string(),
+                Err(err) => format!("{:#}", err),
+            };
+            // removed when dropped, once the hook has run
+            let report = self.write_report(&message, errors)?;
+            values.push(("report", report.path().display().to_string()));
+            // the run already failed, the hook's own failure is only worth a mention
+            if let Err(err) = execute("--on-error", template, &values) {
+                eprintln!("{:#}", err);
+            }
+        }
+
+        Ok(())
+    }
+
+    // created with a random name and never through an existing file, the temp dir may be shared
+    fn write_report(&self, message: &str, errors: usize) -> Result<NamedTempFile> {
+        let mut file = tempfile::Builder::new()
+            .prefix("game-data-scraper-report-")
+            .suffix(".json")
+            .tempfile()
+            .context("Failed to create the error report")?;
+        let report = json!({
+            "url": self.url,
+            "site": self.site,
+            "outfile": self.outfile,
+            "errors": errors,
+            "error": message,
+        });
+        serde_json::to_writer_pretty(file.as_file_mut(), &report)
+            .with_context(|| format!("Failed to write {:?}", file.path()))?;
+        Ok(file)
+    }
+}
+
+fn placeholders(template: &str) -> Result<Vec<&str>> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let close = rest[open..]
+            .find('}')
+            .map(|close| open + close)
+            .ok_or_else(|| anyhow!("Unclosed placeholder in hook {:?}", template))?;
+        names.push(&rest[open + 1..close]);
+        rest = &rest[close + 1..];
+    }
+    Ok(names)
+}
+
+// values are quoted, so paths with spaces or quotes reach the program as one argument
+fn expand(template: &str, values: &[(&str, String)]) -> String {
+    let mut command = String::new();
+    let mut rest = template;
+    while let Some((open, close)) = rest
+        .find('{')
+        .and_then(|open| Some((open, open + rest[open..].find('}')?)))
+    {
+        let name = &rest[open + 1..close];
+        command.push_str(&rest[..open]);
+        match values.iter().find(|(placeholder, _)| *placeholder == name) {
+            Some((_, value)) => command.push_str(&quote(value)),
+            None => command.push_str(&rest[open..=close]),
+        }
+        rest = &rest[close + 1..];
+    }
+    command.push_str(rest);
+    command
+}
+
+#[cfg(unix)]
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(not(unix))]
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+fn execute(flag: &str, template: &str, values: &[(&str, String)]) -> Result<()> {
+    let command = expand(template, values);
+    #[cfg(unix)]
+    let status = Command::new("sh").arg("-c").arg(&command).status();
+    #[cfg(not(unix))]
+    let status = Command::new("cmd").arg("/C").arg(&command).status();
+
+    let status = status.with_context(|| format!("Failed to run {} hook {:?}", flag, command))?;
+    if !status.success() {
+        return Err(anyhow!(
+            "{} hook {:?} exited with {}",
+            flag,
+            command,
+            status
+        ));
+    }
+
+    Ok(())
+}
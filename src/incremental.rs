@@ -0,0 +1,157 @@
+use crate::notifiers::PriceDrop;
+use crate::parsers::dates::parse_feed_date;
+use crate::parsers::price::Price;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct SeenRecord {
+    update_date: String,
+    price: String,
+    // the parsed price_info amount; state written before it was kept is parsed from price
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    amount: Option<f64>,
+}
+
+impl SeenRecord {
+    fn amount(&self) -> Option<f64> {
+        self.amount
+            .or_else(|| Price::parse(&self.price).and_then(|price| price.amount))
+    }
+}
+
+// keyed by record link
+type SeenRecords = HashMap<String, SeenRecord>;
+
+pub struct IncrementalState {
+    path: Option<PathBuf>,
+    seen: SeenRecords,
+}
+
+impl IncrementalState {
+    pub fn load(path: Option<PathBuf>) -> Result<IncrementalState> {
+        let seen = match &path {
+            Some(path) if path.exists() => serde_json::from_str(&fs::read_to_string(path)?)?,
+            _ => SeenRecords::new(),
+        };
+
+        Ok(IncrementalState { path, seen })
+    }
+
+    // records whose link has never been seen, an updated game isn't new
+    pub fn unseen<'a>(&self, records: &'a [ItchData]) -> Vec<&'a ItchData> {
+        records
+            .iter()
+            .filter(|record| !self.seen.contains_key(&record.link))
+            .collect()
+    }
+
+    pub fn take_changed<'a>(
+        &mut self,
+        records: &'a [ItchData],
+    ) -> (Vec<&'a ItchData>, Vec<PriceDrop<'a>>) {
+        let mut changed = Vec::new();
+        let mut price_drops = Vec::new();
+
+        for record in records {
+            let previous = self.seen.get(&record.link);
+            // itch sales don't bump updateDate, so a record only counts as unchanged when its
+            // price is the same too
+            if previous.is_some_and(|seen| {
+                same_date(&seen.update_date, &record.update_date) && seen.price == record.price
+            }) {
+                continue;
+            }
+
+            if let Some(previous) = previous {
+                // free games parse to 0, so going free is a drop too
+                if let (Some(old), Some(new)) = (previous.amount(), record.price_amount()) {
+                    if new < old {
+                        price_drops.push(PriceDrop {
+                            record,
+                            previous_price: previous.price.clone(),
+                        });
+                    }
+                }
+            }
+
+            self.seen.insert(
+                record.link.clone(),
+                SeenRecord {
+                    update_date: record.update_date.clone(),
+                    price: record.price.clone(),
+                    amount: record.price_amount(),
+                },
+            );
+            changed.push(record);
+        }
+
+        (changed, price_drops)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(path) = &self.path {
+            fs::write(path, serde_json::to_string(&self.seen)?)?;
+        }
+
+        Ok(())
+    }
+}
+
+// the same instant may be rendered in different timezones between runs
+fn same_date(a: &str, b: &str) -> bool {
+    match (parse_feed_date(a), parse_feed_date(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(price: &str) -> ItchData {
+        ItchData {
+            link: "https://alice.itch.io/cave-run".to_string(),
+            update_date: "Mon, 01 Apr 2024 10:00:00 GMT".to_string(),
+            price: price.to_string(),
+            price_info: Price::parse(price),
+            ..ItchData::default()
+        }
+    }
+
+    #[test]
+    fn price_drop_is_seen_without_an_update_date_change() {
+        let mut state = IncrementalState::load(None).unwrap();
+        let first = [record("$10.00")];
+        assert_eq!(state.unseen(&first).len(), 1);
+        let (changed, drops) = state.take_changed(&first);
+        assert_eq!((changed.len(), drops.len()), (1, 0));
+
+        let sale = [record("$5.00")];
+        assert!(state.unseen(&sale).is_empty());
+        let (changed, drops) = state.take_changed(&sale);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(drops.len(), 1);
+        assert_eq!(drops[0].previous_price, "$10.00");
+
+        let (changed, drops) = state.take_changed(&sale);
+        assert!(changed.is_empty() && drops.is_empty());
+    }
+
+    #[test]
+    fn going_free_is_a_price_drop() {
+        let mut state = IncrementalState::load(None).unwrap();
+        state.take_changed(&[record("$10.00")]);
+
+        let free = [record("Free")];
+        let (changed, drops) = state.take_changed(&free);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(drops.len(), 1);
+        assert_eq!(drops[0].previous_price, "$10.00");
+    }
+}
@@ -0,0 +1,11 @@
+pub mod checkpoint;
+pub mod diagnostics;
+pub mod exit;
+pub mod ffi;
+pub mod filters;
+pub mod parsers;
+pub mod scrapers;
+pub mod units;
+
+pub use scrapers::options::ScrapeOptions;
+pub use scrapers::scraper::{GameRecord, Scraper};
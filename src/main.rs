@@ -1,57 +1,1331 @@
-use crate::scrapers::itch_rss_scraper::scrape_itch_rss_feed;
-use anyhow::Result;
-use clap::{Parser, ValueEnum};
-use std::path::PathBuf;
-use std::{fs, io, io::Write};
+use crate::checkpoint::{Checkpoint, ResumeMode};
+use crate::config::{Config, Profile};
+use crate::currency::{load_rates, Rates};
+use crate::dataset::expand_path;
+use crate::exit::Failure;
+use crate::filters::{Condition, Filter, Rule};
+use crate::hooks::{Hooks, RunSummary};
+use crate::incremental::IncrementalState;
+use crate::notifiers::Notifier;
+use crate::output::OutputFormat;
+use crate::parsers::dates::{parse_timezone, render_date, OutputZone};
+use crate::parsers::plugin::Plugin;
+use crate::scrapers::bandwidth::Bandwidth;
+use crate::scrapers::distributed::Coordinator;
+use crate::scrapers::fixtures::FixtureRecorder;
+use crate::scrapers::images::{download_images, ImageKind, ImageStore};
+use crate::scrapers::itch_browse_scraper::scrape_itch_browse_feed;
+use crate::scrapers::itch_rss_scraper::{push_down_filters, scrape_feed, ItchData};
+use crate::scrapers::options::{Concurrency, DateField, DateWindow, ScrapeOptions};
+use crate::scrapers::progress::{
+    CountingReporter, ErrorReporter, JsonReporter, ProgressBarReporter, ProgressMode,
+    ProgressReporter,
+};
+use crate::scrapers::rate_limit::RateLimiter;
+use crate::scrapers::scraper::{
+    DefinedSiteScraper, DistributedScraper, ItchBrowseScraper, ItchScraper, Scraper, SteamScraper,
+};
+use crate::scrapers::site_definition::SiteDefinition;
+use crate::scripting::Script;
+use crate::sinks::Sink;
+use crate::snapshot::SnapshotStore;
+use crate::taxonomy::Taxonomy;
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use game_data_scraper::{checkpoint, diagnostics, exit, filters, parsers, scrapers, units};
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io::Write;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::{io, io::IsTerminal};
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::prelude::*;
 
-mod parsers;
-mod scrapers;
+mod avro;
+mod columnar;
+mod commands;
+mod config;
+mod currency;
+mod dataset;
+mod db;
+mod history;
+mod hooks;
+mod incremental;
+mod notifiers;
+mod output;
+mod scripting;
+mod secrets;
+#[cfg(feature = "sentiment")]
+mod sentiment;
+mod sinks;
+mod snapshot;
+mod taxonomy;
+mod tui;
 
 #[derive(Parser, Debug)]
-#[command(version, about)]
-struct Args {
-    #[arg(short, long, value_enum, value_name = "SITE")]
-    site: Site,
+#[command(
+    version,
+    about,
+    arg_required_else_help = true,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
 
-    #[arg(short, long, value_enum, value_name = "URL")]
-    url: String,
+    #[command(flatten)]
+    scrape: Option<ScrapeArgs>,
+
+    #[arg(long, requires = "site")]
+    tui: bool,
+
+    // without --site and --url, every [[jobs]] entry in the file is scraped once
+    #[arg(short, long, value_name = "FILE PATH")]
+    config: Option<PathBuf>,
+
+    #[arg(long, value_name = "NAME", requires = "scrape")]
+    profile: Option<String>,
+
+    #[arg(long, value_name = "FILE PATH", requires = "scrape")]
+    checkpoint: Option<PathBuf>,
+
+    #[arg(long, value_enum, value_name = "MODE", requires = "checkpoint")]
+    resume: Option<ResumeMode>,
+
+    #[arg(long, requires = "checkpoint")]
+    force: bool,
+
+    #[arg(long, global = true)]
+    trace_http: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Keep running and re-scrape on an interval, emitting only new or changed records
+    Watch(Box<commands::watch::WatchArgs>),
+    /// Convert scrape output files into another format, optionally filtering records
+    Export(commands::export::ExportArgs),
+    /// Render trend charts (SVG or PNG) from scrape output files
+    Chart(commands::chart::ChartArgs),
+    /// Serve a scraped SQLite database over a JSON REST API
+    Serve(commands::serve::ServeArgs),
+    /// Accept scrape jobs over HTTP and report their progress and results
+    ServeJobs(commands::serve_jobs::ServeJobsArgs),
+    /// Run a gRPC service for driving scrapes and consuming results
+    Grpc(commands::grpc::GrpcArgs),
+    /// Run a Model Context Protocol server on stdio exposing scraping tools
+    Mcp(commands::mcp::McpArgs),
+    /// Full-text search scrape output files through a tantivy index
+    Search(commands::search::SearchArgs),
+    /// Combine scrape output files, deduplicating records by link and keeping the newest
+    Merge(commands::merge::MergeArgs),
+    /// Parse a single saved page, from a file or stdin, and print the extracted record
+    Parse(commands::parse::ParseArgs),
+    /// Check scrape output files against the record schema and flag impossible values
+    Validate(commands::validate::ValidateArgs),
+    /// Report or merge near-duplicate records such as re-uploads and demo pages
+    Dedupe(commands::dedupe::DedupeArgs),
+    /// Describe every output field with its type, source element and presence
+    ExplainSchema(commands::explain_schema::ExplainSchemaArgs),
+    /// Interactively create a config file for scheduled scrapes
+    Init(commands::init::InitArgs),
+    /// Store and remove API keys, credentials and webhook secrets in the OS keyring
+    Auth(commands::auth::AuthArgs),
+    /// Print the protobuf definition of the records written by --format protobuf
+    Proto(commands::proto::ProtoArgs),
+    /// Query the append-only log of every record observed by scrapes run with --history
+    History(commands::history::HistoryArgs),
+    /// Prune old observations from --history logs by age or file size
+    Clean(commands::clean::CleanArgs),
+    /// Maintain the parser test corpus of saved pages and their expected output
+    TestFixtures(commands::test_fixtures::TestFixturesArgs),
+    /// Re-scrape known game pages and fail when their extraction drifts from what was recorded
+    Canary(commands::canary::CanaryArgs),
+    /// Print shell completions for bash, zsh, fish, elvish or powershell
+    Completions(commands::completions::CompletionsArgs),
+}
+
+#[derive(Args, Deserialize, Debug, Clone, Default)]
+#[group(id = "scrape", multiple = true, requires_all = ["site", "url"])]
+pub struct ScrapeArgs {
+    #[arg(short, long, value_enum, value_name = "SITE", required = false)]
+    pub site: Site,
+
+    #[arg(short, long, value_enum, value_name = "URL", required = false)]
+    pub url: String,
 
     #[arg(short, long, value_name = "FILE PATH")]
-    outfile: Option<PathBuf>,
+    pub outfile: Option<PathBuf>,
+
+    #[arg(short, long, value_enum, value_name = "FORMAT", default_value = "json")]
+    #[serde(default)]
+    pub format: OutputFormat,
+
+    #[arg(long)]
+    #[serde(default)]
+    pub length_prefixed: bool,
+
+    #[arg(long, value_name = "TEXT")]
+    pub list_delimiter: Option<String>,
+
+    #[arg(long)]
+    #[serde(default)]
+    pub stream: bool,
 
     #[arg(short, long, value_name = "INTEGER")]
-    page_limit: Option<i32>,
+    pub page_limit: Option<i32>,
+
+    #[arg(long, value_name = "INTEGER")]
+    pub start_page: Option<i32>,
+
+    #[arg(long, value_name = "INTEGER", conflicts_with = "page_limit")]
+    pub end_page: Option<i32>,
+
+    #[arg(long, value_name = "INTEGER", value_parser = clap::value_parser!(u32).range(1..))]
+    pub sample_pages: Option<u32>,
+
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<String>,
+
+    #[arg(long, value_name = "DATE")]
+    pub until: Option<String>,
+
+    #[arg(long, value_enum, value_name = "FIELD")]
+    pub date_field: Option<DateField>,
+
+    #[arg(long, value_name = "ZONE")]
+    pub timezone: Option<String>,
+
+    #[arg(long = "tag", value_name = "TAG")]
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    #[arg(long = "genre", value_name = "GENRE")]
+    #[serde(default)]
+    pub genres: Vec<String>,
+
+    #[arg(long = "platform", value_name = "PLATFORM")]
+    #[serde(default)]
+    pub platforms: Vec<String>,
+
+    #[arg(long)]
+    #[serde(default)]
+    pub free_only: bool,
+
+    #[arg(long, value_name = "AMOUNT")]
+    pub min_price: Option<f64>,
+
+    #[arg(long, value_name = "AMOUNT")]
+    pub max_price: Option<f64>,
+
+    #[arg(long, value_name = "SCORE")]
+    pub min_rating: Option<f32>,
+
+    #[arg(long, value_name = "COUNT")]
+    pub min_ratings: Option<i32>,
+
+    #[arg(long, value_name = "NAME")]
+    pub rules: Option<String>,
+
+    #[arg(skip)]
+    #[serde(skip)]
+    pub rule: Option<Box<Condition>>,
+
+    #[arg(skip)]
+    #[serde(skip)]
+    pub limiter: Arc<RateLimiter>,
+
+    #[arg(skip)]
+    #[serde(skip)]
+    pub checkpoint: Option<Arc<Checkpoint>>,
 
     #[arg(short, long, value_name = "INTEGER")]
-    max_retries: Option<u32>,
+    pub max_retries: Option<u32>,
+
+    #[arg(long, value_name = "NUMBER")]
+    pub rps: Option<f64>,
+
+    #[arg(long, value_name = "RATE", value_parser = units::parse_rate)]
+    pub max_bandwidth: Option<u64>,
+
+    #[arg(
+        short,
+        long,
+        visible_alias = "concurrency",
+        value_name = "INTEGER",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub jobs: Option<u32>,
+
+    #[arg(long, value_name = "INTEGER", value_parser = clap::value_parser!(u32).range(1..))]
+    pub fetch_jobs: Option<u32>,
+
+    #[arg(long, value_name = "INTEGER", value_parser = clap::value_parser!(u32).range(1..))]
+    pub parse_jobs: Option<u32>,
+
+    #[arg(long, value_name = "INTEGER", value_parser = clap::value_parser!(u32).range(1..))]
+    pub sink_jobs: Option<u32>,
+
+    #[arg(long, value_name = "FILE PATH")]
+    pub taxonomy: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE PATH")]
+    pub script: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    pub record_fixtures: Option<PathBuf>,
+
+    #[arg(long)]
+    #[serde(default)]
+    pub comments: bool,
+
+    #[arg(long, value_name = "FILE PATH")]
+    pub history: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE PATH")]
+    pub snapshot: Option<PathBuf>,
+
+    #[arg(long, value_enum, value_name = "KINDS", value_delimiter = ',')]
+    #[serde(default)]
+    pub download_images: Vec<ImageKind>,
+
+    #[arg(long, value_name = "DIR", default_value = "images")]
+    pub image_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "SIZES",
+        value_delimiter = ',',
+        value_parser = clap::value_parser!(u32).range(1..),
+        requires = "download_images"
+    )]
+    #[serde(default)]
+    pub thumbnails: Vec<u32>,
+
+    #[arg(long, value_name = "URL", requires = "redis_job")]
+    pub redis: Option<String>,
+
+    #[arg(long, value_name = "NAME", requires = "redis")]
+    pub redis_job: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "INTEGER",
+        value_parser = clap::value_parser!(u32).range(1..),
+        requires = "redis"
+    )]
+    pub redis_rps: Option<u32>,
+
+    #[arg(long = "sink", value_name = "URI")]
+    #[serde(default)]
+    pub sinks: Vec<String>,
+
+    #[arg(long, value_name = "FILE PATH")]
+    pub plugin: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE PATH")]
+    pub site_def: Option<PathBuf>,
+
+    #[arg(long, value_name = "CURRENCY")]
+    pub convert_to: Option<String>,
+
+    #[arg(long, value_name = "FILE PATH", requires = "convert_to")]
+    pub rates: Option<PathBuf>,
+
+    #[arg(long, value_enum, value_name = "MODE")]
+    pub progress: Option<ProgressMode>,
+
+    #[arg(short, long, visible_alias = "no-progress")]
+    #[serde(default)]
+    pub quiet: bool,
+
+    #[arg(long, value_name = "COMMAND")]
+    pub on_complete: Option<String>,
+
+    #[arg(long, value_name = "COMMAND")]
+    pub on_error: Option<String>,
+
+    #[arg(long)]
+    #[serde(default)]
+    pub fail_on_error: bool,
+}
+
+impl ScrapeArgs {
+    pub fn pages(&self) -> Result<RangeInclusive<i32>> {
+        let start = self.start_page.unwrap_or(1);
+        let end = match self.end_page {
+            Some(end) => end,
+            None => {
+                let limit = self.page_limit.unwrap_or(300);
+                limit
+                    .checked_sub(1)
+                    .and_then(|pages| start.checked_add(pages))
+                    .ok_or_else(|| {
+                        Failure::Config(anyhow!(
+                            "Invalid page range, a --page-limit of {} from page {} ends outside 1..={}",
+                            limit,
+                            start,
+                            i32::MAX
+                        ))
+                    })?
+            }
+        };
+
+        if start < 1 || end < start {
+            return Err(Failure::Config(anyhow!(
+                "Invalid page range {}..={}, the start page must be at least 1 and not after the end page",
+                start,
+                end
+            ))
+            .into());
+        }
+
+        Ok(start..=end)
+    }
+
+    pub fn date_window(&self) -> Result<DateWindow> {
+        let window = DateWindow::parse(
+            self.date_field.unwrap_or_default(),
+            self.since.as_deref(),
+            self.until.as_deref(),
+        )?;
+        // browse cells have no dates to compare, the feed does
+        if window.is_bounded() && matches!(self.site, Site::ItchBrowse) {
+            return Err(Failure::Config(anyhow!(
+                "--since and --until need feed dates, which --site itch-browse doesn't have; use --site itch"
+            ))
+            .into());
+        }
+        Ok(window)
+    }
+
+    pub fn output_zone(&self) -> Result<Option<OutputZone>> {
+        match &self.timezone {
+            Some(zone) => parse_timezone(zone).map(Some).ok_or_else(|| {
+                Failure::Config(anyhow!(
+                    "Unknown timezone {:?}, expected an IANA name like Europe/Berlin or an offset like +02:00",
+                    zone
+                ))
+                .into()
+            }),
+            None => Ok(None),
+        }
+    }
+
+    pub fn filter(&self) -> Filter {
+        Filter {
+            tags: self.tags.clone(),
+            genres: self.genres.clone(),
+            platforms: self.platforms.clone(),
+            free: self.free_only.then_some(true),
+            min_price: self.min_price,
+            max_price: self.max_price,
+            min_rating: self.min_rating,
+            min_ratings: self.min_ratings,
+        }
+    }
+
+    // tags are pushed down as the raw itch tag the taxonomy maps onto the wanted name, the same
+    // table the pipeline matches them with
+    fn pushdown_filter(&self) -> Filter {
+        let mut filter = self.filter();
+        if let Some(path) = &self.taxonomy {
+            // an unreadable taxonomy fails the run once the pipeline loads it, until then no tag
+            // is pushed down rather than one that might not match
+            let taxonomy = Taxonomy::from_file(path).ok();
+            filter.tags = filter
+                .tags
+                .iter()
+                .filter_map(|tag| taxonomy.as_ref()?.raw_tag(tag))
+                .collect();
+        }
+        filter
+    }
+
+    pub fn resolve_rules(&mut self, rules: &HashMap<String, Rule>) -> Result<()> {
+        let Some(name) = &self.rules else {
+            return Ok(());
+        };
+
+        let rule = rules.get(name).ok_or_else(|| {
+            let mut known: Vec<&str> = rules.keys().map(String::as_str).collect();
+            known.sort();
+            Failure::Config(anyhow!(
+                "Unknown rule set {:?}, the config file defines: {}",
+                name,
+                known.join(", ")
+            ))
+        })?;
+        let condition = rule
+            .compile()
+            .with_context(|| format!("Invalid rule set {:?}", name))?;
+
+        self.rule = Some(Box::new(condition));
+        Ok(())
+    }
+
+    pub fn outfile_path(&self) -> Result<Option<PathBuf>> {
+        // "-" is stdout, for piping binary formats
+        let Some(template) = self.outfile.as_ref().filter(|path| path.as_os_str() != "-") else {
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        let pages = self.pages()?;
+        let values = [
+            ("date", now.format("%Y-%m-%d").to_string()),
+            ("time", now.format("%H%M%S").to_string()),
+            ("timestamp", now.timestamp().to_string()),
+            ("site", self.site.name().to_string()),
+            ("page_start", pages.start().to_string()),
+            ("page_end", pages.end().to_string()),
+        ];
+
+        let path = expand_path(template, &values).map_err(Failure::Config)?;
+        Ok(Some(path))
+    }
+
+    pub fn feed_url(&self) -> String {
+        match self.site {
+            Site::Itch | Site::ItchBrowse => push_down_filters(&self.url, &self.pushdown_filter()),
+            Site::Steam | Site::Custom => self.url.clone(),
+        }
+    }
+
+    pub fn options(&self) -> Result<ScrapeOptions> {
+        Ok(ScrapeOptions {
+            max_retries: self.max_retries.unwrap_or(20),
+            pages: match (&self.checkpoint, self.sample_pages) {
+                (Some(checkpoint), _) => checkpoint.remaining_pages().collect(),
+                (None, Some(count)) => sample_pages(self.pages()?, count as usize),
+                (None, None) => self.pages()?.collect(),
+            },
+            // the run always finishes so nothing scraped is lost, --fail-on-error only changes
+            // how the item errors are reported at the end
+            fail_on_error: false,
+            // only read when asked for, the sentiment feature scores what --comments brings in
+            comments: self.comments,
+            window: self.date_window()?,
+            limiter: match self.rps {
+                Some(rps) => Arc::new(
+                    self.limiter
+                        .with_global_rate(rps)
+                        .map_err(Failure::Config)?,
+                ),
+                None => self.limiter.clone(),
+            },
+            bandwidth: Arc::new(Bandwidth::new(self.max_bandwidth)),
+            checkpoint: self.checkpoint.clone(),
+            concurrency: self.concurrency(),
+            plugin: match &self.plugin {
+                Some(path) => Some(Arc::new(Plugin::from_file(path).map_err(Failure::Config)?)),
+                None => None,
+            },
+            recorder: match &self.record_fixtures {
+                Some(dir) => Some(Arc::new(FixtureRecorder::new(dir)?)),
+                None => None,
+            },
+        })
+    }
+
+    // --jobs sizes every stage, the per-stage flags override it
+    pub fn concurrency(&self) -> Concurrency {
+        let jobs = self.jobs.unwrap_or(1);
+        Concurrency {
+            fetch: self.fetch_jobs.unwrap_or(jobs) as usize,
+            parse: self.parse_jobs.unwrap_or(jobs) as usize,
+            sink: self.sink_jobs.unwrap_or(jobs) as usize,
+        }
+    }
+
+    // --stream writes as it scrapes, so nothing that needs the whole run at once can come with it
+    pub fn check_streaming(&self, tui: bool, checkpoint: bool, profile: bool) -> Result<()> {
+        if !self.stream {
+            return Ok(());
+        }
+        let conflict = if !matches!(self.format, OutputFormat::Ndjson) {
+            "any --format but ndjson"
+        } else if !matches!(self.site, Site::Itch | Site::ItchBrowse) {
+            "--site other than itch or itch-browse"
+        } else if self.redis.is_some() {
+            "--redis"
+        } else if !self.sinks.is_empty() {
+            "--sink"
+        } else if self.snapshot.is_some() {
+            "--snapshot"
+        } else if tui {
+            "--tui"
+        } else if checkpoint {
+            "--checkpoint"
+        } else if profile {
+            // new games and price drops are only known by comparing the whole run to the last
+            "--profile"
+        } else {
+            return Ok(());
+        };
+        Err(Failure::Config(anyhow!("--stream can't be combined with {}", conflict)).into())
+    }
+
+    pub fn reporter(&self) -> Box<dyn ProgressReporter + Send> {
+        if self.quiet {
+            return Box::new(ErrorReporter);
+        }
+
+        match self.progress.unwrap_or_default() {
+            ProgressMode::Bar => Box::new(ProgressBarReporter::new()),
+            ProgressMode::Json => Box::new(JsonReporter),
+            ProgressMode::None => Box::new(ErrorReporter),
+        }
+    }
 }
 
-#[derive(Debug, ValueEnum, Clone)]
-enum Site {
+#[derive(Debug, ValueEnum, Deserialize, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Site {
+    #[default]
     Itch,
+    #[serde(rename = "itch-browse")]
+    ItchBrowse,
+    Steam,
+    Custom,
 }
 
-fn main() -> Result<()> {
-    let args = Args::parse();
-    let page_limit = args.page_limit.unwrap_or(300);
-    let max_retries = args.max_retries.unwrap_or(20);
+impl Site {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Site::Itch => "itch",
+            Site::ItchBrowse => "itch-browse",
+            Site::Steam => "steam",
+            Site::Custom => "custom",
+        }
+    }
+}
 
-    let rt = tokio::runtime::Runtime::new()?;
-    let json = match args.site {
-        Site::Itch => {
-            let itch_data = rt.block_on(scrape_itch_rss_feed(args.url, max_retries, page_limit))?;
-            serde_json::to_string(&itch_data)?
+// the per-record steps between scraping and output, shared by whole runs and --stream
+struct Pipeline<'a> {
+    args: &'a ScrapeArgs,
+    taxonomy: Taxonomy,
+    rates: Option<Rates>,
+    script: Option<Script>,
+    filter: Filter,
+    zone: Option<OutputZone>,
+}
+
+impl Pipeline<'_> {
+    async fn load(args: &ScrapeArgs) -> Result<Pipeline<'_>> {
+        let zone = args.output_zone()?;
+
+        let taxonomy = match &args.taxonomy {
+            Some(path) => Taxonomy::from_file(path).map_err(Failure::Config)?,
+            None => Taxonomy::default(),
+        };
+
+        let rates = match &args.convert_to {
+            Some(target) => {
+                Some(load_rates(&reqwest::Client::new(), args.rates.as_deref(), target).await?)
+            }
+            None => None,
+        };
+
+        let script = match &args.script {
+            Some(path) => Some(Script::from_file(path).map_err(Failure::Config)?),
+            None => None,
+        };
+
+        if let (Some(name), None) = (&args.rules, &args.rule) {
+            return Err(Failure::Config(anyhow!("Rule set {:?} was never loaded", name)).into());
+        }
+
+        let mut filter = args.filter();
+        filter.tags = filter
+            .tags
+            .iter()
+            .map(|tag| taxonomy.canonical_tag(tag))
+            .collect();
+
+        Ok(Pipeline {
+            args,
+            taxonomy,
+            rates,
+            script,
+            filter,
+            zone,
+        })
+    }
+
+    // None when the record is filtered out or dropped by the script
+    fn process(&self, mut record: ItchData) -> Result<Option<ItchData>> {
+        let args = self.args;
+        self.taxonomy.apply(&args.site, &mut record);
+        if let (Some(rates), Some(target)) = (&self.rates, &args.convert_to) {
+            rates.apply(&mut record, target);
+        }
+        #[cfg(feature = "sentiment")]
+        if args.comments {
+            sentiment::score_record(&mut record);
+        }
+
+        if !self.filter.matches(&record)
+            || args
+                .rule
+                .as_ref()
+                .is_some_and(|rule| !rule.matches(&record))
+        {
+            return Ok(None);
         }
+
+        // dates are compared in UTC above and only converted for output
+        if let Some(zone) = self.zone {
+            for date in [
+                &mut record.create_date,
+                &mut record.update_date,
+                &mut record.pub_date,
+                &mut record.release_date,
+                &mut record.updated_date,
+                &mut record.published_date,
+            ] {
+                *date = render_date(date, zone);
+            }
+        }
+
+        match &self.script {
+            Some(script) => script.apply(record),
+            None => Ok(Some(record)),
+        }
+    }
+
+    // images are downloaded and history appended per batch, a single record for --stream
+    async fn finish(
+        &self,
+        records: &mut [ItchData],
+        options: &ScrapeOptions,
+        reporter: &dyn ProgressReporter,
+    ) -> Result<()> {
+        let args = self.args;
+        if !args.download_images.is_empty() {
+            let dir = args.image_dir.as_deref().unwrap_or(Path::new("images"));
+            let store = ImageStore::open(dir)?.with_thumbnails(&args.thumbnails);
+            download_images(records, &args.download_images, &store, options, reporter).await?;
+        }
+
+        if let Some(path) = &args.history {
+            history::append(path, records, Utc::now())
+                .with_context(|| format!("Failed to append to history {:?}", path))?;
+        }
+
+        Ok(())
+    }
+}
+
+pub async fn scrape(args: &ScrapeArgs, reporter: &dyn ProgressReporter) -> Result<Vec<ItchData>> {
+    let options = args.options()?;
+    let pipeline = Pipeline::load(args).await?;
+
+    let mut records = match args.site {
+        Site::Itch => match (&args.redis, &args.redis_job) {
+            (Some(redis), Some(job)) => {
+                let scraper = DistributedScraper {
+                    url: args.feed_url(),
+                    coordinator: Coordinator::connect(redis, job, args.redis_rps).await?,
+                };
+                scraper.scrape(&options, reporter).await?
+            }
+            _ => {
+                ItchScraper::new(args.feed_url())
+                    .scrape(&options, reporter)
+                    .await?
+            }
+        },
+        Site::ItchBrowse => {
+            ItchBrowseScraper::new(args.feed_url())
+                .scrape(&options, reporter)
+                .await?
+        }
+        Site::Steam => {
+            SteamScraper::new(args.feed_url())
+                .scrape(&options, reporter)
+                .await?
+        }
+        Site::Custom => {
+            let path = args
+                .site_def
+                .as_deref()
+                .ok_or_else(|| Failure::Config(anyhow!("--site custom needs a --site-def file")))?;
+            let scraper = DefinedSiteScraper {
+                url: args.feed_url(),
+                definition: SiteDefinition::from_file(path).map_err(Failure::Config)?,
+            };
+            scraper.scrape(&options, reporter).await?
+        }
+    };
+    if let Some(checkpoint) = &args.checkpoint {
+        records.splice(0..0, checkpoint.resumed_records().iter().cloned());
+    }
+
+    let mut records = records
+        .into_iter()
+        .filter_map(|record| pipeline.process(record).transpose())
+        .collect::<Result<Vec<_>>>()?;
+    pipeline.finish(&mut records, &options, reporter).await?;
+
+    Ok(records)
+}
+
+// each record is written as one ndjson line once its game page is parsed, so memory stays
+// bounded and a crash keeps everything written so far
+async fn scrape_streaming(
+    args: &ScrapeArgs,
+    out: &mut dyn Write,
+    reporter: &dyn ProgressReporter,
+) -> Result<usize> {
+    let options = args.options()?;
+    let pipeline = Pipeline::load(args).await?;
+    let out = Mutex::new(out);
+    let written = AtomicUsize::new(0);
+    let failure = Mutex::new(None);
+
+    let emit = |record| {
+        let (pipeline, options, out, written, failure) =
+            (&pipeline, &options, &out, &written, &failure);
+        async move {
+            let result = async {
+                let Some(record) = pipeline.process(record)? else {
+                    return Ok(());
+                };
+                let mut records = [record];
+                pipeline.finish(&mut records, options, reporter).await?;
+                let mut out = out.lock().unwrap();
+                serde_json::to_writer(&mut **out, &records[0])?;
+                out.write_all(b"\n")?;
+                out.flush()?;
+                written.fetch_add(1, Ordering::Relaxed);
+                anyhow::Ok(())
+            }
+            .await;
+            match result {
+                Ok(()) => true,
+                // stops the scrape at this record
+                Err(err) => {
+                    *failure.lock().unwrap() = Some(err);
+                    false
+                }
+            }
+        }
+    };
+    match args.site {
+        Site::ItchBrowse => {
+            scrape_itch_browse_feed(args.feed_url(), &options, reporter, emit).await?
+        }
+        _ => scrape_feed(args.feed_url(), &options, reporter, emit).await?,
+    }
+
+    match failure.into_inner().unwrap() {
+        Some(err) => Err(err),
+        None => Ok(written.into_inner()),
+    }
+}
+
+// sorted so pages are still walked front to back, which the newest-first early stop relies on
+fn sample_pages(pages: RangeInclusive<i32>, count: usize) -> Vec<i32> {
+    let pages: Vec<i32> = pages.collect();
+    let mut sampled: Vec<i32> = pages
+        .choose_multiple(&mut rand::thread_rng(), count.min(pages.len()))
+        .copied()
+        .collect();
+    sampled.sort_unstable();
+    sampled
+}
+
+fn scrape_with_dashboard(
+    rt: &tokio::runtime::Runtime,
+    args: ScrapeArgs,
+) -> Result<(Vec<ItchData>, usize)> {
+    let (reporter, events) = tui::channel();
+    let handle = rt.spawn(async move {
+        let reporter = CountingReporter::new(reporter);
+        let records = scrape(&args, &reporter).await?;
+        Ok((records, reporter.errors()))
+    });
+
+    tui::run_dashboard(events, &handle)?;
+
+    match rt.block_on(handle) {
+        Ok(result) => result,
+        Err(err) if err.is_cancelled() => Err(Failure::Aborted("cancelled".to_string()).into()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+const ENV_PREFIX: &str = "GDS";
+
+// every long flag can also be set through GDS_<FLAG>, or GDS_<SUBCOMMAND>_<FLAG> under a subcommand
+fn with_env_vars(command: clap::Command, prefix: &str) -> clap::Command {
+    let subcommands: Vec<String> = command
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect();
+
+    let mut command = command.mut_args(|arg| match arg.get_long() {
+        Some(long) => {
+            let secret = ["password", "token", "key", "webhook"]
+                .iter()
+                .any(|word| long.contains(word));
+            let name = format!("{}_{}", prefix, env_name(long));
+            arg.env(name).hide_env_values(secret)
+        }
+        None => arg,
+    });
+
+    for name in subcommands {
+        let sub_prefix = format!("{}_{}", prefix, env_name(&name));
+        command = command.mut_subcommand(&name, |sub| with_env_vars(sub, &sub_prefix));
+    }
+
+    command
+}
+
+fn env_name(flag: &str) -> String {
+    flag.replace('-', "_").to_uppercase()
+}
+
+fn parse_cli() -> Result<(Cli, Option<Profile>)> {
+    let mut command = with_env_vars(Cli::command(), ENV_PREFIX);
+    let mut argv: Vec<OsString> = std::env::args_os().collect();
+
+    // a fully env-configured run has no arguments, which shouldn't just print help
+    let env_configured = std::env::vars().any(|(key, _)| key.starts_with("GDS_"));
+    if env_configured {
+        command = command.arg_required_else_help(false);
+    }
+
+    // top-level flags conflict with subcommands, so their variables only apply to scrapes; a
+    // GDS_JOBS set for the whole container mustn't turn `proto` into a scrape without --site
+    let runs_subcommand = command
+        .clone()
+        .ignore_errors(true)
+        .try_get_matches_from(&argv)
+        .is_ok_and(|matches| matches.subcommand().is_some());
+    if runs_subcommand {
+        command = command.mut_args(|arg| match arg.is_global_set() {
+            true => arg,
+            false => arg.env(clap::builder::Resettable::Reset),
+        });
+    }
+
+    let profile = match load_profile(&command, &argv)? {
+        Some(profile) => {
+            argv.splice(1..1, profile_flags(&command, &profile.settings)?);
+            command = command.args_override_self(true);
+            Some(profile)
+        }
+        None => None,
     };
 
-    match args.outfile {
-        Some(file) => {
-            fs::write(file, json)?;
+    let matches = command.get_matches_from(argv);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    Ok((cli, profile))
+}
+
+fn load_profile(command: &clap::Command, argv: &[OsString]) -> Result<Option<Profile>> {
+    let Ok(matches) = command
+        .clone()
+        .ignore_errors(true)
+        .try_get_matches_from(argv)
+    else {
+        return Ok(None);
+    };
+
+    match matches.get_one::<String>("profile") {
+        Some(name) => {
+            let config = matches.get_one::<PathBuf>("config");
+            Config::profile_from(config.map(PathBuf::as_path), name).map(Some)
         }
+        None => Ok(None),
+    }
+}
+
+// turns profile settings into the equivalent flags, placed before the real arguments so
+// anything given explicitly wins
+fn profile_flags(command: &clap::Command, settings: &toml::Table) -> Result<Vec<OsString>> {
+    let mut flags = Vec::new();
+
+    for (key, value) in settings {
+        let long = command
+            .get_arguments()
+            .find(|arg| arg.get_id() == key.as_str())
+            .filter(|arg| !["config", "profile"].contains(&arg.get_id().as_str()))
+            .and_then(|arg| arg.get_long())
+            .ok_or_else(|| Failure::Config(anyhow!("Unknown profile setting {:?}", key)))?;
+
+        let values = match value {
+            toml::Value::Array(values) => values.clone(),
+            value => vec![value.clone()],
+        };
+        for value in values {
+            match value {
+                toml::Value::Boolean(true) => flags.push(format!("--{}", long).into()),
+                toml::Value::Boolean(false) => {}
+                toml::Value::String(value) => flags.push(format!("--{}={}", long, value).into()),
+                toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Datetime(_) => {
+                    flags.push(format!("--{}={}", long, value).into())
+                }
+                _ => {
+                    return Err(Failure::Config(anyhow!(
+                        "Unsupported value for profile setting {:?}",
+                        key
+                    ))
+                    .into())
+                }
+            }
+        }
+    }
+
+    Ok(flags)
+}
+
+// request and response lines, retry decisions and timings from the fetch loop, on stderr
+fn trace_http() {
+    tracing_subscriber::fmt()
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .with_max_level(Level::TRACE)
+        .finish()
+        .with(Targets::new().with_target("http", Level::TRACE))
+        .init();
+}
+
+fn main() -> ExitCode {
+    match parse_cli().and_then(|(cli, profile)| run(cli, profile)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            exit::exit_code(&err)
+        }
+    }
+}
+
+fn run(mut cli: Cli, profile: Option<Profile>) -> Result<()> {
+    if cli.trace_http {
+        trace_http();
+    }
+    let rt = tokio::runtime::Runtime::new()?;
+
+    match cli.command {
+        Some(Command::Watch(args)) => rt.block_on(commands::watch::run(*args)),
+        Some(Command::Export(args)) => commands::export::run(args),
+        Some(Command::Chart(args)) => commands::chart::run(args),
+        Some(Command::Serve(args)) => rt.block_on(commands::serve::run(args)),
+        Some(Command::ServeJobs(args)) => rt.block_on(commands::serve_jobs::run(args)),
+        Some(Command::Grpc(args)) => rt.block_on(commands::grpc::run(args)),
+        Some(Command::Mcp(args)) => rt.block_on(commands::mcp::run(args)),
+        Some(Command::Search(args)) => commands::search::run(args),
+        Some(Command::Merge(args)) => commands::merge::run(args),
+        Some(Command::Parse(args)) => commands::parse::run(args),
+        Some(Command::Validate(args)) => commands::validate::run(args),
+        Some(Command::Dedupe(args)) => commands::dedupe::run(args),
+        Some(Command::ExplainSchema(args)) => commands::explain_schema::run(args),
+        Some(Command::Init(args)) => commands::init::run(args),
+        Some(Command::Auth(args)) => commands::auth::run(args),
+        Some(Command::Proto(args)) => commands::proto::run(args),
+        Some(Command::History(args)) => commands::history::run(args),
+        Some(Command::Clean(args)) => commands::clean::run(args),
+        Some(Command::TestFixtures(args)) => commands::test_fixtures::run(args),
+        Some(Command::Canary(args)) => rt.block_on(commands::canary::run(args)),
+        Some(Command::Completions(args)) => commands::completions::run(args, Cli::command()),
         None => {
-            io::stdout().write_all(json.as_bytes())?;
+            let Some(mut args) = cli.scrape.take() else {
+                let path = cli
+                    .config
+                    .as_deref()
+                    .expect("clap requires scrape args or --config without a subcommand");
+                return run_jobs(&rt, path);
+            };
+            if cli.config.is_some() || cli.profile.is_some() || args.rules.is_some() {
+                let config = Config::load(cli.config.as_deref())?;
+                args.resolve_rules(&config.rules)?;
+                args.limiter = config.rate_limiter()?;
+            }
+            run_scrape(&rt, Some(&cli), profile, args)
         }
     }
+}
+
+// one scrape from flags or a config job through to its output, hooks and exit status; cli is
+// None for jobs, which have no dashboard, checkpoint or profile
+fn run_scrape(
+    rt: &tokio::runtime::Runtime,
+    cli: Option<&Cli>,
+    profile: Option<Profile>,
+    mut args: ScrapeArgs,
+) -> Result<()> {
+    let tui = cli.is_some_and(|cli| cli.tui);
+    let outfile = args.outfile_path()?;
+    args.format = args.format.for_outfile(outfile.as_deref());
+    let sinks = sinks::parse_all(&args.sinks)?;
+    output::check_outfile(args.format, outfile.as_deref())?;
+    output::check_framing(args.format, args.length_prefixed)?;
+    output::check_list_delimiter(args.format, args.list_delimiter.as_deref())?;
+    let checkpoint = cli.and_then(|cli| cli.checkpoint.as_ref().map(|path| (path, cli)));
+    args.check_streaming(tui, checkpoint.is_some(), profile.is_some())?;
+    if let Some((path, cli)) = checkpoint {
+        if args.sample_pages.is_some() {
+            return Err(Failure::Config(anyhow!(
+                "--checkpoint can't resume a --sample-pages run, the sampled pages differ every time"
+            ))
+            .into());
+        }
+        let checkpoint = Checkpoint::open(
+            path,
+            &args.feed_url(),
+            args.pages()?,
+            cli.resume.unwrap_or_default(),
+            cli.force,
+            outfile.as_deref(),
+        )?;
+        args.checkpoint = Some(Arc::new(checkpoint));
+    }
+    let hooks = Hooks {
+        on_complete: args.on_complete.as_deref(),
+        on_error: args.on_error.as_deref(),
+        url: args.feed_url(),
+        site: args.site.name(),
+        outfile: outfile.as_deref(),
+    };
+    hooks.check()?;
+
+    let result = scrape_and_write(
+        rt,
+        tui,
+        profile.zip(cli.and_then(|cli| cli.profile.as_deref())),
+        &args,
+        outfile.as_deref(),
+        &sinks,
+    );
+    hooks.run(&result)?;
+    match (result?.errors, args.fail_on_error) {
+        (0, _) => Ok(()),
+        (errors, false) => Err(Failure::ItemErrors(errors).into()),
+        (errors, true) => Err(Failure::FailedOnError(errors).into()),
+    }
+}
 
-    Ok(())
+// jobs share the config's rules and rate limits; every job runs even after one fails, the first
+// failure is returned for the exit code and the rest are printed
+fn run_jobs(rt: &tokio::runtime::Runtime, path: &Path) -> Result<()> {
+    let config = Config::load(Some(path))?;
+    if config.jobs.is_empty() {
+        return Err(Failure::Config(anyhow!("{:?} does not define any jobs", path)).into());
+    }
+    let limiter = config.rate_limiter()?;
+    let mut jobs = Vec::new();
+    for job in config.jobs {
+        if job.schedule.is_some() || job.state.is_some() {
+            eprintln!(
+                "Warning: job {:?} runs once, its schedule and state only apply under watch",
+                job.name
+            );
+        }
+        let name = job.name.clone();
+        let mut args = job.into_scrape_args()?;
+        args.resolve_rules(&config.rules)?;
+        args.limiter = limiter.clone();
+        // parallel jobs writing to stdout would interleave their output
+        if config.parallel && args.outfile.is_none() {
+            return Err(Failure::Config(anyhow!(
+                "Job {:?} needs an outfile to run in parallel",
+                name
+            ))
+            .into());
+        }
+        jobs.push((name, args));
+    }
+
+    let run_job = |(name, args): (String, ScrapeArgs)| {
+        run_scrape(rt, None, None, args).with_context(|| format!("Job {:?} failed", name))
+    };
+    let results: Vec<Result<()>> = match config.parallel {
+        true => thread::scope(|scope| {
+            let handles: Vec<_> = jobs
+                .into_iter()
+                .map(|job| scope.spawn(move || run_job(job)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("job thread panicked"))
+                .collect()
+        }),
+        false => jobs.into_iter().map(run_job).collect(),
+    };
+
+    let mut failure = None;
+    for err in results.into_iter().filter_map(Result::err) {
+        match failure {
+            None => failure = Some(err),
+            Some(_) => eprintln!("Error: {:?}", err),
+        }
+    }
+    failure.map_or(Ok(()), Err)
+}
+
+fn scrape_and_write(
+    rt: &tokio::runtime::Runtime,
+    tui: bool,
+    profile: Option<(Profile, &str)>,
+    args: &ScrapeArgs,
+    outfile: Option<&Path>,
+    sinks: &[Sink],
+) -> Result<RunSummary> {
+    if args.stream {
+        return stream_and_write(rt, args, outfile);
+    }
+
+    let scraped = if tui {
+        scrape_with_dashboard(rt, args.clone())
+    } else {
+        let reporter = CountingReporter::new(args.reporter());
+        rt.block_on(scrape(args, &reporter))
+            .map(|records| (records, reporter.errors()))
+    };
+
+    let mut seen = None;
+    if let Some((profile, name)) = profile {
+        let notifier = Notifier::new(profile.notifications, profile.slack, profile.email);
+        let mut state = IncrementalState::load(profile.state)?;
+        rt.block_on(async {
+            match &scraped {
+                Ok((records, _)) => {
+                    let new = state.unseen(records);
+                    let (changed, price_drops) = state.take_changed(records);
+                    notifier.new_games(&new).await;
+                    notifier.price_drops(&price_drops).await;
+                    notifier
+                        .run_summary(name, records.len(), changed.len())
+                        .await;
+                }
+                Err(err) => notifier.scrape_failure(name, err).await,
+            }
+        });
+        seen = Some(state);
+    }
+    let (mut itch_data, errors) = scraped?;
+    // only records whose content changed since the last run are written
+    let snapshots = match &args.snapshot {
+        Some(path) => {
+            let mut store = SnapshotStore::load(path)?;
+            let (changed, summary) = store.take_changed(itch_data)?;
+            if !args.quiet {
+                eprintln!("{}", summary.render());
+            }
+            itch_data = changed;
+            Some(store)
+        }
+        None => None,
+    };
+    output::write(
+        args.format,
+        outfile,
+        &itch_data,
+        args.concurrency().sink,
+        args.length_prefixed,
+        args.list_delimiter
+            .as_deref()
+            .unwrap_or(output::DEFAULT_LIST_DELIMITER),
+    )?;
+    rt.block_on(sinks::send_all(sinks, &itch_data))?;
+    if let Some(store) = &snapshots {
+        store.save()?;
+    }
+    if let Some(state) = &seen {
+        state.save()?;
+    }
+    if let Some(checkpoint) = &args.checkpoint {
+        checkpoint.finish()?;
+    }
+
+    Ok(RunSummary {
+        records: itch_data.len(),
+        errors,
+    })
+}
+
+fn stream_and_write(
+    rt: &tokio::runtime::Runtime,
+    args: &ScrapeArgs,
+    outfile: Option<&Path>,
+) -> Result<RunSummary> {
+    let mut out = output::open(outfile)?;
+    let reporter = CountingReporter::new(args.reporter());
+    let written = rt.block_on(scrape_streaming(args, &mut out, &reporter))?;
+
+    Ok(RunSummary {
+        records: written,
+        errors: reporter.errors(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_range_that_overflows_is_a_config_error() {
+        let args = ScrapeArgs {
+            start_page: Some(i32::MAX - 10),
+            page_limit: Some(300),
+            ..ScrapeArgs::default()
+        };
+        let err = args.pages().unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Failure>(),
+            Some(Failure::Config(_))
+        ));
+
+        let args = ScrapeArgs {
+            start_page: Some(i32::MAX - 10),
+            page_limit: Some(11),
+            ..ScrapeArgs::default()
+        };
+        assert_eq!(args.pages().unwrap(), i32::MAX - 10..=i32::MAX);
+    }
+
+    #[test]
+    fn job_concurrency_sets_the_scrape_jobs() {
+        let args = |keys: &str| {
+            let toml = format!("name = \"a\"\nsite = \"itch\"\nurl = \"u\"\n{}", keys);
+            toml::from_str::<config::JobConfig>(&toml)
+                .unwrap()
+                .into_scrape_args()
+        };
+
+        assert_eq!(args("concurrency = 4").unwrap().jobs, Some(4));
+        assert_eq!(args("concurrency = 4\njobs = 2").unwrap().jobs, Some(2));
+        assert_eq!(args("").unwrap().jobs, None);
+        assert!(args("concurrency = 0").is_err());
+    }
+
+    #[test]
+    fn streaming_rejects_a_profile() {
+        let args = ScrapeArgs {
+            stream: true,
+            format: OutputFormat::Ndjson,
+            ..ScrapeArgs::default()
+        };
+        assert!(args.check_streaming(false, false, false).is_ok());
+
+        let err = args.check_streaming(false, false, true).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Failure>(),
+            Some(Failure::Config(_))
+        ));
+    }
 }
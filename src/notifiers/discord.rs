@@ -0,0 +1,59 @@
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::Result;
+use reqwest::Client;
+use serde::Serialize;
+
+// Discord rejects messages with more than 10 embeds
+const MAX_EMBEDS: usize = 10;
+
+#[derive(Serialize, Debug)]
+struct WebhookMessage<'a> {
+    content: String,
+    embeds: Vec<Embed<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+struct Embed<'a> {
+    title: &'a str,
+    url: &'a str,
+    description: String,
+}
+
+pub async fn send_games(
+    client: &Client,
+    webhook: &str,
+    rule_name: &str,
+    games: &[&ItchData],
+) -> Result<()> {
+    for chunk in games.chunks(MAX_EMBEDS) {
+        let message = WebhookMessage {
+            content: format!("{} new game(s) matching **{}**", chunk.len(), rule_name),
+            embeds: chunk.iter().map(|game| game_embed(game)).collect(),
+        };
+
+        client
+            .post(webhook)
+            .json(&message)
+            .send()
+            .await?
+            .error_for_status()?;
+    }
+
+    Ok(())
+}
+
+fn game_embed(game: &ItchData) -> Embed<'_> {
+    let mut description = format!("Price: {}", game.price);
+    if !game.platforms.is_empty() {
+        description.push_str(&format!("\nPlatforms: {}", game.platforms.join(", ")));
+    }
+    if !game.tags.is_empty() {
+        description.push_str(&format!("\nTags: {}", game.tag_names().join(", ")));
+    }
+
+    Embed {
+        title: &game.plain_title,
+        url: &game.link,
+        description,
+    }
+}
@@ -0,0 +1,25 @@
+use super::EmailConfig;
+use anyhow::Result;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+pub async fn send(config: &EmailConfig, subject: &str, body: String) -> Result<()> {
+    let mut builder = Message::builder()
+        .from(config.from.parse()?)
+        .subject(subject);
+    for to in &config.to {
+        builder = builder.to(to.parse()?);
+    }
+    let message = builder.body(body)?;
+
+    let mut transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)?;
+    if let Some(port) = config.smtp_port {
+        transport = transport.port(port);
+    }
+    if let (Some(username), Some(password)) = (&config.username, &config.password) {
+        transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+
+    transport.build().send(message).await?;
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use crate::filters::Filter;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use reqwest::Client;
+use serde::Deserialize;
+
+pub mod discord;
+pub mod email;
+pub mod slack;
+
+#[derive(Deserialize, Debug)]
+pub struct NotificationRule {
+    pub name: String,
+    #[serde(default)]
+    pub filter: Filter,
+    pub discord_webhook: Option<String>,
+    pub slack_webhook: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SlackConfig {
+    pub webhook: String,
+    #[serde(default)]
+    pub run_summaries: bool,
+    #[serde(default)]
+    pub scrape_failures: bool,
+    #[serde(default)]
+    pub price_drops: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EmailConfig {
+    pub smtp_host: String,
+    pub smtp_port: Option<u16>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(default)]
+    pub run_summaries: bool,
+    #[serde(default)]
+    pub scrape_failures: bool,
+}
+
+#[derive(Debug)]
+pub struct PriceDrop<'a> {
+    pub record: &'a ItchData,
+    pub previous_price: String,
+}
+
+#[derive(Default)]
+pub struct Notifier {
+    client: Client,
+    rules: Vec<NotificationRule>,
+    slack: Option<SlackConfig>,
+    email: Option<EmailConfig>,
+}
+
+impl Notifier {
+    pub fn new(
+        rules: Vec<NotificationRule>,
+        slack: Option<SlackConfig>,
+        email: Option<EmailConfig>,
+    ) -> Notifier {
+        Notifier {
+            client: Client::new(),
+            rules,
+            slack,
+            email,
+        }
+    }
+
+    pub async fn new_games(&self, records: &[&ItchData]) {
+        for rule in &self.rules {
+            let matches: Vec<&ItchData> = records
+                .iter()
+                .copied()
+                .filter(|record| rule.filter.matches(record))
+                .collect();
+
+            if matches.is_empty() {
+                continue;
+            }
+
+            if let Some(webhook) = &rule.discord_webhook {
+                if let Err(err) =
+                    discord::send_games(&self.client, webhook, &rule.name, &matches).await
+                {
+                    eprintln!(
+                        "Error sending Discord notification for rule {:?}: {:?}",
+                        rule.name, err
+                    );
+                }
+            }
+
+            if let Some(webhook) = &rule.slack_webhook {
+                let text = slack::format_games(&rule.name, &matches);
+                self.send_slack(webhook, &text).await;
+            }
+        }
+    }
+
+    pub async fn price_drops(&self, drops: &[PriceDrop<'_>]) {
+        if let Some(slack) = self.slack.as_ref().filter(|s| s.price_drops) {
+            if !drops.is_empty() {
+                self.send_slack(&slack.webhook, &slack::format_price_drops(drops))
+                    .await;
+            }
+        }
+    }
+
+    pub async fn run_summary(&self, job: &str, scraped: usize, changed: usize) {
+        if let Some(slack) = self.slack.as_ref().filter(|s| s.run_summaries) {
+            let text = format!(
+                "Finished scraping *{}*: {} record(s), {} new or changed",
+                job, scraped, changed
+            );
+            self.send_slack(&slack.webhook, &text).await;
+        }
+
+        if let Some(email) = self.email.as_ref().filter(|e| e.run_summaries) {
+            let subject = format!("Finished scraping {}", job);
+            let body = format!("Scraped {} record(s), {} new or changed.", scraped, changed);
+            self.send_email(email, &subject, body).await;
+        }
+    }
+
+    pub async fn scrape_failure(&self, job: &str, err: &anyhow::Error) {
+        if let Some(slack) = self.slack.as_ref().filter(|s| s.scrape_failures) {
+            let text = format!("Scraping *{}* failed: `{}`", job, err);
+            self.send_slack(&slack.webhook, &text).await;
+        }
+
+        if let Some(email) = self.email.as_ref().filter(|e| e.scrape_failures) {
+            let subject = format!("Scraping {} failed", job);
+            self.send_email(email, &subject, format!("{:?}", err)).await;
+        }
+    }
+
+    async fn send_email(&self, config: &EmailConfig, subject: &str, body: String) {
+        if let Err(err) = email::send(config, subject, body).await {
+            eprintln!("Error sending email notification: {:?}", err);
+        }
+    }
+
+    async fn send_slack(&self, webhook: &str, text: &str) {
+        if let Err(err) = slack::send_text(&self.client, webhook, text).await {
+            eprintln!("Error sending Slack notification: {:?}", err);
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use super::PriceDrop;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::Result;
+use reqwest::Client;
+use serde_json::json;
+
+pub async fn send_text(client: &Client, webhook: &str, text: &str) -> Result<()> {
+    client
+        .post(webhook)
+        .json(&json!({ "text": text }))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+pub fn format_games(rule_name: &str, games: &[&ItchData]) -> String {
+    let mut text = format!("{} new game(s) matching *{}*", games.len(), rule_name);
+    for game in games {
+        text.push_str(&format!(
+            "\n• <{}|{}> ({})",
+            game.link, game.plain_title, game.price
+        ));
+    }
+
+    text
+}
+
+pub fn format_price_drops(drops: &[PriceDrop]) -> String {
+    let mut text = format!("{} price drop(s)", drops.len());
+    for drop in drops {
+        text.push_str(&format!(
+            "\n• <{}|{}>: {} → {}",
+            drop.record.link, drop.record.plain_title, drop.previous_price, drop.record.price
+        ));
+    }
+
+    text
+}
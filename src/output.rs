@@ -0,0 +1,199 @@
+use crate::avro;
+use crate::columnar;
+use crate::commands::grpc::proto::GameRecord;
+use crate::dataset::{create_parent_dirs, to_json_batched};
+use crate::db;
+use crate::exit::Failure;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, Context, Result};
+use arrow::ipc::writer::StreamWriter;
+use clap::ValueEnum;
+use prost::Message;
+use serde::Deserialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+#[derive(Debug, ValueEnum, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    ArrowStream,
+    Avro,
+    Protobuf,
+    Msgpack,
+    Cbor,
+    Ndjson,
+    Csv,
+    Sqlite,
+    Duckdb,
+}
+
+impl OutputFormat {
+    // an --outfile ending in .db, .sqlite or .sqlite3 is written as a SQLite database and one
+    // ending in .duckdb as a DuckDB one, unless another format was asked for
+    pub fn for_outfile(self, outfile: Option<&Path>) -> OutputFormat {
+        let extension = outfile
+            .and_then(|path| path.extension())
+            .and_then(|ext| ext.to_str());
+        match (self, extension) {
+            (OutputFormat::Json, Some("db" | "sqlite" | "sqlite3")) => OutputFormat::Sqlite,
+            (OutputFormat::Json, Some("duckdb")) => OutputFormat::Duckdb,
+            (format, _) => format,
+        }
+    }
+}
+
+// list cells in csv output, chosen so a list never needs quoting on its own
+pub const DEFAULT_LIST_DELIMITER: &str = "; ";
+
+pub fn check_framing(format: OutputFormat, length_prefixed: bool) -> Result<()> {
+    match (format, length_prefixed) {
+        (OutputFormat::Msgpack | OutputFormat::Cbor, _) | (_, false) => Ok(()),
+        (format, true) => Err(Failure::Config(anyhow!(
+            "--length-prefixed only applies to --format msgpack and cbor, not {}",
+            format
+                .to_possible_value()
+                .map_or(String::new(), |value| value.get_name().to_string())
+        ))
+        .into()),
+    }
+}
+
+pub fn check_list_delimiter(format: OutputFormat, list_delimiter: Option<&str>) -> Result<()> {
+    match (format, list_delimiter) {
+        (_, None) | (OutputFormat::Csv, Some(_)) => Ok(()),
+        (_, Some(_)) => {
+            Err(Failure::Config(anyhow!("--list-delimiter only applies to --format csv")).into())
+        }
+    }
+}
+
+pub fn check_outfile(format: OutputFormat, outfile: Option<&Path>) -> Result<()> {
+    match (format, outfile) {
+        (OutputFormat::Sqlite, None) => Err(Failure::Config(anyhow!(
+            "--format sqlite needs an --outfile for the database"
+        ))
+        .into()),
+        (OutputFormat::Duckdb, None) => Err(Failure::Config(anyhow!(
+            "--format duckdb needs an --outfile for the database"
+        ))
+        .into()),
+        (OutputFormat::Duckdb, Some(_)) => columnar::check_duckdb(),
+        _ => Ok(()),
+    }
+}
+
+// the outfile, or stdout without one
+pub fn open(outfile: Option<&Path>) -> Result<Box<dyn Write>> {
+    Ok(match outfile {
+        Some(path) => {
+            create_parent_dirs(path)?;
+            Box::new(BufWriter::new(
+                File::create(path).with_context(|| format!("Failed to create {:?}", path))?,
+            ))
+        }
+        None => Box::new(io::stdout().lock()),
+    })
+}
+
+// writes the scraped records
+pub fn write(
+    format: OutputFormat,
+    outfile: Option<&Path>,
+    records: &[ItchData],
+    jobs: usize,
+    length_prefixed: bool,
+    list_delimiter: &str,
+) -> Result<()> {
+    // games already in the database are replaced, so re-scrapes update it in place
+    if let OutputFormat::Sqlite = format {
+        let path = outfile.ok_or_else(|| anyhow!("--format sqlite needs an --outfile"))?;
+        create_parent_dirs(path)?;
+        let mut conn = db::open(path)?;
+        return db::insert_records(&mut conn, records);
+    }
+    if let OutputFormat::Duckdb = format {
+        let path = outfile.ok_or_else(|| anyhow!("--format duckdb needs an --outfile"))?;
+        create_parent_dirs(path)?;
+        return columnar::write_duckdb(path, records);
+    }
+
+    let mut out = open(outfile)?;
+
+    match format {
+        OutputFormat::Json => out.write_all(to_json_batched(records, jobs)?.as_bytes())?,
+        // the IPC stream format can be read as it arrives, e.g. pyarrow.ipc.open_stream(sys.stdin.buffer)
+        OutputFormat::ArrowStream => {
+            let batch = columnar::record_batch(records)?;
+            let mut writer = StreamWriter::try_new(&mut out, &batch.schema())?;
+            writer.write(&batch)?;
+            writer.finish()?;
+        }
+        OutputFormat::Sqlite | OutputFormat::Duckdb => {
+            unreachable!("databases are written before opening the outfile")
+        }
+        OutputFormat::Avro => avro::write_container(&mut out, records)?,
+        OutputFormat::Ndjson => {
+            for record in records {
+                serde_json::to_writer(&mut out, record)?;
+                out.write_all(b"\n")?;
+            }
+        }
+        // the same flat columns as the spreadsheet sinks, one row per record
+        OutputFormat::Csv => {
+            write_csv_row(&mut out, columnar::header())?;
+            for record in records {
+                write_csv_row(&mut out, columnar::delimited_row(record, list_delimiter))?;
+            }
+        }
+        // varint length-delimited GameRecord messages, the `proto` command prints their definition
+        OutputFormat::Protobuf => {
+            for record in records {
+                out.write_all(&GameRecord::from(record.clone()).encode_length_delimited_to_vec())?;
+            }
+        }
+        // field-named maps, back to back or each behind a 4-byte big-endian length; back to back
+        // CBOR items are a CBOR sequence (RFC 8742)
+        OutputFormat::Msgpack | OutputFormat::Cbor => {
+            for record in records {
+                let bytes = match format {
+                    OutputFormat::Msgpack => rmp_serde::to_vec_named(record)?,
+                    _ => {
+                        let mut bytes = Vec::new();
+                        ciborium::into_writer(record, &mut bytes)?;
+                        bytes
+                    }
+                };
+                if length_prefixed {
+                    out.write_all(&(bytes.len() as u32).to_be_bytes())?;
+                }
+                out.write_all(&bytes)?;
+            }
+        }
+    }
+    out.flush()?;
+
+    Ok(())
+}
+
+// RFC 4180: cells holding the separator, a quote or a line break are quoted, quotes doubled
+fn write_csv_row<S: AsRef<str>>(
+    out: &mut dyn Write,
+    cells: impl IntoIterator<Item = S>,
+) -> Result<()> {
+    let row: Vec<String> = cells
+        .into_iter()
+        .map(|cell| {
+            let cell = cell.as_ref();
+            match cell.contains([',', '"', '\n', '\r']) {
+                true => format!("\"{}\"", cell.replace('"', "\"\"")),
+                false => cell.to_string(),
+            }
+        })
+        .collect();
+    out.write_all(row.join(",").as_bytes())?;
+    out.write_all(b"\r\n")?;
+    Ok(())
+}
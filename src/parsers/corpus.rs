@@ -0,0 +1,61 @@
+use crate::parsers::itch_game_info_parser::{parse_itch_game_comments, parse_itch_game_page_data};
+use anyhow::{Context, Result};
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// saved itch game pages, each next to a .json file holding what the parser is expected to
+// extract from it
+pub const CORPUS_DIR: &str = "tests/fixtures/pages";
+
+#[derive(Debug)]
+pub struct GoldenCase {
+    pub name: String,
+    pub page: PathBuf,
+    pub golden: PathBuf,
+}
+
+impl GoldenCase {
+    pub fn render(&self) -> Result<String> {
+        let html = fs::read_to_string(&self.page)
+            .with_context(|| format!("Failed to read {:?}", self.page))?;
+        Ok(render(&html))
+    }
+}
+
+pub fn cases(dir: &Path) -> Result<Vec<GoldenCase>> {
+    let mut cases = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {:?}", dir))? {
+        let page = entry?.path();
+        if page
+            .extension()
+            .is_some_and(|extension| extension == "html")
+        {
+            cases.push(GoldenCase {
+                name: page
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned(),
+                golden: page.with_extension("json"),
+                page,
+            });
+        }
+    }
+    cases.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(cases)
+}
+
+// parse errors are part of the expected output, so pages the parser rejects are covered too
+pub fn render(html: &str) -> String {
+    let value = match parse_itch_game_page_data(html) {
+        Ok(mut data) => {
+            data.comments = parse_itch_game_comments(html);
+            json!(data)
+        }
+        Err(err) => json!({ "error": err.to_string() }),
+    };
+
+    serde_json::to_string_pretty(&value).unwrap_or_default() + "\n"
+}
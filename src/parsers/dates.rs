@@ -0,0 +1,71 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+
+// naive layouts seen in itch feeds and pages, all of which are UTC
+const UTC_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S", "%d %B %Y @ %H:%M UTC"];
+
+// itch release dates are usually just a day, e.g. "Apr 10, 2024"
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%b %d, %Y", "%B %d, %Y"];
+
+pub fn parse_feed_date(s: &str) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+
+    DateTime::parse_from_rfc2822(s)
+        .or_else(|_| DateTime::parse_from_rfc3339(s))
+        .map(|date| date.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            UTC_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+                .map(|date| date.and_utc())
+        })
+}
+
+fn parse_day(s: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(s.trim(), format).ok())
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum OutputZone {
+    Named(Tz),
+    Fixed(FixedOffset),
+}
+
+pub fn parse_timezone(s: &str) -> Option<OutputZone> {
+    if let Ok(tz) = s.parse::<Tz>() {
+        return Some(OutputZone::Named(tz));
+    }
+
+    DateTime::parse_from_str(&format!("2000-01-01 00:00 {}", s), "%Y-%m-%d %H:%M %:z")
+        .ok()
+        .map(|date| OutputZone::Fixed(*date.offset()))
+}
+
+// re-renders any recognised date as RFC 3339 in the given zone, day-only dates as YYYY-MM-DD;
+// anything unrecognised is left untouched
+pub fn render_date(s: &str, zone: OutputZone) -> String {
+    if let Some(date) = parse_feed_date(s) {
+        return match zone {
+            OutputZone::Named(tz) => date.with_timezone(&tz).to_rfc3339(),
+            OutputZone::Fixed(offset) => date.with_timezone(&offset).to_rfc3339(),
+        };
+    }
+
+    match parse_day(s) {
+        Some(day) => day.format("%Y-%m-%d").to_string(),
+        None => s.to_string(),
+    }
+}
+
+// accepts anything parse_feed_date does, plus bare dates; a bare date used as an end bound
+// covers the whole day
+pub fn parse_date_bound(s: &str, end_of_day: bool) -> Option<DateTime<Utc>> {
+    parse_feed_date(s).or_else(|| {
+        let date = parse_day(s)?;
+        let date = if end_of_day { date.succ_opt()? } else { date };
+        Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+    })
+}
@@ -1,37 +1,189 @@
+use crate::parsers::dates::parse_date_bound;
 use anyhow::Result;
+use async_graphql::SimpleObject;
+use schemars::JsonSchema;
 use scraper::{ElementRef, Html, Selector};
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
 use thiserror::Error;
+use utoipa::ToSchema;
 
 #[derive(Default, Debug, Serialize)]
 pub struct MoreInfoTableData {
     pub status: String,
     pub release_date: String,
+    pub updated_date: String,
+    pub published_date: String,
     pub platforms: Vec<String>,
-    pub rating: ItchRating,
-    pub authors: Vec<String>,
-    pub genres: Vec<String>,
+    pub rating: Option<ItchRating>,
+    pub authors: Vec<Author>,
+    pub genres: Vec<Genre>,
     pub made_with: Vec<String>,
-    pub tags: Vec<String>,
+    pub tags: Vec<Tag>,
     pub average_session: String,
     pub languages: Vec<String>,
     pub inputs: Vec<String>,
     pub links: Vec<Link>,
     pub accessibility: Vec<String>,
+    pub comments: Vec<Comment>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub price: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub cover_image: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub screenshots: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
-#[derive(Default, Debug, Serialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, SimpleObject, JsonSchema, ToSchema)]
 pub struct Link {
     pub name: String,
     pub url: String,
 }
 
-#[derive(Default, Debug, Serialize)]
+// the creator page is the stable identity, display names can collide
+#[derive(Default, Debug, Clone, Serialize, Deserialize, SimpleObject, JsonSchema, ToSchema)]
+#[serde(from = "AuthorEntry")]
+pub struct Author {
+    pub name: String,
+    pub url: String,
+}
+
+// records written before author urls were kept list bare names
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AuthorEntry {
+    Name(String),
+    Author {
+        name: String,
+        #[serde(default)]
+        url: String,
+    },
+}
+
+impl Author {
+    // by creator page when both have one, by name otherwise
+    pub fn is_same(&self, other: &Author) -> bool {
+        match (self.url.is_empty(), other.url.is_empty()) {
+            (false, false) => self.url.eq_ignore_ascii_case(&other.url),
+            _ => self.name.eq_ignore_ascii_case(&other.name),
+        }
+    }
+}
+
+impl From<AuthorEntry> for Author {
+    fn from(entry: AuthorEntry) -> Author {
+        match entry {
+            AuthorEntry::Name(name) => Author {
+                name,
+                url: String::new(),
+            },
+            AuthorEntry::Author { name, url } => Author { name, url },
+        }
+    }
+}
+
+// the slug is what itch browse urls use, e.g. /games/tag-pixel-art, and doesn't change with the
+// display name
+#[derive(
+    Default, Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, JsonSchema, ToSchema,
+)]
+#[serde(from = "TagEntry")]
+pub struct Tag {
+    pub name: String,
+    pub slug: String,
+    pub url: String,
+}
+
+// records written before tag slugs were kept list bare names
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TagEntry {
+    Name(String),
+    Tag {
+        name: String,
+        #[serde(default)]
+        slug: String,
+        #[serde(default)]
+        url: String,
+    },
+}
+
+impl From<TagEntry> for Tag {
+    fn from(entry: TagEntry) -> Tag {
+        match entry {
+            TagEntry::Name(name) => Tag {
+                name,
+                ..Tag::default()
+            },
+            TagEntry::Tag { name, slug, url } => Tag { name, slug, url },
+        }
+    }
+}
+
+impl Tag {
+    pub fn matches(&self, wanted: &str) -> bool {
+        self.name.eq_ignore_ascii_case(wanted)
+            || (!self.slug.is_empty() && self.slug.eq_ignore_ascii_case(wanted))
+    }
+}
+
+// the url is the genre's browse page, which discovery jobs follow to find related games
+#[derive(
+    Default, Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, JsonSchema, ToSchema,
+)]
+#[serde(from = "GenreEntry")]
+pub struct Genre {
+    pub name: String,
+    pub url: String,
+}
+
+// records written before genre urls were kept list bare names
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GenreEntry {
+    Name(String),
+    Genre {
+        name: String,
+        #[serde(default)]
+        url: String,
+    },
+}
+
+impl From<GenreEntry> for Genre {
+    fn from(entry: GenreEntry) -> Genre {
+        match entry {
+            GenreEntry::Name(name) => Genre {
+                name,
+                ..Genre::default()
+            },
+            GenreEntry::Genre { name, url } => Genre { name, url },
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize, SimpleObject, JsonSchema, ToSchema)]
+pub struct Comment {
+    pub author: String,
+    pub body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentiment: Option<f32>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize, SimpleObject, JsonSchema, ToSchema)]
 pub struct ItchRating {
     pub score: f32,
     pub count: i32,
 }
 
+// records written before ratings were optional hold a 0/0 rating for unrated games
+pub fn deserialize_rating<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<ItchRating>, D::Error> {
+    let rating = Option::<ItchRating>::deserialize(deserializer)?;
+    Ok(rating.filter(|rating| rating.score != 0.0 || rating.count != 0))
+}
+
 #[derive(Error, Debug)]
 pub enum ItchHTMLDataFormatError {
     #[error("Unknown data type found in Itch.io TD element: {data:?})")]
@@ -50,9 +202,11 @@ pub enum ItchHTMLDataFormatError {
     },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum ItchTableData {
     ReleaseDate,
+    Updated,
+    Published,
     Status,
     Platforms,
     Rating,
@@ -72,6 +226,8 @@ impl ItchTableData {
         match s {
             "Status" => Some(ItchTableData::Status),
             "Release date" => Some(ItchTableData::ReleaseDate),
+            "Updated" => Some(ItchTableData::Updated),
+            "Published" => Some(ItchTableData::Published),
             "Accessibility" => Some(ItchTableData::Accessibility),
             "Platforms" => Some(ItchTableData::Platforms),
             "Rating" => Some(ItchTableData::Rating),
@@ -87,11 +243,136 @@ impl ItchTableData {
             "Language" => Some(ItchTableData::Languages),
             "Inputs" => Some(ItchTableData::Inputs),
             "Links" => Some(ItchTableData::Links),
-            _ => None,
+            _ => LOCALIZED_LABELS
+                .iter()
+                .find(|(label, _)| *label == s)
+                .map(|(_, data_type)| *data_type),
+        }
+    }
+
+    // rows whose label isn't known in any locale are recognized by the itch browse links in
+    // their value cell, e.g. /games/platform-windows
+    fn from_cell(el: ElementRef) -> Option<ItchTableData> {
+        let rating_selector = Selector::parse(r#"[itemprop="aggregateRating"]"#).unwrap();
+        let anchor_selector = Selector::parse("a[href]").unwrap();
+
+        if el.select(&rating_selector).next().is_some() {
+            return Some(ItchTableData::Rating);
+        }
+
+        let href = el.select(&anchor_selector).next()?.value().attr("href")?;
+        let (host, path) = href.split_once("://").map_or(("", href), |(_, rest)| {
+            rest.split_once('/').unwrap_or((rest, ""))
+        });
+        if host.ends_with(".itch.io") {
+            return Some(ItchTableData::Authors);
+        }
+
+        let browse = path.trim_start_matches('/').strip_prefix("games/")?;
+        let prefixes = [
+            ("platform-", ItchTableData::Platforms),
+            ("genre-", ItchTableData::Genres),
+            ("made-with-", ItchTableData::MadeWith),
+            ("tag-", ItchTableData::Tags),
+            ("lang-", ItchTableData::Languages),
+            ("input-", ItchTableData::Inputs),
+            ("accessibility-", ItchTableData::Accessibility),
+        ];
+        match prefixes
+            .iter()
+            .find(|(prefix, _)| browse.starts_with(prefix))
+        {
+            Some((_, data_type)) => Some(*data_type),
+            None => match browse {
+                "released" | "in-development" | "prototype" | "on-hold" | "canceled" => {
+                    Some(ItchTableData::Status)
+                }
+                _ => None,
+            },
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ItchTableData::Status => "Status",
+            ItchTableData::ReleaseDate => "Release date",
+            ItchTableData::Updated => "Updated",
+            ItchTableData::Published => "Published",
+            ItchTableData::Accessibility => "Accessibility",
+            ItchTableData::Platforms => "Platforms",
+            ItchTableData::Rating => "Rating",
+            ItchTableData::Authors => "Authors",
+            ItchTableData::Genres => "Genres",
+            ItchTableData::MadeWith => "Made with",
+            ItchTableData::Tags => "Tags",
+            ItchTableData::AverageSession => "Average session",
+            ItchTableData::Languages => "Languages",
+            ItchTableData::Inputs => "Inputs",
+            ItchTableData::Links => "Links",
         }
     }
 }
 
+// row labels of the pages itch serves to non-English locales
+const LOCALIZED_LABELS: &[(&str, ItchTableData)] = &[
+    // French
+    ("Statut", ItchTableData::Status),
+    ("Date de sortie", ItchTableData::ReleaseDate),
+    ("Mis à jour", ItchTableData::Updated),
+    ("Publié", ItchTableData::Published),
+    ("Plateformes", ItchTableData::Platforms),
+    ("Évaluation", ItchTableData::Rating),
+    ("Note", ItchTableData::Rating),
+    ("Auteur", ItchTableData::Authors),
+    ("Auteurs", ItchTableData::Authors),
+    ("Fait avec", ItchTableData::MadeWith),
+    ("Étiquettes", ItchTableData::Tags),
+    ("Session moyenne", ItchTableData::AverageSession),
+    ("Langue", ItchTableData::Languages),
+    ("Langues", ItchTableData::Languages),
+    ("Entrées", ItchTableData::Inputs),
+    ("Liens", ItchTableData::Links),
+    ("Accessibilité", ItchTableData::Accessibility),
+    // German
+    ("Veröffentlichungsdatum", ItchTableData::ReleaseDate),
+    ("Aktualisiert", ItchTableData::Updated),
+    ("Veröffentlicht", ItchTableData::Published),
+    ("Plattformen", ItchTableData::Platforms),
+    ("Bewertung", ItchTableData::Rating),
+    ("Autor", ItchTableData::Authors),
+    ("Autoren", ItchTableData::Authors),
+    ("Erstellt mit", ItchTableData::MadeWith),
+    ("Durchschnittliche Sitzung", ItchTableData::AverageSession),
+    ("Sprache", ItchTableData::Languages),
+    ("Sprachen", ItchTableData::Languages),
+    ("Eingaben", ItchTableData::Inputs),
+    ("Barrierefreiheit", ItchTableData::Accessibility),
+    // Spanish and Portuguese
+    ("Estado", ItchTableData::Status),
+    ("Fecha de lanzamiento", ItchTableData::ReleaseDate),
+    ("Data de lançamento", ItchTableData::ReleaseDate),
+    ("Actualizado", ItchTableData::Updated),
+    ("Atualizado", ItchTableData::Updated),
+    ("Publicado", ItchTableData::Published),
+    ("Plataformas", ItchTableData::Platforms),
+    ("Valoración", ItchTableData::Rating),
+    ("Avaliação", ItchTableData::Rating),
+    ("Autores", ItchTableData::Authors),
+    ("Género", ItchTableData::Genres),
+    ("Gênero", ItchTableData::Genres),
+    ("Hecho con", ItchTableData::MadeWith),
+    ("Feito com", ItchTableData::MadeWith),
+    ("Etiquetas", ItchTableData::Tags),
+    ("Sesión promedio", ItchTableData::AverageSession),
+    ("Sessão média", ItchTableData::AverageSession),
+    ("Idioma", ItchTableData::Languages),
+    ("Idiomas", ItchTableData::Languages),
+    ("Entradas", ItchTableData::Inputs),
+    ("Enlaces", ItchTableData::Links),
+    ("Accesibilidad", ItchTableData::Accessibility),
+    ("Acessibilidade", ItchTableData::Accessibility),
+];
+
 pub fn parse_itch_game_page_data(
     raw_html: &str,
 ) -> Result<MoreInfoTableData, ItchHTMLDataFormatError> {
@@ -100,62 +381,168 @@ pub fn parse_itch_game_page_data(
     let td_selector = Selector::parse("td").unwrap();
 
     let mut itch_data = MoreInfoTableData::default();
+    // a bad row is skipped with a warning, the page only fails when no row could be read
+    let mut parsed_rows = 0;
+    let mut first_error = None;
 
-    for tr in document.select(&tr_selector) {
+    for (index, tr) in document.select(&tr_selector).enumerate() {
         let tds: Vec<ElementRef> = tr.select(&td_selector).collect();
         if tds.len() != 2 {
-            return Err(ItchHTMLDataFormatError::MissingElements);
+            let label = tds
+                .first()
+                .map(|td| td.text().collect::<String>().trim().to_string())
+                .filter(|label| !label.is_empty())
+                .unwrap_or_else(|| format!("row {}", index + 1));
+            let err = ItchHTMLDataFormatError::MissingElements;
+            itch_data
+                .warnings
+                .push(format!("Skipped {}: {}", label, err));
+            first_error.get_or_insert(err);
+            continue;
         }
 
-        let data_type = match parse_row_data_type(tds[0]) {
-            Ok(data) => data,
-            Err(_) => continue,
+        let data_type = match parse_row_data_type(tds[0]).ok() {
+            Some(data_type) => data_type,
+            None => match ItchTableData::from_cell(tds[1]) {
+                Some(data_type) => data_type,
+                None => {
+                    itch_data.warnings.push(format!(
+                        "Ignored unknown row {:?}",
+                        tds[0].text().collect::<String>().trim()
+                    ));
+                    continue;
+                }
+            },
         };
+        let label = data_type.label();
         let data = tds[1];
 
-        match data_type {
-            ItchTableData::ReleaseDate => {
-                itch_data.release_date = data.text().collect::<String>().trim().to_owned()
-            }
-            ItchTableData::Status => {
-                itch_data.status = data.text().collect::<String>().trim().to_owned()
-            }
-            ItchTableData::Accessibility => {
-                itch_data.accessibility = parse_anchor_separated_strings(data)
-            }
-            ItchTableData::Platforms => {
-                itch_data.platforms = parse_anchor_separated_strings(data);
-            }
-            ItchTableData::Rating => itch_data.rating = parse_rating_element(data, data_type)?,
-            ItchTableData::Authors => itch_data.authors = parse_anchor_separated_strings(data),
-            ItchTableData::Genres => itch_data.genres = parse_anchor_separated_strings(data),
-            ItchTableData::MadeWith => itch_data.made_with = parse_anchor_separated_strings(data),
-            ItchTableData::Tags => itch_data.tags = parse_anchor_separated_strings(data),
-            ItchTableData::AverageSession => {
-                itch_data.average_session = data.text().collect::<String>().trim().to_owned()
-            }
-            ItchTableData::Languages => {
-                itch_data.languages = parse_anchor_separated_strings(data);
-            }
-            ItchTableData::Inputs => {
-                itch_data.inputs = parse_anchor_separated_strings(data);
-            }
-            ItchTableData::Links => {
-                itch_data.links = parse_links(data)?;
-            }
+        if let Err(err) = parse_row(&mut itch_data, data_type, data) {
+            itch_data
+                .warnings
+                .push(format!("Skipped {}: {}", label, err));
+            first_error.get_or_insert(err);
+            continue;
+        }
+        parsed_rows += 1;
+
+        // kept as text, but consumers filtering or sorting on it should know it won't parse
+        let date = match data_type {
+            ItchTableData::ReleaseDate => &itch_data.release_date,
+            ItchTableData::Updated => &itch_data.updated_date,
+            ItchTableData::Published => &itch_data.published_date,
+            _ => continue,
+        };
+        if parse_date_bound(date, false).is_none() {
+            itch_data
+                .warnings
+                .push(format!("Unrecognized date in {} row: {:?}", label, date));
         }
     }
 
+    if parsed_rows == 0 {
+        if let Some(err) = first_error {
+            return Err(err);
+        }
+    }
+
+    itch_data.price = parse_price(&document);
+    itch_data.cover_image = parse_cover_image(&document);
+    itch_data.screenshots = parse_screenshots(&document);
+
     Ok(itch_data)
 }
 
+fn parse_row(
+    itch_data: &mut MoreInfoTableData,
+    data_type: ItchTableData,
+    data: ElementRef,
+) -> Result<(), ItchHTMLDataFormatError> {
+    match data_type {
+        ItchTableData::ReleaseDate => {
+            itch_data.release_date = data.text().collect::<String>().trim().to_owned()
+        }
+        ItchTableData::Updated => {
+            itch_data.updated_date = parse_timestamp_element(data, data_type)?
+        }
+        ItchTableData::Published => {
+            itch_data.published_date = parse_timestamp_element(data, data_type)?
+        }
+        ItchTableData::Status => {
+            itch_data.status = data.text().collect::<String>().trim().to_owned()
+        }
+        ItchTableData::Accessibility => {
+            itch_data.accessibility = parse_anchor_separated_strings(data)
+        }
+        ItchTableData::Platforms => {
+            itch_data.platforms = parse_anchor_separated_strings(data);
+        }
+        ItchTableData::Rating => itch_data.rating = parse_optional_rating_element(data, data_type)?,
+        ItchTableData::Authors => itch_data.authors = parse_authors(data),
+        ItchTableData::Genres => itch_data.genres = parse_genres(data),
+        ItchTableData::MadeWith => itch_data.made_with = parse_anchor_separated_strings(data),
+        ItchTableData::Tags => itch_data.tags = parse_tags(data),
+        ItchTableData::AverageSession => {
+            itch_data.average_session = data.text().collect::<String>().trim().to_owned()
+        }
+        ItchTableData::Languages => {
+            itch_data.languages = parse_anchor_separated_strings(data);
+        }
+        ItchTableData::Inputs => {
+            itch_data.inputs = parse_anchor_separated_strings(data);
+        }
+        ItchTableData::Links => {
+            itch_data.links = parse_links(data)?;
+        }
+    }
+
+    Ok(())
+}
+
 fn parse_row_data_type(el: ElementRef) -> Result<ItchTableData, ItchHTMLDataFormatError> {
     let inner_html = el.inner_html();
 
-    if let Some(table_data) = ItchTableData::from_str(&inner_html) {
+    if let Some(table_data) = ItchTableData::from_str(&inner_html)
+        .or_else(|| ItchTableData::from_str(el.text().collect::<String>().trim()))
+    {
         Ok(table_data)
     } else {
-        Err(ItchHTMLDataFormatError::UnknownDataType { data: inner_html }.into())
+        Err(ItchHTMLDataFormatError::UnknownDataType { data: inner_html })
+    }
+}
+
+// unrated games show the row with "No ratings yet" and no aggregate rating markup
+fn parse_optional_rating_element(
+    el: ElementRef,
+    data_type: ItchTableData,
+) -> Result<Option<ItchRating>, ItchHTMLDataFormatError> {
+    let aggregate_selector = Selector::parse(
+        r#"[itemprop="aggregateRating"], [itemprop="ratingValue"], [itemprop="ratingCount"]"#,
+    )
+    .unwrap();
+    if el.select(&aggregate_selector).next().is_none() {
+        return Ok(None);
+    }
+
+    let rating = parse_rating_element(el, data_type)?;
+    Ok(Some(rating).filter(|rating| rating.count > 0))
+}
+
+// the cell text is relative ("2 days ago"), the abbr title holds the exact
+// "02 April 2024 @ 10:00 UTC" timestamp
+fn parse_timestamp_element(
+    el: ElementRef,
+    data_type: ItchTableData,
+) -> Result<String, ItchHTMLDataFormatError> {
+    let abbr_selector = Selector::parse("abbr[title]").unwrap();
+
+    let timestamp = match el.select(&abbr_selector).next() {
+        Some(abbr) => abbr.value().attr("title").unwrap_or_default().to_string(),
+        None => el.text().collect::<String>(),
+    };
+    match timestamp.trim() {
+        "" => Err(ItchHTMLDataFormatError::MissingData { data_type }),
+        timestamp => Ok(timestamp.to_string()),
     }
 }
 
@@ -177,14 +564,13 @@ fn parse_rating_element(
                     return Err(ItchHTMLDataFormatError::InvalidData {
                         data_type,
                         found: score_str.to_string(),
-                    }
-                    .into());
+                    });
                 }
             } else {
-                return Err(ItchHTMLDataFormatError::MissingData { data_type }.into());
+                return Err(ItchHTMLDataFormatError::MissingData { data_type });
             }
         }
-        None => return Err(ItchHTMLDataFormatError::MissingData { data_type }.into()),
+        None => return Err(ItchHTMLDataFormatError::MissingData { data_type }),
     }
 
     match el.select(&count_selector).next() {
@@ -196,14 +582,13 @@ fn parse_rating_element(
                     return Err(ItchHTMLDataFormatError::InvalidData {
                         data_type,
                         found: rating_count.to_string(),
-                    }
-                    .into());
+                    });
                 }
             } else {
-                return Err(ItchHTMLDataFormatError::MissingData { data_type }.into());
+                return Err(ItchHTMLDataFormatError::MissingData { data_type });
             }
         }
-        None => return Err(ItchHTMLDataFormatError::MissingData { data_type }.into()),
+        None => return Err(ItchHTMLDataFormatError::MissingData { data_type }),
     }
 
     Ok(rating)
@@ -213,7 +598,90 @@ fn parse_anchor_separated_strings(el: ElementRef) -> Vec<String> {
     el.text()
         .flat_map(|s| s.split("\n"))
         .map(|s| s.trim().to_string())
-        .filter(|s| s != "," && s.len() > 0)
+        .filter(|s| s != "," && !s.is_empty())
+        .collect()
+}
+
+fn parse_tags(el: ElementRef) -> Vec<Tag> {
+    let anchor_selector = Selector::parse("a").unwrap();
+
+    let tags: Vec<Tag> = el
+        .select(&anchor_selector)
+        .map(|anchor| {
+            let url = anchor.value().attr("href").unwrap_or_default();
+            Tag {
+                name: anchor.text().collect::<String>().trim().to_string(),
+                slug: url
+                    .trim_end_matches('/')
+                    .rsplit('/')
+                    .next()
+                    .and_then(|segment| segment.strip_prefix("tag-"))
+                    .unwrap_or_default()
+                    .to_string(),
+                url: url.to_string(),
+            }
+        })
+        .filter(|tag| !tag.name.is_empty())
+        .collect();
+    if !tags.is_empty() {
+        return tags;
+    }
+
+    parse_anchor_separated_strings(el)
+        .into_iter()
+        .map(|name| Tag {
+            name,
+            ..Tag::default()
+        })
+        .collect()
+}
+
+fn parse_genres(el: ElementRef) -> Vec<Genre> {
+    let anchor_selector = Selector::parse("a").unwrap();
+
+    let genres: Vec<Genre> = el
+        .select(&anchor_selector)
+        .map(|anchor| Genre {
+            name: anchor.text().collect::<String>().trim().to_string(),
+            url: anchor.value().attr("href").unwrap_or_default().to_string(),
+        })
+        .filter(|genre| !genre.name.is_empty())
+        .collect();
+    if !genres.is_empty() {
+        return genres;
+    }
+
+    parse_anchor_separated_strings(el)
+        .into_iter()
+        .map(|name| Genre {
+            name,
+            ..Genre::default()
+        })
+        .collect()
+}
+
+// an author without a creator page is plain text in the cell, kept with an empty url
+fn parse_authors(el: ElementRef) -> Vec<Author> {
+    let anchor_selector = Selector::parse("a").unwrap();
+
+    let authors: Vec<Author> = el
+        .select(&anchor_selector)
+        .map(|anchor| Author {
+            name: anchor.text().collect::<String>().trim().to_string(),
+            url: anchor.value().attr("href").unwrap_or_default().to_string(),
+        })
+        .filter(|author| !author.name.is_empty())
+        .collect();
+    if !authors.is_empty() {
+        return authors;
+    }
+
+    parse_anchor_separated_strings(el)
+        .into_iter()
+        .map(|name| Author {
+            name,
+            url: String::new(),
+        })
         .collect()
 }
 
@@ -232,10 +700,91 @@ fn parse_links(el: ElementRef) -> Result<Vec<Link>, ItchHTMLDataFormatError> {
         } else {
             return Err(ItchHTMLDataFormatError::MissingData {
                 data_type: ItchTableData::Links,
-            }
-            .into());
+            });
         }
     }
 
     Ok(links)
 }
+
+// the buy row's price as displayed, with "or more" kept for pay what you want games; empty
+// for games that can't be bought
+fn parse_price(document: &Html) -> String {
+    let buy_row = Selector::parse(".buy_row").unwrap();
+    let value = Selector::parse(".price_value, .dollars, [itemprop=price]").unwrap();
+    let Some(row) = document.select(&buy_row).next() else {
+        return String::new();
+    };
+
+    let text = row.text().collect::<String>();
+    let price = row
+        .select(&value)
+        .next()
+        .map(|value| value.text().collect::<String>().trim().to_string())
+        .unwrap_or_default();
+    match (price.is_empty(), text.to_lowercase()) {
+        (true, text) if text.contains("name your own price") => "Name your own price".to_string(),
+        (false, text) if text.contains("or more") => format!("{} or more", price),
+        _ => price,
+    }
+}
+
+fn parse_cover_image(document: &Html) -> String {
+    let og_image = Selector::parse(r#"meta[property="og:image"]"#).unwrap();
+
+    document
+        .select(&og_image)
+        .find_map(|meta| meta.value().attr("content"))
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+// the links hold the full size image, the img inside them only a thumbnail
+fn parse_screenshots(document: &Html) -> Vec<String> {
+    let screenshot_selector = Selector::parse("div.screenshot_list a").unwrap();
+    let img_selector = Selector::parse("img").unwrap();
+
+    document
+        .select(&screenshot_selector)
+        .filter_map(|anchor| {
+            anchor.value().attr("href").or_else(|| {
+                anchor
+                    .select(&img_selector)
+                    .next()
+                    .and_then(|img| img.value().attr("src"))
+            })
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+// kept out of parse_itch_game_page_data, a scrape only reads comments when asked to
+pub fn parse_itch_game_comments(raw_html: &str) -> Vec<Comment> {
+    parse_comments(&Html::parse_document(raw_html))
+}
+
+fn parse_comments(document: &Html) -> Vec<Comment> {
+    let post_selector = Selector::parse("div.community_post").unwrap();
+    let author_selector = Selector::parse(".post_author a").unwrap();
+    let body_selector = Selector::parse(".post_body").unwrap();
+
+    document
+        .select(&post_selector)
+        .filter_map(|post| {
+            let body: String = post.select(&body_selector).next()?.text().collect();
+            let author: String = post
+                .select(&author_selector)
+                .next()
+                .map(|a| a.text().collect())
+                .unwrap_or_default();
+
+            Some(Comment {
+                author: author.trim().to_string(),
+                body: body.trim().to_string(),
+                sentiment: None,
+            })
+        })
+        .filter(|comment| !comment.body.is_empty())
+        .collect()
+}
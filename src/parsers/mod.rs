@@ -1 +1,5 @@
+pub mod corpus;
+pub mod dates;
 pub mod itch_game_info_parser;
+pub mod plugin;
+pub mod price;
@@ -0,0 +1,51 @@
+use anyhow::{anyhow, Context, Result};
+use std::fmt;
+use std::path::Path;
+use wasmtime::component::{Component, Linker};
+use wasmtime::{Engine, Store};
+
+mod bindings {
+    wasmtime::component::bindgen!({
+        path: "wit/site-parser.wit",
+        world: "site-parser",
+    });
+}
+
+pub use bindings::GameRecord;
+use bindings::SiteParserPre;
+
+pub struct Plugin {
+    engine: Engine,
+    parser: SiteParserPre<()>,
+}
+
+impl Plugin {
+    pub fn from_file(path: &Path) -> Result<Plugin> {
+        let engine = Engine::default();
+        let component = Component::from_file(&engine, path)
+            .with_context(|| format!("Failed to load plugin {:?}", path))?;
+        let instance = Linker::new(&engine)
+            .instantiate_pre(&component)
+            .with_context(|| format!("Plugin {:?} needs imports that aren't provided", path))?;
+        let parser = SiteParserPre::new(instance)
+            .with_context(|| format!("Plugin {:?} doesn't export parse(html)", path))?;
+
+        Ok(Plugin { engine, parser })
+    }
+
+    // every page gets a fresh instance so one bad page can't leave state behind for the next
+    pub fn parse(&self, html: &str) -> Result<GameRecord> {
+        let mut store = Store::new(&self.engine, ());
+        let parser = self.parser.instantiate(&mut store)?;
+
+        parser
+            .call_parse(&mut store, html)?
+            .map_err(|message| anyhow!(message))
+    }
+}
+
+impl fmt::Debug for Plugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Plugin").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,152 @@
+use async_graphql::SimpleObject;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// longer symbols first so "CA$" isn't read as a plain dollar sign
+const SYMBOLS: [(&str, &str); 14] = [
+    ("CA$", "CAD"),
+    ("AU$", "AUD"),
+    ("NZ$", "NZD"),
+    ("R$", "BRL"),
+    ("C$", "CAD"),
+    ("A$", "AUD"),
+    ("zł", "PLN"),
+    ("$", "USD"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+    ("¥", "JPY"),
+    ("₹", "INR"),
+    ("₩", "KRW"),
+    ("₽", "RUB"),
+];
+
+// a price with no digits at all ("", "N/A") isn't free, only one that says so or is all zeros
+pub fn is_free(price: &str) -> bool {
+    let mut digits = price.chars().filter(|c| c.is_ascii_digit()).peekable();
+    price.to_lowercase().contains("free") || (digits.peek().is_some() && digits.all(|c| c == '0'))
+}
+
+// the first number in the text, whatever the locale: "$1,299.00", "1.299,00 €", "4,99 €" and
+// "1 299 kr" all read as the amount they show
+pub fn price_amount(price: &str) -> Option<f64> {
+    let number: String = price
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit() || ".,' \u{a0}\u{202f}".contains(*c))
+        .collect();
+    let number = number.trim_end_matches(|c: char| !c.is_ascii_digit());
+    // spaces and apostrophes only ever group thousands
+    let number: String = number
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+        .collect();
+
+    // with both separators the last one is the decimal point
+    let decimal = match (number.rfind('.'), number.rfind(',')) {
+        (Some(dot), Some(comma)) => Some(dot.max(comma)),
+        (Some(at), None) | (None, Some(at)) => {
+            let separator = number.as_bytes()[at] as char;
+            let repeated = number.matches(separator).count() > 1;
+            let whole = &number[..at];
+            // a single separator before exactly three digits groups thousands, "1,299" or
+            // "1.299", unless nothing but a zero precedes it
+            let grouping = number.len() - at - 1 == 3 && !whole.trim_start_matches('0').is_empty();
+            (!repeated && !grouping).then_some(at)
+        }
+        (None, None) => None,
+    };
+
+    let amount: String = number
+        .char_indices()
+        .filter_map(|(at, c)| match c {
+            '.' | ',' if Some(at) == decimal => Some('.'),
+            '.' | ',' => None,
+            c => Some(c),
+        })
+        .collect();
+    amount.parse().ok()
+}
+
+// feed prices look like "$5.00 USD", "$0 USD or more", "4,99 €" or "Free"; comma decimals and
+// thousands separators are read by price_amount
+pub fn amount(price: &str) -> Option<f64> {
+    if price.trim().is_empty() {
+        return None;
+    }
+
+    price_amount(price).or_else(|| is_free(price).then_some(0.0))
+}
+
+pub fn currency(price: &str) -> Option<String> {
+    let code = price
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .find(|word| word.len() == 3 && word.chars().all(|c| c.is_ascii_uppercase()));
+    if let Some(code) = code {
+        return Some(code.to_string());
+    }
+
+    SYMBOLS
+        .iter()
+        .find(|(symbol, _)| price.contains(symbol))
+        .map(|(_, code)| code.to_string())
+}
+
+// pay what you want, the amount is only the minimum
+pub fn or_more(price: &str) -> bool {
+    price.to_lowercase().contains("or more")
+}
+
+// pay what you want, with nothing to pay by default
+pub fn name_your_own(price: &str) -> bool {
+    price.to_lowercase().contains("name your own price")
+}
+
+// the price text read once, so consumers don't have to know itch's display formats
+#[derive(
+    Default, Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, JsonSchema, ToSchema,
+)]
+pub struct Price {
+    // the price, or the minimum when pwyw; None when the text holds no number and isn't free
+    pub amount: Option<f64>,
+    // ISO 4217, empty when the text names no currency
+    pub currency: String,
+    pub free: bool,
+    pub pwyw: bool,
+}
+
+impl Price {
+    pub fn parse(price: &str) -> Option<Price> {
+        if price.trim().is_empty() {
+            return None;
+        }
+
+        let pwyw = or_more(price) || name_your_own(price);
+        let amount = match name_your_own(price) {
+            true => amount(price).or(Some(0.0)),
+            false => amount(price),
+        };
+        Some(Price {
+            amount,
+            currency: currency(price).unwrap_or_default(),
+            free: !pwyw && amount == Some(0.0),
+            pwyw,
+        })
+    }
+
+    // the feed price wins, the game page fills in what it left out
+    pub fn from_sources(feed: &str, page: &str) -> Option<Price> {
+        match (Price::parse(feed), Price::parse(page)) {
+            (Some(mut price), Some(page)) => {
+                if price.currency.is_empty() {
+                    price.currency = page.currency;
+                }
+                if price.amount.is_none() {
+                    price.amount = page.amount;
+                }
+                Some(price)
+            }
+            (feed, page) => feed.or(page),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use reqwest::Url;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tokio::time::{sleep, Duration, Instant};
+
+// bytes downloaded per host, and an optional cap on the combined download rate of a scrape
+#[derive(Debug, Default)]
+pub struct Bandwidth {
+    limit: Option<u64>,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    hosts: BTreeMap<String, u64>,
+    // when the bytes already let through will have been paid for at the limit
+    paid_until: Option<Instant>,
+}
+
+impl Bandwidth {
+    pub fn new(bytes_per_second: Option<u64>) -> Bandwidth {
+        Bandwidth {
+            limit: bytes_per_second.filter(|limit| *limit > 0),
+            state: Mutex::default(),
+        }
+    }
+
+    // counts a chunk of a response body and, with a limit, waits until the rate is back under it
+    pub async fn consume(&self, url: &str, bytes: usize) {
+        let wait = {
+            let mut state = self.state.lock().unwrap();
+            let host = Url::parse(url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_lowercase))
+                .unwrap_or_default();
+            *state.hosts.entry(host).or_default() += bytes as u64;
+
+            match self.limit {
+                Some(limit) => {
+                    let now = Instant::now();
+                    let start = state.paid_until.filter(|paid| *paid > now).unwrap_or(now);
+                    let paid_until = start + Duration::from_secs_f64(bytes as f64 / limit as f64);
+                    state.paid_until = Some(paid_until);
+                    paid_until - now
+                }
+                None => Duration::ZERO,
+            }
+        };
+
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+    }
+
+    pub fn hosts(&self) -> BTreeMap<String, u64> {
+        self.state.lock().unwrap().hosts.clone()
+    }
+
+    pub fn total(&self) -> u64 {
+        self.state.lock().unwrap().hosts.values().sum()
+    }
+}
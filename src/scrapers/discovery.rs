@@ -0,0 +1,156 @@
+use crate::scrapers::bandwidth::Bandwidth;
+use crate::scrapers::itch_rss_scraper::{fetch_url, parse_rss_feed};
+use crate::scrapers::progress::{ProgressReporter, ScrapeEvent};
+use crate::scrapers::rate_limit::RateLimiter;
+use anyhow::{bail, Result};
+use reqwest::{Client, Url};
+use scraper::{Html, Selector};
+
+// what a --url turned out to point at
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedSource {
+    Feed(String),
+    // a page without a feed, whose linked games are scraped directly
+    Games(Vec<GameLink>),
+    // an itch browse listing, paginated through its json endpoint instead of the feed
+    Browse(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameLink {
+    pub title: String,
+    pub link: String,
+}
+
+// browse urls, creator pages and game pages are all accepted in place of the feed, anything
+// ending in .xml is trusted to be one without fetching it first
+pub async fn discover_feed(
+    client: &Client,
+    url: &str,
+    max_retries: u32,
+    limiter: &RateLimiter,
+    bandwidth: &Bandwidth,
+    reporter: &dyn ProgressReporter,
+) -> Result<FeedSource> {
+    if is_feed_url(url) {
+        return Ok(FeedSource::Feed(url.to_string()));
+    }
+
+    let body = fetch_url(client, url, max_retries, limiter, bandwidth, reporter).await?;
+    if parse_rss_feed(&body).is_ok() {
+        return Ok(FeedSource::Feed(url.to_string()));
+    }
+
+    let Some(source) = from_page(url, &body) else {
+        bail!(
+            "{:?} is neither an RSS feed nor a page linking to games, pass a feed url such as https://itch.io/games/newest.xml",
+            url
+        );
+    };
+    let message = match &source {
+        FeedSource::Feed(feed) => {
+            format!("{:?} is a web page, not a feed; scraping its feed {:?}", url, feed)
+        }
+        FeedSource::Games(games) => format!(
+            "No feed found for {:?}, scraping the {} game page(s) it links to instead; feed fields like price and dates will be empty",
+            url,
+            games.len()
+        ),
+        FeedSource::Browse(listing) => {
+            format!("{:?} is a browse listing, scraping {:?}", url, listing)
+        }
+    };
+    reporter.report(ScrapeEvent::Warning {
+        url: url.to_string(),
+        message,
+    });
+
+    Ok(source)
+}
+
+fn is_feed_url(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|parsed| parsed.path().ends_with(".xml"))
+}
+
+pub fn from_page(url: &str, body: &str) -> Option<FeedSource> {
+    let html = Html::parse_document(body);
+
+    // checked before the alternate link, game pages advertise their devlog feed
+    if html
+        .select(&Selector::parse(".game_info_panel_widget").unwrap())
+        .next()
+        .is_some()
+    {
+        return Some(FeedSource::Games(vec![GameLink {
+            title: page_title(&html),
+            link: url.to_string(),
+        }]));
+    }
+
+    let alternate = Selector::parse("link[rel=alternate][type='application/rss+xml']").unwrap();
+    if let Some(feed) = html
+        .select(&alternate)
+        .filter_map(|link| link.value().attr("href"))
+        .find_map(|href| absolute(url, href))
+    {
+        return Some(FeedSource::Feed(feed));
+    }
+
+    if let Some(feed) = browse_feed(url) {
+        return Some(FeedSource::Feed(feed));
+    }
+
+    let mut games: Vec<GameLink> = Vec::new();
+    for anchor in html.select(&Selector::parse("a.title.game_link").unwrap()) {
+        let Some(link) = anchor
+            .value()
+            .attr("href")
+            .and_then(|href| absolute(url, href))
+        else {
+            continue;
+        };
+        if !games.iter().any(|game| game.link == link) {
+            games.push(GameLink {
+                title: anchor.text().collect::<String>().trim().to_string(),
+                link,
+            });
+        }
+    }
+
+    (!games.is_empty()).then_some(FeedSource::Games(games))
+}
+
+// every itch browse page has a feed at the same path with .xml appended
+fn browse_feed(url: &str) -> Option<String> {
+    let mut parsed = Url::parse(url).ok()?;
+    if parsed.host_str() != Some("itch.io") || !parsed.path().starts_with("/games") {
+        return None;
+    }
+
+    let path = format!("{}.xml", parsed.path().trim_end_matches('/'));
+    parsed.set_path(&path);
+    parsed.set_query(None);
+    Some(parsed.to_string())
+}
+
+pub(crate) fn absolute(base: &str, href: &str) -> Option<String> {
+    Url::parse(base)
+        .and_then(|base| base.join(href))
+        .ok()
+        .map(|url| url.to_string())
+}
+
+fn page_title(html: &Html) -> String {
+    let og_title = Selector::parse("meta[property='og:title']").unwrap();
+    if let Some(title) = html
+        .select(&og_title)
+        .find_map(|meta| meta.value().attr("content"))
+    {
+        return title.trim().to_string();
+    }
+
+    html.select(&Selector::parse("title").unwrap())
+        .next()
+        .map(|title| title.text().collect::<String>().trim().to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,285 @@
+use crate::scrapers::discovery::{discover_feed, FeedSource};
+use crate::scrapers::itch_rss_scraper::{
+    fetch_url, game_item, item_date, parse_rss_feed, report_downloaded, scrape_game, ItchData, Item,
+};
+use crate::scrapers::options::ScrapeOptions;
+use crate::scrapers::progress::{ProgressReporter, ScrapeEvent};
+use anyhow::{bail, Context, Result};
+use futures_util::future::try_join_all;
+use redis::aio::MultiplexedConnection;
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::{sleep, Duration, Instant};
+
+// keys outlive the crawl by this long, so a job name can be reused to pick up where it stopped
+const KEY_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+// a worker that died mid-item never marks it done, so an empty queue is only waited on this long
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+// marking an url seen, counting it as pending and queueing it happen together, so two workers
+// never both queue the same page
+const PUSH_SCRIPT: &str = "
+if redis.call('SADD', KEYS[1], ARGV[1]) == 0 then
+    return 0
+end
+redis.call('INCR', KEYS[2])
+redis.call('RPUSH', KEYS[3], ARGV[2])
+for _, key in ipairs(KEYS) do
+    redis.call('EXPIRE', key, ARGV[3])
+end
+return 1
+";
+
+const BUDGET_SCRIPT: &str = "
+local count = redis.call('INCR', KEYS[1])
+if count == 1 then
+    redis.call('EXPIRE', KEYS[1], 2)
+end
+return count
+";
+
+#[derive(Serialize, Deserialize, Debug)]
+enum Work {
+    Feed { url: String, page: i32 },
+    Game(Box<Item>),
+}
+
+// a work queue, seen-set and per-host request budget in redis, shared by every instance
+// scraping under the same job name
+pub struct Coordinator {
+    conn: MultiplexedConnection,
+    prefix: String,
+    requests_per_second: Option<u32>,
+}
+
+impl Coordinator {
+    pub async fn connect(
+        url: &str,
+        job: &str,
+        requests_per_second: Option<u32>,
+    ) -> Result<Coordinator> {
+        let client =
+            redis::Client::open(url).with_context(|| format!("Invalid redis url {:?}", url))?;
+        let conn = client
+            .get_multiplexed_async_connection()
+            .await
+            .with_context(|| format!("Failed to connect to redis at {:?}", url))?;
+
+        Ok(Coordinator {
+            conn,
+            prefix: format!("gds:{}", job),
+            requests_per_second,
+        })
+    }
+
+    fn key(&self, name: &str) -> String {
+        format!("{}:{}", self.prefix, name)
+    }
+
+    async fn push(&self, seen_as: &str, work: &Work) -> Result<bool> {
+        let pushed: i64 = redis::cmd("EVAL")
+            .arg(PUSH_SCRIPT)
+            .arg(3)
+            .arg(self.key("seen"))
+            .arg(self.key("pending"))
+            .arg(self.key("queue"))
+            .arg(seen_as)
+            .arg(serde_json::to_string(work)?)
+            .arg(KEY_TTL_SECS)
+            .query_async(&mut self.conn.clone())
+            .await?;
+        Ok(pushed == 1)
+    }
+
+    async fn pop(&self) -> Result<Option<Work>> {
+        let work: Option<String> = redis::cmd("LPOP")
+            .arg(self.key("queue"))
+            .query_async(&mut self.conn.clone())
+            .await?;
+        match work {
+            Some(work) => Ok(Some(serde_json::from_str(&work)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn done(&self) -> Result<()> {
+        redis::cmd("DECR")
+            .arg(self.key("pending"))
+            .query_async::<i64>(&mut self.conn.clone())
+            .await?;
+        Ok(())
+    }
+
+    async fn pending(&self) -> Result<i64> {
+        let pending: Option<i64> = redis::cmd("GET")
+            .arg(self.key("pending"))
+            .query_async(&mut self.conn.clone())
+            .await?;
+        Ok(pending.unwrap_or(0))
+    }
+
+    // one counter per host and second, so the budget holds across every machine in the job
+    async fn throttle(&self, url: &str) -> Result<()> {
+        let Some(limit) = self.requests_per_second else {
+            return Ok(());
+        };
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+
+        loop {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+            let count: u32 = redis::cmd("EVAL")
+                .arg(BUDGET_SCRIPT)
+                .arg(1)
+                .arg(self.key(&format!("budget:{}:{}", host, now.as_secs())))
+                .query_async(&mut self.conn.clone())
+                .await?;
+            if count <= limit {
+                return Ok(());
+            }
+            sleep(Duration::from_secs(1) - Duration::from_nanos(now.subsec_nanos().into())).await;
+        }
+    }
+}
+
+// every instance seeds the same feed pages, the seen-set keeps all but the first from queueing
+// them; records are returned by whichever instance scraped them
+pub async fn scrape_distributed(
+    url: String,
+    coordinator: &Coordinator,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
+) -> Result<Vec<ItchData>> {
+    let client = Client::new();
+    reporter.report(ScrapeEvent::Started {
+        pages: options.pages.len() as u64,
+    });
+
+    coordinator.throttle(&url).await?;
+    let source = discover_feed(
+        &client,
+        &url,
+        options.max_retries,
+        &options.limiter,
+        &options.bandwidth,
+        reporter,
+    )
+    .await?;
+    match &source {
+        FeedSource::Feed(feed_url) => {
+            for &page in &options.pages {
+                let work = Work::Feed {
+                    url: feed_url.clone(),
+                    page,
+                };
+                coordinator.push(&format!("feed:{}", page), &work).await?;
+            }
+        }
+        FeedSource::Games(games) => {
+            for game in games {
+                coordinator
+                    .push(&game.link, &Work::Game(Box::new(game_item(game))))
+                    .await?;
+            }
+        }
+        // discovery only ever finds feeds and game links
+        FeedSource::Browse(listing) => bail!("Browse listing {:?} can't be distributed", listing),
+    }
+
+    let records = Mutex::new(Vec::new());
+    let workers = (0..options.concurrency.fetch)
+        .map(|_| work(&client, coordinator, options, reporter, &records));
+    try_join_all(workers).await?;
+
+    report_downloaded(&options.bandwidth, reporter);
+    reporter.report(ScrapeEvent::Finished);
+    Ok(records.into_inner().unwrap())
+}
+
+async fn work(
+    client: &Client,
+    coordinator: &Coordinator,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
+    records: &Mutex<Vec<ItchData>>,
+) -> Result<()> {
+    let mut idle_since = Instant::now();
+    loop {
+        let Some(work) = coordinator.pop().await? else {
+            if coordinator.pending().await? <= 0 || idle_since.elapsed() > IDLE_TIMEOUT {
+                return Ok(());
+            }
+            sleep(POLL_INTERVAL).await;
+            continue;
+        };
+
+        // marked done even when it failed, or the other workers would wait on it forever
+        let result = run(client, coordinator, work, options, reporter, records).await;
+        coordinator.done().await?;
+        result?;
+        idle_since = Instant::now();
+    }
+}
+
+async fn run(
+    client: &Client,
+    coordinator: &Coordinator,
+    work: Work,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
+    records: &Mutex<Vec<ItchData>>,
+) -> Result<()> {
+    match work {
+        Work::Feed { url, page } => {
+            let rss_url = format!("{}?page={}", url, page);
+            coordinator.throttle(&rss_url).await?;
+            let rss_string = fetch_url(
+                client,
+                &rss_url,
+                options.max_retries,
+                &options.limiter,
+                &options.bandwidth,
+                reporter,
+            )
+            .await?;
+            match parse_rss_feed(&rss_string) {
+                Ok(feed) => {
+                    for item in feed.into_items() {
+                        if options
+                            .window
+                            .contains(item_date(&item, options.window.field))
+                        {
+                            let link = item.link.clone();
+                            coordinator.push(&link, &Work::Game(Box::new(item))).await?;
+                        }
+                    }
+                }
+                Err(err) => {
+                    let message = format!("Error parsing RSS xml for URL {:?}: {:?}", rss_url, err);
+                    reporter.report(ScrapeEvent::Error {
+                        message: message.clone(),
+                        url: rss_url,
+                        diagnostic: None,
+                    });
+                    if options.fail_on_error {
+                        anyhow::bail!(message);
+                    }
+                }
+            }
+            reporter.report(ScrapeEvent::PageFinished { page });
+        }
+        Work::Game(item) => {
+            coordinator.throttle(&item.link).await?;
+            if let Some(record) = scrape_game(client, *item, options, reporter).await? {
+                records.lock().unwrap().push(record);
+            }
+        }
+    }
+
+    Ok(())
+}
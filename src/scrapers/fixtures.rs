@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const MANIFEST_FILE: &str = "fixtures.json";
+
+// which file holds each recorded response, feed pages by page number and game pages by url
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Manifest {
+    pub feeds: BTreeMap<i32, String>,
+    pub pages: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    pub fn load(dir: &Path) -> Result<Manifest> {
+        let path = dir.join(MANIFEST_FILE);
+        let contents =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid fixture manifest {:?}", path))
+    }
+}
+
+// saves the responses of a live scrape so they can be served back by the integration tests
+#[derive(Debug)]
+pub struct FixtureRecorder {
+    dir: PathBuf,
+    manifest: Mutex<Manifest>,
+}
+
+impl FixtureRecorder {
+    pub fn new(dir: &Path) -> Result<FixtureRecorder> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+
+        Ok(FixtureRecorder {
+            dir: dir.to_path_buf(),
+            manifest: Mutex::new(Manifest::default()),
+        })
+    }
+
+    pub fn record_feed(&self, page: i32, body: &str) -> Result<()> {
+        let file = format!("feed-{}.xml", page);
+        self.write(&file, body)?;
+        self.update(|manifest| manifest.feeds.insert(page, file))
+    }
+
+    pub fn record_page(&self, url: &str, body: &str) -> Result<()> {
+        let file = format!("page-{}.html", file_stem(url));
+        self.write(&file, &sanitize(body))?;
+        self.update(|manifest| manifest.pages.insert(url.to_string(), file))
+    }
+
+    fn write(&self, file: &str, body: &str) -> Result<()> {
+        let path = self.dir.join(file);
+        fs::write(&path, body).with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    // rewritten after every response so an interrupted run still leaves a usable manifest
+    fn update(&self, change: impl FnOnce(&mut Manifest) -> Option<String>) -> Result<()> {
+        let mut manifest = self.manifest.lock().unwrap();
+        change(&mut manifest);
+        self.write(MANIFEST_FILE, &serde_json::to_string_pretty(&*manifest)?)
+    }
+}
+
+// https://author.itch.io/some-game -> author.itch.io-some-game
+fn file_stem(url: &str) -> String {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+
+    url.trim_end_matches('/')
+        .chars()
+        .map(|c| match c {
+            '/' => '-',
+            c if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+// drops scripts, which carry analytics ids and session state, and the csrf token tag; the
+// parser never reads either
+pub fn sanitize(html: &str) -> String {
+    let without_scripts = remove_between(html, "<script", "</script>");
+    remove_tags_containing(&without_scripts, "csrf_token")
+}
+
+fn remove_between(html: &str, open: &str, close: &str) -> String {
+    // ascii lowercasing keeps byte offsets the same
+    let lower = html.to_ascii_lowercase();
+    let mut output = String::with_capacity(html.len());
+    let mut position = 0;
+
+    while let Some(start) = lower[position..].find(open).map(|index| position + index) {
+        output.push_str(&html[position..start]);
+        position = match lower[start..].find(close) {
+            Some(end) => start + end + close.len(),
+            None => html.len(),
+        };
+    }
+    output.push_str(&html[position..]);
+
+    output
+}
+
+fn remove_tags_containing(html: &str, needle: &str) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(index) = rest.find(needle) {
+        let start = rest[..index].rfind('<');
+        let end = rest[index..].find('>');
+        match (start, end) {
+            // only when the needle is inside the tag, not in text after it
+            (Some(start), Some(end)) if !rest[start..index].contains('>') => {
+                output.push_str(&rest[..start]);
+                rest = &rest[index + end + 1..];
+            }
+            _ => {
+                output.push_str(&rest[..index + needle.len()]);
+                rest = &rest[index + needle.len()..];
+            }
+        }
+    }
+    output.push_str(rest);
+
+    output
+}
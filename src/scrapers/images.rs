@@ -0,0 +1,278 @@
+use crate::scrapers::itch_rss_scraper::{fetch_bytes, ItchData};
+use crate::scrapers::options::ScrapeOptions;
+use crate::scrapers::progress::{ProgressReporter, ScrapeEvent};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use futures_util::stream::{self, StreamExt};
+use image::{imageops::FilterType, ImageFormat};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// remembers which file each url was saved to, so later runs don't download it again
+const INDEX_FILE: &str = "index.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageKind {
+    Covers,
+    Screenshots,
+}
+
+impl ImageKind {
+    fn singular(&self) -> &'static str {
+        match self {
+            ImageKind::Covers => "cover",
+            ImageKind::Screenshots => "screenshot",
+        }
+    }
+}
+
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Serialize,
+    Deserialize,
+    async_graphql::SimpleObject,
+    schemars::JsonSchema,
+    utoipa::ToSchema,
+)]
+pub struct StoredImage {
+    pub kind: String,
+    pub url: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thumbnails: Vec<Thumbnail>,
+}
+
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Serialize,
+    Deserialize,
+    async_graphql::SimpleObject,
+    schemars::JsonSchema,
+    utoipa::ToSchema,
+)]
+pub struct Thumbnail {
+    // the longest side in pixels
+    pub size: u32,
+    pub path: String,
+}
+
+// files are named by the sha-256 of their contents, so an image shared by several games, or
+// re-uploaded under a new url, is only stored once
+#[derive(Debug)]
+pub struct ImageStore {
+    dir: PathBuf,
+    index: Mutex<BTreeMap<String, String>>,
+    thumbnail_sizes: Vec<u32>,
+}
+
+impl ImageStore {
+    pub fn open(dir: &Path) -> Result<ImageStore> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        let index_path = dir.join(INDEX_FILE);
+        let index = if index_path.exists() {
+            let contents = fs::read_to_string(&index_path)
+                .with_context(|| format!("Failed to read {:?}", index_path))?;
+            serde_json::from_str(&contents)
+                .with_context(|| format!("Invalid image index {:?}", index_path))?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(ImageStore {
+            dir: dir.to_path_buf(),
+            index: Mutex::new(index),
+            thumbnail_sizes: Vec::new(),
+        })
+    }
+
+    pub fn with_thumbnails(mut self, sizes: &[u32]) -> ImageStore {
+        self.thumbnail_sizes = sizes.to_vec();
+        self.thumbnail_sizes.sort_unstable();
+        self.thumbnail_sizes.dedup();
+        self
+    }
+
+    fn known(&self, url: &str) -> Option<String> {
+        let index = self.index.lock().unwrap();
+        index
+            .get(url)
+            .filter(|path| Path::new(path).exists())
+            .cloned()
+    }
+
+    fn store(&self, url: &str, bytes: &[u8]) -> Result<String> {
+        let hash = hex::encode(Sha256::digest(bytes));
+        let dir = self.dir.join(&hash[..2]);
+        let path = dir.join(format!("{}.{}", hash, extension(url)));
+        if !path.exists() {
+            fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+            fs::write(&path, bytes).with_context(|| format!("Failed to write {:?}", path))?;
+        }
+
+        let path = path.display().to_string();
+        self.index
+            .lock()
+            .unwrap()
+            .insert(url.to_string(), path.clone());
+        Ok(path)
+    }
+
+    // thumbnails sit next to the original as <hash>-<size>.<ext>, so they're only generated once
+    // per image and size; images already smaller than a size are used as is
+    fn thumbnails(&self, path: &str) -> Result<Vec<Thumbnail>> {
+        if self.thumbnail_sizes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let original = Path::new(path);
+        let stem = original.file_stem().unwrap_or_default().to_string_lossy();
+        let mut thumbnails = Vec::new();
+        let mut image = None;
+        for &size in &self.thumbnail_sizes {
+            let format = thumbnail_format(original);
+            let thumbnail = original.with_file_name(format!(
+                "{}-{}.{}",
+                stem,
+                size,
+                format.extensions_str()[0]
+            ));
+            if !thumbnail.exists() {
+                if image.is_none() {
+                    image = Some(
+                        image::open(original)
+                            .with_context(|| format!("Failed to decode {:?}", original))?,
+                    );
+                }
+                let image = image.as_ref().unwrap();
+                if image.width() <= size && image.height() <= size {
+                    thumbnails.push(Thumbnail {
+                        size,
+                        path: path.to_string(),
+                    });
+                    continue;
+                }
+                image
+                    .resize(size, size, FilterType::Lanczos3)
+                    .save_with_format(&thumbnail, format)
+                    .with_context(|| format!("Failed to write {:?}", thumbnail))?;
+            }
+            thumbnails.push(Thumbnail {
+                size,
+                path: thumbnail.display().to_string(),
+            });
+        }
+
+        Ok(thumbnails)
+    }
+
+    pub fn save_index(&self) -> Result<()> {
+        let path = self.dir.join(INDEX_FILE);
+        let index = self.index.lock().unwrap();
+        fs::write(&path, serde_json::to_string_pretty(&*index)?)
+            .with_context(|| format!("Failed to write {:?}", path))
+    }
+}
+
+// photos stay jpeg, everything else becomes png since not every source format can be encoded
+fn thumbnail_format(original: &Path) -> ImageFormat {
+    match ImageFormat::from_path(original) {
+        Ok(ImageFormat::Jpeg) => ImageFormat::Jpeg,
+        _ => ImageFormat::Png,
+    }
+}
+
+fn extension(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| {
+            let (_, extension) = url.path().rsplit_once('.')?;
+            Some(extension.to_lowercase())
+        })
+        .filter(|extension| ["png", "jpg", "jpeg", "gif", "webp"].contains(&extension.as_str()))
+        .unwrap_or_else(|| "img".to_string())
+}
+
+// a failed image is a warning, the record is still written with the images that did download
+pub async fn download_images(
+    records: &mut [ItchData],
+    kinds: &[ImageKind],
+    store: &ImageStore,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
+) -> Result<()> {
+    let client = Client::new();
+
+    for record in records.iter_mut() {
+        let mut wanted: Vec<(ImageKind, String)> = Vec::new();
+        if kinds.contains(&ImageKind::Covers) && !record.cover_image.is_empty() {
+            wanted.push((ImageKind::Covers, record.cover_image.clone()));
+        }
+        if kinds.contains(&ImageKind::Screenshots) {
+            wanted.extend(
+                record
+                    .screenshots
+                    .iter()
+                    .map(|url| (ImageKind::Screenshots, url.clone())),
+            );
+        }
+
+        let mut downloads = stream::iter(wanted)
+            .map(|(kind, url)| {
+                let client = &client;
+                async move {
+                    let path = match store.known(&url) {
+                        Some(path) => Ok(path),
+                        None => fetch_bytes(
+                            client,
+                            &url,
+                            options.max_retries,
+                            &options.limiter,
+                            &options.bandwidth,
+                            reporter,
+                        )
+                        .await
+                        .map_err(anyhow::Error::from)
+                        .and_then(|bytes| store.store(&url, &bytes)),
+                    };
+                    (kind, url, path)
+                }
+            })
+            .buffered(options.concurrency.fetch);
+
+        while let Some((kind, url, path)) = downloads.next().await {
+            match path {
+                Ok(path) => {
+                    let thumbnails = store.thumbnails(&path).unwrap_or_else(|err| {
+                        reporter.report(ScrapeEvent::Warning {
+                            url: url.clone(),
+                            message: format!("Failed to make thumbnails of {:?}: {:#}", url, err),
+                        });
+                        Vec::new()
+                    });
+                    record.images.push(StoredImage {
+                        kind: kind.singular().to_string(),
+                        url,
+                        path,
+                        thumbnails,
+                    });
+                }
+                Err(err) => reporter.report(ScrapeEvent::Warning {
+                    message: format!("Failed to download image {:?}: {:#}", url, err),
+                    url,
+                }),
+            }
+        }
+    }
+
+    store.save_index()
+}
@@ -0,0 +1,152 @@
+use crate::scrapers::discovery::{absolute, FeedSource};
+use crate::scrapers::itch_rss_scraper::{scrape_source, ItchData, Item};
+use crate::scrapers::options::ScrapeOptions;
+use crate::scrapers::progress::ProgressReporter;
+use anyhow::{Context, Result};
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use std::future::{ready, Future};
+
+// what https://itch.io/games/newest?page=2&format=json returns, the same game cells the html
+// listing renders
+#[derive(Deserialize, Debug)]
+struct BrowsePage {
+    #[serde(default)]
+    content: String,
+}
+
+// the feed stops after a few pages, the browse listing keeps going until it runs out of games
+pub async fn scrape_itch_browse(
+    url: String,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
+) -> Result<Vec<ItchData>> {
+    let mut output = Vec::new();
+    scrape_itch_browse_feed(url, options, reporter, |record| {
+        output.push(record);
+        ready(true)
+    })
+    .await?;
+
+    Ok(output)
+}
+
+// emit gets each record as soon as its game page is parsed, returning false stops the scrape
+pub async fn scrape_itch_browse_feed<F, Fut>(
+    url: String,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
+    emit: F,
+) -> Result<()>
+where
+    F: FnMut(ItchData) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let listing = listing_url(&url)?;
+    scrape_source(
+        url,
+        Some(FeedSource::Browse(listing)),
+        options,
+        reporter,
+        emit,
+    )
+    .await
+}
+
+// feed urls are accepted too, https://itch.io/games/newest.xml lists the same games as
+// https://itch.io/games/newest
+pub fn listing_url(url: &str) -> Result<String> {
+    let mut parsed = Url::parse(url).with_context(|| format!("Invalid url {:?}", url))?;
+    if let Some(path) = parsed.path().strip_suffix(".xml").map(str::to_string) {
+        parsed.set_path(&path);
+    }
+    let query: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(name, _)| name != "page" && name != "format")
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    parsed.set_query(None);
+    if !query.is_empty() {
+        parsed.query_pairs_mut().extend_pairs(query);
+    }
+    Ok(parsed.to_string())
+}
+
+pub(crate) fn browse_page_url(listing: &str, page: i32) -> Result<String> {
+    let mut url = Url::parse(listing).with_context(|| format!("Invalid url {:?}", listing))?;
+    url.query_pairs_mut()
+        .append_pair("page", &page.to_string())
+        .append_pair("format", "json");
+    Ok(url.to_string())
+}
+
+// the json endpoint wraps the cells in {"content": ...}, a plain html listing is read as is
+pub(crate) fn parse_browse_page(url: &str, body: &str) -> Result<Vec<Item>> {
+    let content = match body.trim_start().starts_with('{') {
+        true => serde_json::from_str::<BrowsePage>(body)?.content,
+        false => body.to_string(),
+    };
+
+    let html = Html::parse_fragment(&content);
+    let mut items: Vec<Item> = Vec::new();
+    for cell in html.select(&Selector::parse(".game_cell").unwrap()) {
+        let Some(item) = cell_item(url, cell) else {
+            continue;
+        };
+        if !items.iter().any(|existing| existing.link == item.link) {
+            items.push(item);
+        }
+    }
+
+    Ok(items)
+}
+
+// cells carry no dates, those come from the game page or not at all
+fn cell_item(url: &str, cell: ElementRef) -> Option<Item> {
+    let anchor = cell
+        .select(&Selector::parse("a.title.game_link").unwrap())
+        .next()?;
+    let link = absolute(url, anchor.value().attr("href")?)?;
+    let title = text(anchor);
+    let description = cell
+        .select(&Selector::parse(".game_text").unwrap())
+        .next()
+        .map(|text_element| {
+            text_element
+                .value()
+                .attr("title")
+                .map(str::to_string)
+                .unwrap_or_else(|| text(text_element))
+        })
+        .unwrap_or_default();
+    let imageurl = cell
+        .select(&Selector::parse(".game_thumb img").unwrap())
+        .next()
+        .and_then(|img| {
+            img.value()
+                .attr("data-lazy_src")
+                .or_else(|| img.value().attr("src"))
+        })
+        .unwrap_or_default()
+        .to_string();
+
+    Some(Item {
+        guid: link.clone(),
+        plain_title: title.clone(),
+        title,
+        link,
+        price: cell
+            .select(&Selector::parse(".price_value").unwrap())
+            .next()
+            .map(text)
+            .unwrap_or_default(),
+        description,
+        imageurl,
+        ..Item::default()
+    })
+}
+
+fn text(element: ElementRef) -> String {
+    element.text().collect::<String>().trim().to_string()
+}
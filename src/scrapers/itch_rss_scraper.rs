@@ -1,51 +1,116 @@
+use crate::diagnostics::ParseDiagnostic;
+use crate::filters::{platform_segment, Filter};
+use crate::parsers::dates::parse_feed_date;
 use crate::parsers::itch_game_info_parser::{
-    parse_itch_game_page_data, ItchRating, Link, MoreInfoTableData,
+    deserialize_rating, parse_itch_game_comments, parse_itch_game_page_data, Author, Comment,
+    Genre, ItchHTMLDataFormatError, ItchRating, Link, MoreInfoTableData, Tag,
 };
-use anyhow::Result;
-use indicatif::{ProgressBar, ProgressStyle};
-use reqwest::{Client, StatusCode};
-use tokio::time::{sleep, Duration};
+use crate::parsers::plugin::GameRecord;
+use crate::parsers::price::Price;
+use crate::scrapers::bandwidth::Bandwidth;
+use crate::scrapers::discovery::{discover_feed, FeedSource, GameLink};
+use crate::scrapers::images::StoredImage;
+use crate::scrapers::itch_browse_scraper::{browse_page_url, parse_browse_page};
+use crate::scrapers::options::{DateField, ScrapeOptions};
+use crate::scrapers::progress::{ProgressReporter, ScrapeEvent};
+use crate::scrapers::rate_limit::RateLimiter;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use futures_util::stream::{self, Stream, StreamExt};
+use reqwest::{Client, Response, StatusCode, Url};
+use std::future::{ready, Future};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::time::{sleep, Duration, Instant};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, trace};
 
-#[derive(Default, Debug, serde::Serialize)]
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    serde::Serialize,
+    serde::Deserialize,
+    async_graphql::SimpleObject,
+    schemars::JsonSchema,
+    utoipa::ToSchema,
+)]
+#[graphql(name = "GameRecord")]
+#[schema(as = GameRecord)]
 pub struct ItchData {
-    title: String,
-    plain_title: String,
-    link: String,
-    create_date: String,
-    update_date: String,
-    release_date: String,
-    pub_date: String,
-    price: String,
-    description: String,
-    rating: ItchRating,
-    authors: Vec<String>,
-    genres: Vec<String>,
-    made_with: Vec<String>,
-    tags: Vec<String>,
-    average_session: String,
-    languages: Vec<String>,
-    inputs: Vec<String>,
-    links: Vec<Link>,
-    status: String,
-    platforms: Vec<String>,
-    accessibility: Vec<String>,
+    pub title: String,
+    pub plain_title: String,
+    pub link: String,
+    pub create_date: String,
+    pub update_date: String,
+    pub release_date: String,
+    #[serde(default)]
+    pub updated_date: String,
+    #[serde(default)]
+    pub published_date: String,
+    pub pub_date: String,
+    pub price: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_info: Option<Price>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_converted: Option<f64>,
+    pub description: String,
+    // None when the game has no ratings yet
+    #[serde(default, deserialize_with = "deserialize_rating")]
+    pub rating: Option<ItchRating>,
+    pub authors: Vec<Author>,
+    pub genres: Vec<Genre>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub raw_genres: Vec<String>,
+    pub made_with: Vec<String>,
+    pub tags: Vec<Tag>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub raw_tags: Vec<String>,
+    pub average_session: String,
+    pub languages: Vec<String>,
+    pub inputs: Vec<String>,
+    pub links: Vec<Link>,
+    pub status: String,
+    pub platforms: Vec<String>,
+    pub accessibility: Vec<String>,
+    #[serde(default)]
+    pub cover_image: String,
+    #[serde(default)]
+    pub screenshots: Vec<String>,
+    // local copies saved by --download-images
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<StoredImage>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<Comment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentiment: Option<f32>,
+    // info table rows that couldn't be read and were left out
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    // fields derived by a --script transform
+    #[serde(flatten)]
+    #[graphql(skip)]
+    #[schema(ignore)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
-#[derive(Debug, serde::Deserialize, PartialEq)]
-struct Item {
-    guid: String,
-    title: String,
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub(crate) struct Item {
+    pub(crate) guid: String,
+    pub(crate) title: String,
     #[serde(rename = "plainTitle")]
-    plain_title: String,
-    link: String,
-    price: String,
-    description: String,
+    pub(crate) plain_title: String,
+    pub(crate) link: String,
+    pub(crate) price: String,
+    pub(crate) description: String,
     #[serde(rename = "pubDate")]
-    pub_date: String,
+    pub(crate) pub_date: String,
     #[serde(rename = "createDate")]
-    create_date: String,
+    pub(crate) create_date: String,
     #[serde(rename = "updateDate")]
-    update_date: String,
+    pub(crate) update_date: String,
+    #[serde(default)]
+    pub(crate) imageurl: String,
 }
 
 #[derive(Debug, serde::Deserialize, PartialEq)]
@@ -55,83 +120,640 @@ struct Channel {
 }
 
 #[derive(Debug, serde::Deserialize, PartialEq)]
-struct Rss {
+pub struct Rss {
     channel: Channel,
 }
 
+pub fn parse_rss_feed(xml: &str) -> Result<Rss, quick_xml::DeError> {
+    quick_xml::de::from_str(xml)
+}
+
+impl Rss {
+    pub(crate) fn into_items(self) -> Vec<Item> {
+        self.channel.items
+    }
+}
+
+impl ItchData {
+    pub fn genre_names(&self) -> Vec<&str> {
+        self.genres
+            .iter()
+            .map(|genre| genre.name.as_str())
+            .collect()
+    }
+
+    pub fn tag_names(&self) -> Vec<&str> {
+        self.tags.iter().map(|tag| tag.name.as_str()).collect()
+    }
+
+    // free games are 0, None when no price could be read
+    pub fn price_amount(&self) -> Option<f64> {
+        self.price_info.as_ref().and_then(|price| price.amount)
+    }
+
+    // records written before price_info split the price into price_amount, price_currency and
+    // price_or_more, which would otherwise be carried along in extra; they were all read from the
+    // display price, so it's parsed again instead
+    pub fn upgrade(&mut self) {
+        for field in ["price_amount", "price_currency", "price_or_more"] {
+            self.extra.remove(field);
+        }
+        if self.price_info.is_none() {
+            self.price_info = Price::parse(&self.price);
+        }
+    }
+}
+
+// itch browse feeds accept filter path segments, e.g. /games/newest/tag-roguelike/platform-linux.xml,
+// so matching records can be narrowed server side before any game page is fetched; the browse
+// pages themselves take the same segments without the .xml
+pub fn push_down_filters(url: &str, filter: &Filter) -> String {
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+    if parsed.host_str() != Some("itch.io") {
+        return url.to_string();
+    }
+    let extension = if parsed.path().ends_with(".xml") {
+        ".xml"
+    } else {
+        ""
+    };
+    let Some(mut path) = parsed
+        .path()
+        .strip_suffix(extension)
+        .map(|path| path.trim_end_matches('/'))
+        .filter(|path| path.starts_with("/games"))
+        .map(str::to_string)
+    else {
+        return url.to_string();
+    };
+
+    let segments = filter
+        .tags
+        .iter()
+        .map(|tag| Some(format!("tag-{}", slug(tag))))
+        .chain(filter.genres.iter().map(|genre| genre_slug(genre)))
+        .chain(
+            filter
+                .platforms
+                .iter()
+                .map(|platform| platform_segment(platform).map(str::to_string)),
+        )
+        .chain([(filter.free == Some(true)).then(|| "free".to_string())])
+        .flatten();
+
+    for segment in segments {
+        if !path.split('/').any(|existing| existing == segment) {
+            path = format!("{}/{}", path, segment);
+        }
+    }
+
+    parsed.set_path(&format!("{}{}", path, extension));
+    parsed.to_string()
+}
+
+fn slug(value: &str) -> String {
+    value
+        .trim()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn genre_slug(genre: &str) -> Option<String> {
+    match slug(genre).as_str() {
+        "role-playing" | "rpg" => Some("genre-rpg".to_string()),
+        "action"
+        | "adventure"
+        | "card-game"
+        | "educational"
+        | "fighting"
+        | "interactive-fiction"
+        | "platformer"
+        | "puzzle"
+        | "racing"
+        | "rhythm"
+        | "shooter"
+        | "simulation"
+        | "sports"
+        | "strategy"
+        | "survival"
+        | "visual-novel" => Some(format!("genre-{}", slug(genre))),
+        _ => None,
+    }
+}
+
 pub async fn scrape_itch_rss_feed(
     url: String,
-    max_retries: u32,
-    page_limit: i32,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
 ) -> Result<Vec<ItchData>> {
+    let mut output = Vec::new();
+    scrape_feed(url, options, reporter, |record| {
+        output.push(record);
+        ready(true)
+    })
+    .await?;
+
+    Ok(output)
+}
+
+// records are sent as soon as they're parsed, the bounded channel pauses scraping while the
+// consumer is busy and dropping the stream stops it at the next record. Pages that fail are Err
+// items between the records, a scrape that can't go on ends the stream with one
+pub fn scrape_itch_rss_feed_stream(
+    url: String,
+    options: ScrapeOptions,
+) -> impl Stream<Item = Result<ItchData>> {
+    let (tx, rx) = mpsc::channel(1);
+
+    tokio::spawn(async move {
+        let errors = PendingErrors::default();
+        let result = scrape_feed(url, &options, &errors, |record| {
+            let (tx, pending) = (tx.clone(), errors.take());
+            async move {
+                for message in pending {
+                    if tx.send(Err(anyhow!(message))).await.is_err() {
+                        return false;
+                    }
+                }
+                tx.send(Ok(record)).await.is_ok()
+            }
+        })
+        .await;
+
+        let mut pending = errors.take();
+        // --fail-on-error reports the page before failing with the same message
+        if let Err(err) = &result {
+            pending.retain(|message| *message != err.to_string());
+        }
+        for message in pending {
+            if tx.send(Err(anyhow!(message))).await.is_err() {
+                return;
+            }
+        }
+        if let Err(err) = result {
+            let _ = tx.send(Err(err)).await;
+        }
+    });
+
+    ReceiverStream::new(rx)
+}
+
+// report() can't wait on the channel, so errors are held until the next record is sent
+#[derive(Default)]
+struct PendingErrors(Mutex<Vec<String>>);
+
+impl PendingErrors {
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl ProgressReporter for PendingErrors {
+    fn report(&self, event: ScrapeEvent) {
+        if let ScrapeEvent::Error { message, .. } = event {
+            self.0.lock().unwrap().push(message);
+        }
+    }
+}
+
+// emit gets each record as soon as its game page is parsed, returning false stops the scrape
+pub async fn scrape_feed<F, Fut>(
+    url: String,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
+    emit: F,
+) -> Result<()>
+where
+    F: FnMut(ItchData) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    scrape_source(url, None, options, reporter, emit).await
+}
+
+// the source is discovered from the url unless the caller already knows what it points at
+pub(crate) async fn scrape_source<F, Fut>(
+    url: String,
+    source: Option<FeedSource>,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
+    mut emit: F,
+) -> Result<()>
+where
+    F: FnMut(ItchData) -> Fut,
+    Fut: Future<Output = bool>,
+{
     let client = Client::new();
+    let max_retries = options.max_retries;
+    let limiter = options.limiter.as_ref();
+    let bandwidth = options.bandwidth.as_ref();
 
-    let pb = ProgressBar::new(page_limit as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} pages")?
-            .progress_chars("##-"),
-    );
-    pb.enable_steady_tick(Duration::new(0, 100000000));
-    pb.tick();
-
-    let mut itch_data_output = Vec::new();
-    for page in 1..=page_limit {
-        let rss_url = format!("{}?page={}", url, page);
-        let rss_string = fetch_url(&client, &rss_url, max_retries).await?;
-
-        match quick_xml::de::from_str::<Rss>(&rss_string) {
-            Ok(feed) => {
-                for item in feed.channel.items {
-                    let game_data = fetch_url(&client, &item.link, max_retries).await?;
-                    match parse_itch_game_page_data(&game_data) {
-                        Ok(data) => {
-                            itch_data_output.push(combine_itch_rss_and_info_data(data, item))
+    reporter.report(ScrapeEvent::Started {
+        pages: options.pages.len() as u64,
+    });
+
+    // only kept for the checkpoint, the records themselves go straight to emit
+    let mut saved = Vec::new();
+    let mut ordering = FeedOrdering::new();
+    let source = match source {
+        Some(source) => source,
+        None => discover_feed(&client, &url, max_retries, limiter, bandwidth, reporter).await?,
+    };
+    for (index, &page) in options.pages.iter().enumerate() {
+        let (rss_url, feed_items) = match &source {
+            FeedSource::Feed(feed_url) => {
+                let rss_url = format!("{}?page={}", feed_url, page);
+                let rss_string =
+                    fetch_url(&client, &rss_url, max_retries, limiter, bandwidth, reporter).await?;
+                if let Some(recorder) = &options.recorder {
+                    recorder.record_feed(page, &rss_string)?;
+                }
+                let items = parse_rss_feed(&rss_string)
+                    .map(|feed| feed.channel.items)
+                    .map_err(|err| {
+                        format!("Error parsing RSS xml for URL {:?}: {:?}", rss_url, err)
+                    });
+                (rss_url, items)
+            }
+            FeedSource::Browse(listing) => {
+                let page_url = browse_page_url(listing, page)?;
+                let body = fetch_url(
+                    &client,
+                    &page_url,
+                    max_retries,
+                    limiter,
+                    bandwidth,
+                    reporter,
+                )
+                .await?;
+                let items = parse_browse_page(&page_url, &body)
+                    .map_err(|err| format!("Error parsing browse page {:?}: {:#}", page_url, err));
+                // listings end with an empty page rather than an error
+                if items.as_ref().is_ok_and(Vec::is_empty) {
+                    break;
+                }
+                (page_url, items)
+            }
+            // a page without a feed has nothing to paginate
+            FeedSource::Games(_) if index > 0 => break,
+            FeedSource::Games(games) => (url.clone(), Ok(games.iter().map(game_item).collect())),
+        };
+
+        match feed_items {
+            Ok(feed_items) => {
+                let mut items = Vec::new();
+                let mut past_window = false;
+                for item in feed_items {
+                    let date = item_date(&item, options.window.field);
+                    ordering.observe(date);
+
+                    if !options.window.contains(date) {
+                        // newest-first feeds can't contain anything in the window past this point
+                        if ordering.is_newest_first() && options.window.is_before(date) {
+                            past_window = true;
+                            break;
                         }
+                        continue;
+                    }
+                    items.push(item);
+                }
+
+                // game pages are fetched and parsed concurrently, but buffered() keeps feed order
+                let mut pages = stream::iter(items)
+                    .map(|item| async {
+                        let game_data = fetch_url(
+                            &client,
+                            &item.link,
+                            max_retries,
+                            limiter,
+                            bandwidth,
+                            reporter,
+                        )
+                        .await;
+                        (item, game_data)
+                    })
+                    .buffered(options.concurrency.fetch)
+                    .map(|(item, game_data)| async move {
+                        let page = match game_data {
+                            Ok(game_data) => Ok(parse_page(&item, game_data, options).await?),
+                            Err(err) => Err(err),
+                        };
+                        anyhow::Ok((item, page))
+                    })
+                    .buffered(options.concurrency.parse);
+
+                while let Some(page_data) = pages.next().await {
+                    let (item, page) = page_data?;
+                    // a game page that can't be fetched is reported and skipped, the same as one
+                    // that doesn't parse
+                    let (game_data, parsed) = match page {
+                        Ok(page) => page,
                         Err(err) => {
-                            eprintln!("Error parsing Itch game page {:?}: {:?}", &item.link, err)
+                            let message = format!("Failed to fetch {:?}: {}", item.link, err);
+                            reporter.report(ScrapeEvent::Error {
+                                message: message.clone(),
+                                url: item.link,
+                                diagnostic: None,
+                            });
+                            match options.fail_on_error {
+                                true => return Err(anyhow!(message)),
+                                false => continue,
+                            }
                         }
+                    };
+                    let Some(record) = into_record(item, &game_data, parsed, options, reporter)?
+                    else {
+                        continue;
+                    };
+                    if options.checkpoint.is_some() {
+                        saved.push(record.clone());
+                    }
+                    if !emit(record).await {
+                        return Ok(());
                     }
                 }
+
+                if past_window {
+                    reporter.report(ScrapeEvent::PageFinished { page });
+                    report_downloaded(bandwidth, reporter);
+                    reporter.report(ScrapeEvent::Finished);
+                    return Ok(());
+                }
             }
-            Err(err) => {
-                eprintln!("Error parsing RSS xml for URL {:?}: {:?}", rss_url, err);
+            Err(message) => {
+                reporter.report(ScrapeEvent::Error {
+                    message: message.clone(),
+                    url: rss_url,
+                    diagnostic: None,
+                });
+                if options.fail_on_error {
+                    return Err(anyhow!(message));
+                }
             }
         }
 
-        pb.inc(1);
+        if let Some(checkpoint) = &options.checkpoint {
+            checkpoint.save(page + 1, &saved)?;
+        }
+        reporter.report(ScrapeEvent::PageFinished { page });
+    }
+
+    report_downloaded(bandwidth, reporter);
+    reporter.report(ScrapeEvent::Finished);
+    Ok(())
+}
+
+// one game page on its own, for scrapers that hand out pages one at a time
+pub(crate) async fn scrape_game(
+    client: &Client,
+    item: Item,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
+) -> Result<Option<ItchData>> {
+    let game_data = fetch_url(
+        client,
+        &item.link,
+        options.max_retries,
+        &options.limiter,
+        &options.bandwidth,
+        reporter,
+    )
+    .await?;
+    let (game_data, parsed) = parse_page(&item, game_data, options).await?;
+    into_record(item, &game_data, parsed, options, reporter)
+}
+
+pub(crate) fn game_item(game: &GameLink) -> Item {
+    Item {
+        guid: game.link.clone(),
+        title: game.title.clone(),
+        plain_title: game.title.clone(),
+        link: game.link.clone(),
+        ..Item::default()
+    }
+}
+
+async fn parse_page(
+    item: &Item,
+    game_data: String,
+    options: &ScrapeOptions,
+) -> Result<(String, Result<PageData, PageError>)> {
+    if let Some(recorder) = &options.recorder {
+        recorder.record_page(&item.link, &game_data)?;
+    }
+    let plugin = options.plugin.clone();
+    let comments = options.comments;
+    let parsed = tokio::task::spawn_blocking(move || {
+        let parsed = match plugin {
+            Some(plugin) => plugin
+                .parse(&game_data)
+                .map(PageData::Plugin)
+                .map_err(PageError::Plugin),
+            None => parse_itch_game_page_data(&game_data)
+                .map(|mut data| {
+                    if comments {
+                        data.comments = parse_itch_game_comments(&game_data);
+                    }
+                    PageData::Itch(data)
+                })
+                .map_err(PageError::Itch),
+        };
+        (game_data, parsed)
+    })
+    .await?;
+
+    Ok(parsed)
+}
+
+// a page that doesn't parse is reported and skipped, or fails the scrape with --fail-on-error
+fn into_record(
+    item: Item,
+    game_data: &str,
+    parsed: Result<PageData, PageError>,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
+) -> Result<Option<ItchData>> {
+    match parsed {
+        Ok(data) => {
+            reporter.report(ScrapeEvent::Item {
+                title: item.plain_title.clone(),
+                link: item.link.clone(),
+            });
+            Ok(Some(match data {
+                PageData::Itch(data) => combine_itch_rss_and_info_data(data, item),
+                PageData::Plugin(data) => combine_rss_and_plugin_data(data, item),
+            }))
+        }
+        Err(PageError::Itch(err)) => {
+            let diagnostic = ParseDiagnostic::from_html_error(&item.link, game_data, &err);
+            let message = diagnostic.to_string();
+            reporter.report(ScrapeEvent::Error {
+                message: message.clone(),
+                url: item.link,
+                diagnostic: Some(Arc::new(diagnostic)),
+            });
+            match options.fail_on_error {
+                true => Err(anyhow!(message)),
+                false => Ok(None),
+            }
+        }
+        Err(PageError::Plugin(err)) => {
+            let message = format!("Plugin failed to parse {:?}: {:#}", item.link, err);
+            reporter.report(ScrapeEvent::Error {
+                message: message.clone(),
+                url: item.link,
+                diagnostic: None,
+            });
+            match options.fail_on_error {
+                true => Err(anyhow!(message)),
+                false => Ok(None),
+            }
+        }
     }
+}
+
+pub(crate) fn report_downloaded(bandwidth: &Bandwidth, reporter: &dyn ProgressReporter) {
+    reporter.report(ScrapeEvent::Downloaded {
+        bytes: bandwidth.total(),
+        hosts: bandwidth.hosts(),
+    });
+}
 
-    pb.finish_with_message("Done scraping.");
-    Ok(itch_data_output)
+enum PageData {
+    Itch(MoreInfoTableData),
+    Plugin(GameRecord),
 }
 
-async fn fetch_url(client: &Client, url: &str, max_retries: u32) -> Result<String, reqwest::Error> {
+enum PageError {
+    Itch(ItchHTMLDataFormatError),
+    Plugin(anyhow::Error),
+}
+
+// only trusts a newest-first order once it has actually seen dates going down
+struct FeedOrdering {
+    previous: Option<DateTime<Utc>>,
+    descending: bool,
+    decreases: usize,
+}
+
+impl FeedOrdering {
+    fn new() -> FeedOrdering {
+        FeedOrdering {
+            previous: None,
+            descending: true,
+            decreases: 0,
+        }
+    }
+
+    fn observe(&mut self, date: Option<DateTime<Utc>>) {
+        match (self.previous, date) {
+            (Some(previous), Some(date)) if date > previous => self.descending = false,
+            (Some(previous), Some(date)) if date < previous => self.decreases += 1,
+            (_, None) => self.descending = false,
+            _ => {}
+        }
+        if date.is_some() {
+            self.previous = date;
+        }
+    }
+
+    fn is_newest_first(&self) -> bool {
+        self.descending && self.decreases > 0
+    }
+}
+
+pub(crate) fn item_date(item: &Item, field: DateField) -> Option<DateTime<Utc>> {
+    let date = match field {
+        DateField::Published => &item.pub_date,
+        DateField::Created => &item.create_date,
+        DateField::Updated => &item.update_date,
+    };
+
+    parse_feed_date(date)
+}
+
+pub(crate) async fn fetch_url(
+    client: &Client,
+    url: &str,
+    max_retries: u32,
+    limiter: &RateLimiter,
+    bandwidth: &Bandwidth,
+    reporter: &dyn ProgressReporter,
+) -> Result<String, reqwest::Error> {
+    let body = fetch_bytes(client, url, max_retries, limiter, bandwidth, reporter).await?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+pub(crate) async fn fetch_bytes(
+    client: &Client,
+    url: &str,
+    max_retries: u32,
+    limiter: &RateLimiter,
+    bandwidth: &Bandwidth,
+    reporter: &dyn ProgressReporter,
+) -> Result<Vec<u8>, reqwest::Error> {
     let mut retries = 0;
     let mut delay = 1;
 
     loop {
+        let waiting = Instant::now();
+        let _permit = limiter.acquire(url).await;
+        if waiting.elapsed() >= Duration::from_millis(1) {
+            debug!(target: "http", url, waited_ms = waiting.elapsed().as_millis() as u64, "rate limit wait");
+        }
+
+        debug!(target: "http", url, attempt = retries + 1, "> GET {}", url);
+        let started = Instant::now();
         let response = client.get(url).send().await;
+        let elapsed_ms = started.elapsed().as_millis() as u64;
 
         match response {
-            Ok(res) => match res.status() {
-                StatusCode::OK => return Ok(res.text().await?),
-                StatusCode::TOO_MANY_REQUESTS => {
-                    if retries >= max_retries {
+            Ok(res) => {
+                debug!(
+                    target: "http",
+                    url,
+                    status = res.status().as_u16(),
+                    elapsed_ms,
+                    content_length = res.content_length(),
+                    "< {:?} {}",
+                    res.version(),
+                    res.status()
+                );
+                trace!(target: "http", url, headers = ?res.headers(), "response headers");
+
+                match res.status() {
+                    StatusCode::OK => return read_body(res, url, bandwidth).await,
+                    StatusCode::TOO_MANY_REQUESTS => {
+                        if retries >= max_retries {
+                            debug!(target: "http", url, retries, "rate limited, giving up");
+                            return Err(res.error_for_status().unwrap_err());
+                        }
+
+                        debug!(target: "http", url, retry_in_secs = delay, "rate limited, retrying");
+                        reporter.report(ScrapeEvent::RateLimited {
+                            url: url.to_string(),
+                            retry_in: Duration::from_secs(delay),
+                        });
+                        sleep(Duration::from_secs(delay)).await;
+                        delay = std::cmp::min(300, delay * 2);
+                        retries += 1;
+                    }
+                    status => {
+                        debug!(target: "http", url, status = status.as_u16(), "not retrying");
                         return Err(res.error_for_status().unwrap_err());
                     }
-
-                    sleep(Duration::from_secs(delay)).await;
-                    delay = std::cmp::min(300, delay * 2);
-                    retries += 1;
                 }
-                _ => return Err(res.error_for_status().unwrap_err()),
-            },
+            }
             Err(err) => {
                 if retries >= max_retries {
+                    debug!(target: "http", url, elapsed_ms, retries, error = %err, "request failed, giving up");
                     return Err(err);
                 }
 
+                debug!(target: "http", url, elapsed_ms, retry_in_secs = delay, error = %err, "request failed, retrying");
                 sleep(Duration::from_secs(delay)).await;
                 delay = std::cmp::min(300, delay * 2);
                 retries += 1;
@@ -140,7 +762,37 @@ async fn fetch_url(client: &Client, url: &str, max_retries: u32) -> Result<Strin
     }
 }
 
+// read chunk by chunk so every chunk is counted, and throttled under --max-bandwidth
+async fn read_body(
+    mut res: Response,
+    url: &str,
+    bandwidth: &Bandwidth,
+) -> Result<Vec<u8>, reqwest::Error> {
+    let mut body = Vec::new();
+    while let Some(chunk) = res.chunk().await? {
+        bandwidth.consume(url, chunk.len()).await;
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(body)
+}
+
+// pages scraped without a feed have no feed dates at all, which isn't worth a warning
+fn feed_date_warnings(item: &Item) -> Vec<String> {
+    [
+        ("pubDate", &item.pub_date),
+        ("createDate", &item.create_date),
+        ("updateDate", &item.update_date),
+    ]
+    .into_iter()
+    .filter(|(_, date)| !date.is_empty() && parse_feed_date(date).is_none())
+    .map(|(field, date)| format!("Unrecognized feed {} {:?}", field, date))
+    .collect()
+}
+
 fn combine_itch_rss_and_info_data(table_data: MoreInfoTableData, rss_data: Item) -> ItchData {
+    let mut warnings = feed_date_warnings(&rss_data);
+    warnings.extend(table_data.warnings);
     ItchData {
         update_date: rss_data.update_date,
         create_date: rss_data.create_date,
@@ -148,7 +800,9 @@ fn combine_itch_rss_and_info_data(table_data: MoreInfoTableData, rss_data: Item)
         link: rss_data.link,
         description: rss_data.description,
         pub_date: rss_data.pub_date,
+        price_info: Price::from_sources(&rss_data.price, &table_data.price),
         price: rss_data.price,
+        price_converted: None,
         title: rss_data.title,
         average_session: table_data.average_session,
         platforms: table_data.platforms,
@@ -157,11 +811,99 @@ fn combine_itch_rss_and_info_data(table_data: MoreInfoTableData, rss_data: Item)
         inputs: table_data.inputs,
         authors: table_data.authors,
         release_date: table_data.release_date,
+        updated_date: table_data.updated_date,
+        published_date: table_data.published_date,
         rating: table_data.rating,
         links: table_data.links,
         genres: table_data.genres,
+        raw_genres: Vec::new(),
         status: table_data.status,
         tags: table_data.tags,
+        raw_tags: Vec::new(),
         accessibility: table_data.accessibility,
+        cover_image: if rss_data.imageurl.is_empty() {
+            table_data.cover_image
+        } else {
+            rss_data.imageurl
+        },
+        screenshots: table_data.screenshots,
+        images: Vec::new(),
+        comments: table_data.comments,
+        sentiment: None,
+        warnings,
+        extra: serde_json::Map::new(),
+    }
+}
+
+fn combine_rss_and_plugin_data(plugin_data: GameRecord, rss_data: Item) -> ItchData {
+    let warnings = feed_date_warnings(&rss_data);
+    let price = plugin_data.price.unwrap_or(rss_data.price);
+    ItchData {
+        update_date: rss_data.update_date,
+        create_date: rss_data.create_date,
+        plain_title: rss_data.plain_title,
+        link: rss_data.link,
+        description: plugin_data.description.unwrap_or(rss_data.description),
+        pub_date: rss_data.pub_date,
+        price_info: Price::parse(&price),
+        price,
+        price_converted: None,
+        title: plugin_data.title.unwrap_or(rss_data.title),
+        average_session: plugin_data.average_session,
+        platforms: plugin_data.platforms,
+        languages: plugin_data.languages,
+        made_with: plugin_data.made_with,
+        inputs: plugin_data.inputs,
+        authors: plugin_data
+            .authors
+            .into_iter()
+            .map(|name| Author {
+                name,
+                url: String::new(),
+            })
+            .collect(),
+        release_date: plugin_data.release_date,
+        updated_date: String::new(),
+        published_date: String::new(),
+        rating: Some(ItchRating {
+            score: plugin_data.rating.score,
+            count: plugin_data.rating.count,
+        })
+        .filter(|rating| rating.score != 0.0 || rating.count != 0),
+        links: plugin_data
+            .links
+            .into_iter()
+            .map(|link| Link {
+                name: link.name,
+                url: link.url,
+            })
+            .collect(),
+        genres: plugin_data
+            .genres
+            .into_iter()
+            .map(|name| Genre {
+                name,
+                ..Genre::default()
+            })
+            .collect(),
+        raw_genres: Vec::new(),
+        status: plugin_data.status,
+        tags: plugin_data
+            .tags
+            .into_iter()
+            .map(|name| Tag {
+                name,
+                ..Tag::default()
+            })
+            .collect(),
+        raw_tags: Vec::new(),
+        accessibility: plugin_data.accessibility,
+        cover_image: rss_data.imageurl,
+        screenshots: Vec::new(),
+        images: Vec::new(),
+        comments: Vec::new(),
+        sentiment: None,
+        warnings,
+        extra: serde_json::Map::new(),
     }
 }
@@ -1 +1,14 @@
+pub mod bandwidth;
+pub mod discovery;
+pub mod distributed;
+pub mod fixtures;
+pub mod images;
+pub mod itch_browse_scraper;
 pub mod itch_rss_scraper;
+pub mod options;
+pub mod progress;
+pub mod rate_limit;
+pub mod scraper;
+pub mod session;
+pub mod site_definition;
+pub mod steam_scraper;
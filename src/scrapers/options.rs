@@ -0,0 +1,116 @@
+use crate::checkpoint::Checkpoint;
+use crate::exit::Failure;
+use crate::parsers::dates::parse_date_bound;
+use crate::parsers::plugin::Plugin;
+use crate::scrapers::bandwidth::Bandwidth;
+use crate::scrapers::fixtures::FixtureRecorder;
+use crate::scrapers::rate_limit::RateLimiter;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct ScrapeOptions {
+    pub max_retries: u32,
+    pub pages: Vec<i32>,
+    pub fail_on_error: bool,
+    // whether game page comments are read into each record
+    pub comments: bool,
+    pub window: DateWindow,
+    pub limiter: Arc<RateLimiter>,
+    pub bandwidth: Arc<Bandwidth>,
+    pub checkpoint: Option<Arc<Checkpoint>>,
+    pub concurrency: Concurrency,
+    pub plugin: Option<Arc<Plugin>>,
+    pub recorder: Option<Arc<FixtureRecorder>>,
+}
+
+// what the cli runs with when no flags are given, for embedding the scrapers as a library
+impl Default for ScrapeOptions {
+    fn default() -> ScrapeOptions {
+        ScrapeOptions {
+            max_retries: 20,
+            pages: (1..=300).collect(),
+            fail_on_error: false,
+            comments: false,
+            window: DateWindow::default(),
+            limiter: Arc::new(RateLimiter::default()),
+            bandwidth: Arc::new(Bandwidth::default()),
+            checkpoint: None,
+            concurrency: Concurrency::default(),
+            plugin: None,
+            recorder: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Concurrency {
+    pub fetch: usize,
+    pub parse: usize,
+    pub sink: usize,
+}
+
+impl Default for Concurrency {
+    fn default() -> Concurrency {
+        Concurrency {
+            fetch: 1,
+            parse: 1,
+            sink: 1,
+        }
+    }
+}
+
+#[derive(Debug, ValueEnum, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DateField {
+    #[default]
+    Published,
+    Created,
+    Updated,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DateWindow {
+    pub field: DateField,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl DateWindow {
+    pub fn parse(field: DateField, since: Option<&str>, until: Option<&str>) -> Result<DateWindow> {
+        let bound = |value: Option<&str>, end_of_day: bool| match value {
+            Some(value) => parse_date_bound(value, end_of_day)
+                .map(Some)
+                .ok_or_else(|| Failure::Config(anyhow!("Unrecognized date {:?}", value))),
+            None => Ok(None),
+        };
+
+        Ok(DateWindow {
+            field,
+            since: bound(since, false)?,
+            until: bound(until, true)?,
+        })
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+
+    pub fn contains(&self, date: Option<DateTime<Utc>>) -> bool {
+        if !self.is_bounded() {
+            return true;
+        }
+
+        date.is_some_and(|date| {
+            self.since.is_none_or(|since| date >= since)
+                && self.until.is_none_or(|until| date < until)
+        })
+    }
+
+    pub fn is_before(&self, date: Option<DateTime<Utc>>) -> bool {
+        matches!((self.since, date), (Some(since), Some(date)) if date < since)
+    }
+}
@@ -0,0 +1,189 @@
+use crate::diagnostics::ParseDiagnostic;
+use crate::units::format_size;
+use clap::ValueEnum;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ScrapeEvent {
+    Started {
+        pages: u64,
+    },
+    PageFinished {
+        page: i32,
+    },
+    Item {
+        title: String,
+        link: String,
+    },
+    Error {
+        url: String,
+        message: String,
+        #[serde(skip)]
+        diagnostic: Option<Arc<ParseDiagnostic>>,
+    },
+    Warning {
+        url: String,
+        message: String,
+    },
+    Downloaded {
+        bytes: u64,
+        hosts: BTreeMap<String, u64>,
+    },
+    RateLimited {
+        url: String,
+        #[serde(rename = "retry_in_secs", serialize_with = "as_secs")]
+        retry_in: Duration,
+    },
+    Finished,
+}
+
+fn as_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+#[derive(Debug, ValueEnum, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressMode {
+    #[default]
+    Bar,
+    Json,
+    None,
+}
+
+pub trait ProgressReporter: Sync {
+    fn report(&self, event: ScrapeEvent);
+}
+
+pub struct ProgressBarReporter {
+    bar: ProgressBar,
+}
+
+impl ProgressBarReporter {
+    pub fn new() -> ProgressBarReporter {
+        ProgressBarReporter {
+            bar: ProgressBar::new(0),
+        }
+    }
+}
+
+impl Default for ProgressBarReporter {
+    fn default() -> ProgressBarReporter {
+        ProgressBarReporter::new()
+    }
+}
+
+impl ProgressReporter for ProgressBarReporter {
+    fn report(&self, event: ScrapeEvent) {
+        match event {
+            ScrapeEvent::Started { pages } => {
+                self.bar.set_length(pages);
+                self.bar.set_style(
+                    ProgressStyle::default_bar()
+                        .template(
+                            "{spinner:.green} [{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} pages",
+                        )
+                        .expect("progress bar template is valid")
+                        .progress_chars("##-"),
+                );
+                self.bar.enable_steady_tick(Duration::new(0, 100000000));
+                self.bar.tick();
+            }
+            ScrapeEvent::PageFinished { .. } => self.bar.inc(1),
+            ScrapeEvent::Error {
+                message,
+                diagnostic,
+                ..
+            } => self.bar.suspend(|| match diagnostic {
+                Some(diagnostic) => eprintln!("{}", diagnostic.render()),
+                None => eprintln!("{}", message),
+            }),
+            ScrapeEvent::Warning { message, .. } => {
+                self.bar.suspend(|| eprintln!("Warning: {}", message))
+            }
+            ScrapeEvent::Downloaded { bytes, hosts } => self.bar.suspend(|| {
+                let hosts: Vec<String> = hosts
+                    .iter()
+                    .map(|(host, bytes)| format!("{} {}", host, format_size(*bytes)))
+                    .collect();
+                eprintln!("Downloaded {} ({})", format_size(bytes), hosts.join(", "))
+            }),
+            ScrapeEvent::Finished => self.bar.finish_with_message("Done scraping."),
+            ScrapeEvent::Item { .. } | ScrapeEvent::RateLimited { .. } => {}
+        }
+    }
+}
+
+pub struct SilentReporter;
+
+impl ProgressReporter for SilentReporter {
+    fn report(&self, _event: ScrapeEvent) {}
+}
+
+// for --quiet and --progress none: no bar or summaries, but a failed page is still reported
+pub struct ErrorReporter;
+
+impl ProgressReporter for ErrorReporter {
+    fn report(&self, event: ScrapeEvent) {
+        if let ScrapeEvent::Error {
+            message,
+            diagnostic,
+            ..
+        } = event
+        {
+            match diagnostic {
+                Some(diagnostic) => eprintln!("{}", diagnostic.render()),
+                None => eprintln!("{}", message),
+            }
+        }
+    }
+}
+
+pub struct JsonReporter;
+
+impl ProgressReporter for JsonReporter {
+    fn report(&self, event: ScrapeEvent) {
+        if let Ok(line) = serde_json::to_string(&event) {
+            let _ = writeln!(io::stderr().lock(), "{}", line);
+        }
+    }
+}
+
+pub struct CountingReporter<R> {
+    inner: R,
+    errors: AtomicUsize,
+}
+
+impl<R: ProgressReporter> CountingReporter<R> {
+    pub fn new(inner: R) -> CountingReporter<R> {
+        CountingReporter {
+            inner,
+            errors: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn errors(&self) -> usize {
+        self.errors.load(Ordering::Relaxed)
+    }
+}
+
+impl<R: ProgressReporter> ProgressReporter for CountingReporter<R> {
+    fn report(&self, event: ScrapeEvent) {
+        if let ScrapeEvent::Error { .. } = event {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        self.inner.report(event);
+    }
+}
+
+impl<R: ProgressReporter + ?Sized> ProgressReporter for Box<R> {
+    fn report(&self, event: ScrapeEvent) {
+        (**self).report(event)
+    }
+}
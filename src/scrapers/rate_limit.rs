@@ -0,0 +1,149 @@
+use anyhow::{anyhow, Result};
+use reqwest::Url;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::{sleep, Duration, Instant};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct HostLimit {
+    pub requests_per_second: f64,
+    pub burst: Option<u32>,
+    pub max_concurrency: Option<usize>,
+}
+
+// one token bucket and concurrency cap per configured host, shared by every scrape that
+// holds the same limiter, and an optional bucket every request takes from whatever its host
+#[derive(Debug, Default, Clone)]
+pub struct RateLimiter {
+    hosts: HashMap<String, Arc<HostBucket>>,
+    global: Option<Arc<HostBucket>>,
+}
+
+#[derive(Debug)]
+struct HostBucket {
+    rate: f64,
+    burst: f64,
+    state: Mutex<Bucket>,
+    running: Option<Arc<Semaphore>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(limits: HashMap<String, HostLimit>) -> Result<RateLimiter> {
+        let mut hosts = HashMap::new();
+        for (host, limit) in limits {
+            if limit.requests_per_second.is_nan() || limit.requests_per_second <= 0.0 {
+                return Err(anyhow!(
+                    "Rate limit for {:?} needs a requests_per_second above 0",
+                    host
+                ));
+            }
+            if limit.max_concurrency == Some(0) {
+                return Err(anyhow!(
+                    "Rate limit for {:?} needs a max_concurrency above 0",
+                    host
+                ));
+            }
+
+            hosts.insert(
+                host.to_lowercase(),
+                Arc::new(HostBucket::new(
+                    limit.requests_per_second,
+                    limit.burst,
+                    limit.max_concurrency,
+                )),
+            );
+        }
+
+        Ok(RateLimiter {
+            hosts,
+            global: None,
+        })
+    }
+
+    // a copy that also holds every request to requests_per_second across all hosts; the host
+    // buckets stay shared with the original
+    pub fn with_global_rate(&self, requests_per_second: f64) -> Result<RateLimiter> {
+        if requests_per_second.is_nan() || requests_per_second <= 0.0 {
+            return Err(anyhow!("--rps needs to be above 0"));
+        }
+
+        Ok(RateLimiter {
+            hosts: self.hosts.clone(),
+            global: Some(Arc::new(HostBucket::new(requests_per_second, None, None))),
+        })
+    }
+
+    // waits for the next free slot, the global one first; the returned permit caps the host's
+    // concurrency until dropped
+    pub async fn acquire(&self, url: &str) -> Option<OwnedSemaphorePermit> {
+        if let Some(global) = &self.global {
+            global.take().await;
+        }
+        let bucket = self.bucket_for(url)?;
+
+        let permit = match &bucket.running {
+            Some(running) => running.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        bucket.take().await;
+
+        permit
+    }
+
+    // the most specific configured host wins, so "itch.io" also covers creator subdomains
+    // like someone.itch.io unless they have their own entry
+    fn bucket_for(&self, url: &str) -> Option<&HostBucket> {
+        let url = Url::parse(url).ok()?;
+        let host = url.host_str()?.to_lowercase();
+
+        self.hosts
+            .iter()
+            .filter(|(configured, _)| {
+                host == **configured || host.ends_with(&format!(".{}", configured))
+            })
+            .max_by_key(|(configured, _)| configured.len())
+            .map(|(_, bucket)| bucket.as_ref())
+    }
+}
+
+impl HostBucket {
+    fn new(rate: f64, burst: Option<u32>, max_concurrency: Option<usize>) -> HostBucket {
+        let burst = f64::from(burst.unwrap_or(1).max(1));
+        HostBucket {
+            rate,
+            burst,
+            state: Mutex::new(Bucket {
+                tokens: burst,
+                refilled: Instant::now(),
+            }),
+            running: max_concurrency.map(|max| Arc::new(Semaphore::new(max))),
+        }
+    }
+
+    async fn take(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                let now = Instant::now();
+                let elapsed = now.duration_since(state.refilled).as_secs_f64();
+                state.tokens = (state.tokens + elapsed * self.rate).min(self.burst);
+                state.refilled = now;
+
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - state.tokens) / self.rate)
+            };
+            sleep(wait).await;
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::scrapers::distributed::{scrape_distributed, Coordinator};
+use crate::scrapers::itch_browse_scraper::scrape_itch_browse;
+use crate::scrapers::itch_rss_scraper::{scrape_itch_rss_feed, ItchData};
+use crate::scrapers::options::ScrapeOptions;
+use crate::scrapers::progress::ProgressReporter;
+use crate::scrapers::site_definition::{scrape_defined_site, SiteDefinition};
+use crate::scrapers::steam_scraper::scrape_steam;
+use anyhow::Result;
+use std::future::Future;
+
+// the name the record goes by in the graphql and openapi schemas
+pub type GameRecord = ItchData;
+
+// one per site, so a service can hold whichever it was configured with and scrape it the same
+// way the binary does, minus the output and post-processing steps
+pub trait Scraper {
+    fn scrape(
+        &self,
+        options: &ScrapeOptions,
+        reporter: &dyn ProgressReporter,
+    ) -> impl Future<Output = Result<Vec<GameRecord>>> + Send;
+}
+
+pub struct ItchScraper {
+    pub url: String,
+}
+
+impl ItchScraper {
+    pub fn new(url: impl Into<String>) -> ItchScraper {
+        ItchScraper { url: url.into() }
+    }
+}
+
+impl Scraper for ItchScraper {
+    fn scrape(
+        &self,
+        options: &ScrapeOptions,
+        reporter: &dyn ProgressReporter,
+    ) -> impl Future<Output = Result<Vec<GameRecord>>> + Send {
+        scrape_itch_rss_feed(self.url.clone(), options, reporter)
+    }
+}
+
+pub struct ItchBrowseScraper {
+    pub url: String,
+}
+
+impl ItchBrowseScraper {
+    pub fn new(url: impl Into<String>) -> ItchBrowseScraper {
+        ItchBrowseScraper { url: url.into() }
+    }
+}
+
+impl Scraper for ItchBrowseScraper {
+    fn scrape(
+        &self,
+        options: &ScrapeOptions,
+        reporter: &dyn ProgressReporter,
+    ) -> impl Future<Output = Result<Vec<GameRecord>>> + Send {
+        scrape_itch_browse(self.url.clone(), options, reporter)
+    }
+}
+
+pub struct SteamScraper {
+    pub url: String,
+}
+
+impl SteamScraper {
+    pub fn new(url: impl Into<String>) -> SteamScraper {
+        SteamScraper { url: url.into() }
+    }
+}
+
+impl Scraper for SteamScraper {
+    fn scrape(
+        &self,
+        options: &ScrapeOptions,
+        reporter: &dyn ProgressReporter,
+    ) -> impl Future<Output = Result<Vec<GameRecord>>> + Send {
+        scrape_steam(self.url.clone(), options, reporter)
+    }
+}
+
+pub struct DefinedSiteScraper {
+    pub url: String,
+    pub definition: SiteDefinition,
+}
+
+impl Scraper for DefinedSiteScraper {
+    fn scrape(
+        &self,
+        options: &ScrapeOptions,
+        reporter: &dyn ProgressReporter,
+    ) -> impl Future<Output = Result<Vec<GameRecord>>> + Send {
+        scrape_defined_site(self.url.clone(), &self.definition, options, reporter)
+    }
+}
+
+pub struct DistributedScraper {
+    pub url: String,
+    pub coordinator: Coordinator,
+}
+
+impl Scraper for DistributedScraper {
+    fn scrape(
+        &self,
+        options: &ScrapeOptions,
+        reporter: &dyn ProgressReporter,
+    ) -> impl Future<Output = Result<Vec<GameRecord>>> + Send {
+        scrape_distributed(self.url.clone(), &self.coordinator, options, reporter)
+    }
+}
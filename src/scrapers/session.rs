@@ -0,0 +1,81 @@
+use crate::scrapers::itch_rss_scraper::{scrape_feed, ItchData};
+use crate::scrapers::options::ScrapeOptions;
+use crate::scrapers::progress::{ProgressReporter, ScrapeEvent, SilentReporter};
+use anyhow::Result;
+use std::future::ready;
+
+type ItemHook = Box<dyn Fn(&ItchData) + Send + Sync>;
+type PageHook = Box<dyn Fn(i32) + Send + Sync>;
+type ErrorHook = Box<dyn Fn(&str, &str) + Send + Sync>;
+
+pub struct ScrapeSession {
+    url: String,
+    options: ScrapeOptions,
+    reporter: Box<dyn ProgressReporter + Send>,
+    on_item: Vec<ItemHook>,
+    on_page: Vec<PageHook>,
+    on_error: Vec<ErrorHook>,
+}
+
+impl ScrapeSession {
+    pub fn new(url: impl Into<String>, options: ScrapeOptions) -> ScrapeSession {
+        ScrapeSession {
+            url: url.into(),
+            options,
+            reporter: Box::new(SilentReporter),
+            on_item: Vec::new(),
+            on_page: Vec::new(),
+            on_error: Vec::new(),
+        }
+    }
+
+    pub fn reporter(mut self, reporter: impl ProgressReporter + Send + 'static) -> ScrapeSession {
+        self.reporter = Box::new(reporter);
+        self
+    }
+
+    pub fn on_item(mut self, hook: impl Fn(&ItchData) + Send + Sync + 'static) -> ScrapeSession {
+        self.on_item.push(Box::new(hook));
+        self
+    }
+
+    pub fn on_page(mut self, hook: impl Fn(i32) + Send + Sync + 'static) -> ScrapeSession {
+        self.on_page.push(Box::new(hook));
+        self
+    }
+
+    // hooks get the failing URL and the error message
+    pub fn on_error(mut self, hook: impl Fn(&str, &str) + Send + Sync + 'static) -> ScrapeSession {
+        self.on_error.push(Box::new(hook));
+        self
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        scrape_feed(self.url.clone(), &self.options, self, |record| {
+            for hook in &self.on_item {
+                hook(&record);
+            }
+            ready(true)
+        })
+        .await
+    }
+}
+
+impl ProgressReporter for ScrapeSession {
+    fn report(&self, event: ScrapeEvent) {
+        match &event {
+            ScrapeEvent::PageFinished { page } => {
+                for hook in &self.on_page {
+                    hook(*page);
+                }
+            }
+            ScrapeEvent::Error { url, message, .. } => {
+                for hook in &self.on_error {
+                    hook(url, message);
+                }
+            }
+            _ => {}
+        }
+        self.reporter.report(event);
+    }
+}
@@ -0,0 +1,279 @@
+use crate::filters::record_date;
+use crate::parsers::itch_game_info_parser::ItchRating;
+use crate::scrapers::itch_rss_scraper::{fetch_url, report_downloaded, ItchData};
+use crate::scrapers::options::ScrapeOptions;
+use crate::scrapers::progress::{ProgressReporter, ScrapeEvent};
+use anyhow::{anyhow, Context, Result};
+use futures_util::stream::{self, StreamExt};
+use reqwest::{Client, Url};
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+// e.g.
+//
+//   page_url = "{url}?page={page}"
+//   item_selector = "div.game_cell a.title"
+//
+//   [fields]
+//   title = "h1"
+//   tags = "ul.tags a"
+//   pub_date = { selector = "time", attr = "datetime" }
+//   "rating.score" = { selector = "[itemprop=ratingValue]", attr = "content" }
+//
+// fields are record field names, dotted for nested ones, and anything that isn't a record
+// field is kept as an extra field
+#[derive(Deserialize, Debug)]
+struct Definition {
+    #[serde(default = "default_page_url")]
+    page_url: String,
+    item_selector: String,
+    #[serde(default)]
+    fields: BTreeMap<String, FieldSource>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum FieldSource {
+    Selector(String),
+    Detailed {
+        selector: String,
+        attr: Option<String>,
+    },
+}
+
+fn default_page_url() -> String {
+    "{url}?page={page}".to_string()
+}
+
+#[derive(Debug)]
+struct Field {
+    path: Vec<String>,
+    selector: Selector,
+    attr: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct SiteDefinition {
+    page_url: String,
+    item_selector: Selector,
+    fields: Vec<Field>,
+}
+
+impl SiteDefinition {
+    pub fn from_file(path: &Path) -> Result<SiteDefinition> {
+        let contents =
+            fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let definition: Definition = toml::from_str(&contents)
+            .with_context(|| format!("Invalid site definition {:?}", path))?;
+
+        let fields = definition
+            .fields
+            .into_iter()
+            .map(|(name, source)| {
+                let (selector, attr) = match source {
+                    FieldSource::Selector(selector) => (selector, None),
+                    FieldSource::Detailed { selector, attr } => (selector, attr),
+                };
+                Ok(Field {
+                    path: name.split('.').map(str::to_string).collect(),
+                    selector: parse_selector(&selector)
+                        .with_context(|| format!("Invalid selector for field {:?}", name))?,
+                    attr,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(SiteDefinition {
+            page_url: definition.page_url,
+            item_selector: parse_selector(&definition.item_selector)
+                .context("Invalid item_selector")?,
+            fields,
+        })
+    }
+
+    fn item_links(&self, listing_url: &str, html: &str) -> Vec<String> {
+        let base = Url::parse(listing_url).ok();
+        let document = Html::parse_document(html);
+
+        let mut links: Vec<String> = Vec::new();
+        for element in document.select(&self.item_selector) {
+            let Some(href) = element.value().attr("href") else {
+                continue;
+            };
+            let link = match &base {
+                Some(base) => base
+                    .join(href)
+                    .map(String::from)
+                    .unwrap_or(href.to_string()),
+                None => href.to_string(),
+            };
+            if !links.contains(&link) {
+                links.push(link);
+            }
+        }
+
+        links
+    }
+
+    pub fn parse_page(&self, link: &str, html: &str) -> Result<ItchData> {
+        let document = Html::parse_document(html);
+        let mut record = serde_json::to_value(ItchData::default())?;
+        record["link"] = Value::from(link);
+        // so rating.score and rating.count can be mapped, an unmapped rating reads back as None
+        record["rating"] = serde_json::to_value(ItchRating::default())?;
+
+        for field in &self.fields {
+            let values: Vec<String> = document
+                .select(&field.selector)
+                .filter_map(|element| field.extract(element))
+                .collect();
+            let name = field.path.join(".");
+
+            let slot = slot(&mut record, &field.path)
+                .ok_or_else(|| anyhow!("{:?} is not a field that can be mapped", name))?;
+            *slot = match slot {
+                Value::Array(_) => Value::from(values),
+                Value::Number(_) => match values.first() {
+                    Some(value) => number(value).ok_or_else(|| {
+                        anyhow!("Field {:?} expects a number, found {:?}", name, value)
+                    })?,
+                    None => continue,
+                },
+                _ => match values.into_iter().next() {
+                    Some(value) => Value::from(value),
+                    None => continue,
+                },
+            };
+        }
+
+        // a page usually only has one title, which itch feeds split in two
+        if record["plain_title"] == "" {
+            record["plain_title"] = record["title"].clone();
+        }
+
+        Ok(serde_json::from_value(record)?)
+    }
+}
+
+impl Field {
+    fn extract(&self, element: ElementRef) -> Option<String> {
+        let value = match &self.attr {
+            Some(attr) => element.value().attr(attr)?.to_string(),
+            None => element.text().collect::<Vec<_>>().join(" "),
+        };
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        (!value.is_empty()).then_some(value)
+    }
+}
+
+fn parse_selector(selector: &str) -> Result<Selector> {
+    Selector::parse(selector).map_err(|err| anyhow!("{:?}: {}", selector, err))
+}
+
+// record fields keep their type, anything else lands in the record's extra fields
+fn slot<'a>(record: &'a mut Value, path: &[String]) -> Option<&'a mut Value> {
+    let (last, parents) = path.split_last()?;
+    let mut value = record;
+    for parent in parents {
+        value = value.get_mut(parent.as_str())?;
+    }
+
+    let object: &mut Map<String, Value> = value.as_object_mut()?;
+    Some(object.entry(last.as_str()).or_insert(Value::Null))
+}
+
+fn number(value: &str) -> Option<Value> {
+    let cleaned: String = value
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
+        .collect();
+    let number: f64 = cleaned.parse().ok()?;
+
+    serde_json::Number::from_f64(number).map(Value::Number)
+}
+
+pub async fn scrape_defined_site(
+    url: String,
+    definition: &SiteDefinition,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
+) -> Result<Vec<ItchData>> {
+    let client = Client::new();
+    let max_retries = options.max_retries;
+    let limiter = options.limiter.as_ref();
+    let bandwidth = options.bandwidth.as_ref();
+
+    reporter.report(ScrapeEvent::Started {
+        pages: options.pages.len() as u64,
+    });
+
+    let mut output = Vec::new();
+    for &page in &options.pages {
+        let listing_url = definition
+            .page_url
+            .replace("{url}", &url)
+            .replace("{page}", &page.to_string());
+        let listing = fetch_url(
+            &client,
+            &listing_url,
+            max_retries,
+            limiter,
+            bandwidth,
+            reporter,
+        )
+        .await?;
+        let links = definition.item_links(&listing_url, &listing);
+
+        let mut pages = stream::iter(links)
+            .map(|link| async {
+                let html =
+                    fetch_url(&client, &link, max_retries, limiter, bandwidth, reporter).await;
+                (link, html)
+            })
+            .buffered(options.concurrency.fetch);
+
+        while let Some((link, html)) = pages.next().await {
+            match definition.parse_page(&link, &html?) {
+                Ok(record) => {
+                    if options.window.is_bounded()
+                        && !options
+                            .window
+                            .contains(record_date(&record, options.window.field))
+                    {
+                        continue;
+                    }
+                    reporter.report(ScrapeEvent::Item {
+                        title: record.plain_title.clone(),
+                        link: record.link.clone(),
+                    });
+                    output.push(record);
+                }
+                Err(err) => {
+                    let message = format!("Failed to parse {:?}: {:#}", link, err);
+                    reporter.report(ScrapeEvent::Error {
+                        message: message.clone(),
+                        url: link,
+                        diagnostic: None,
+                    });
+                    if options.fail_on_error {
+                        return Err(anyhow!(message));
+                    }
+                }
+            }
+        }
+
+        if let Some(checkpoint) = &options.checkpoint {
+            checkpoint.save(page + 1, &output)?;
+        }
+        reporter.report(ScrapeEvent::PageFinished { page });
+    }
+
+    report_downloaded(bandwidth, reporter);
+    reporter.report(ScrapeEvent::Finished);
+    Ok(output)
+}
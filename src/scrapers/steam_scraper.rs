@@ -0,0 +1,411 @@
+use crate::filters::record_date;
+use crate::parsers::itch_game_info_parser::{Author, Genre, ItchRating, Link, Tag};
+use crate::parsers::price::Price;
+use crate::scrapers::itch_rss_scraper::{fetch_url, report_downloaded, ItchData};
+use crate::scrapers::options::ScrapeOptions;
+use crate::scrapers::progress::{ProgressReporter, ScrapeEvent};
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
+use futures_util::stream::{self, StreamExt};
+use reqwest::{Client, Url};
+use scraper::Html;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+// the app list comes back in one response, it's cut into pages of this many apps so --page-limit
+// and checkpoints work the same as for a feed
+pub const PAGE_SIZE: usize = 50;
+
+const DATE_FORMATS: [&str; 3] = ["%d %b, %Y", "%b %d, %Y", "%B %d, %Y"];
+
+#[derive(Deserialize, Debug)]
+struct AppDetails {
+    success: bool,
+    data: Option<App>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct App {
+    #[serde(rename = "type")]
+    kind: String,
+    name: String,
+    steam_appid: u64,
+    short_description: String,
+    is_free: bool,
+    price_overview: Option<PriceOverview>,
+    developers: Vec<String>,
+    platforms: Platforms,
+    categories: Vec<Described>,
+    genres: Vec<Described>,
+    release_date: ReleaseDate,
+    header_image: String,
+    screenshots: Vec<Screenshot>,
+    supported_languages: String,
+    website: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PriceOverview {
+    currency: String,
+    // in cents
+    #[serde(rename = "final")]
+    final_price: i64,
+    final_formatted: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct Platforms {
+    windows: bool,
+    mac: bool,
+    linux: bool,
+}
+
+// category ids are numbers and genre ids strings
+#[derive(Deserialize, Debug)]
+struct Described {
+    id: Value,
+    description: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct ReleaseDate {
+    coming_soon: bool,
+    date: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct Screenshot {
+    path_full: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct Reviews {
+    query_summary: ReviewSummary,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct ReviewSummary {
+    total_positive: i32,
+    total_reviews: i32,
+}
+
+// --url is an app list, either the GetAppList api or a storefront listing such as
+// featuredcategories; app details and reviews come from the store next to it
+pub async fn scrape_steam(
+    url: String,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
+) -> Result<Vec<ItchData>> {
+    let client = Client::new();
+    let max_retries = options.max_retries;
+    let limiter = options.limiter.as_ref();
+    let bandwidth = options.bandwidth.as_ref();
+
+    reporter.report(ScrapeEvent::Started {
+        pages: options.pages.len() as u64,
+    });
+
+    let list = fetch_url(&client, &url, max_retries, limiter, bandwidth, reporter).await?;
+    let list: Value = serde_json::from_str(&list)
+        .with_context(|| format!("{:?} is not a Steam app list", url))?;
+    let ids = app_ids(&list);
+    let store = store_url(&url)?;
+
+    let mut output = Vec::new();
+    for &page in &options.pages {
+        let start = (page.max(1) - 1) as usize * PAGE_SIZE;
+        let page_ids: Vec<u64> = ids.iter().skip(start).take(PAGE_SIZE).copied().collect();
+        let mut apps = stream::iter(page_ids)
+            .map(|id| {
+                let (client, store) = (&client, &store);
+                async move { (id, scrape_app(client, store, id, options, reporter).await) }
+            })
+            .buffered(options.concurrency.fetch);
+
+        while let Some((id, result)) = apps.next().await {
+            match result {
+                // dlc, soundtracks, videos and delisted apps
+                Ok(None) => {}
+                Ok(Some(record)) => {
+                    if options.window.is_bounded()
+                        && !options
+                            .window
+                            .contains(record_date(&record, options.window.field))
+                    {
+                        continue;
+                    }
+                    reporter.report(ScrapeEvent::Item {
+                        title: record.plain_title.clone(),
+                        link: record.link.clone(),
+                    });
+                    output.push(record);
+                }
+                Err(err) => {
+                    let message = format!("Failed to scrape Steam app {}: {:#}", id, err);
+                    reporter.report(ScrapeEvent::Error {
+                        message: message.clone(),
+                        url: app_link(id),
+                        diagnostic: None,
+                    });
+                    if options.fail_on_error {
+                        return Err(anyhow!(message));
+                    }
+                }
+            }
+        }
+
+        if let Some(checkpoint) = &options.checkpoint {
+            checkpoint.save(page + 1, &output)?;
+        }
+        reporter.report(ScrapeEvent::PageFinished { page });
+    }
+
+    report_downloaded(bandwidth, reporter);
+    reporter.report(ScrapeEvent::Finished);
+    Ok(output)
+}
+
+async fn scrape_app(
+    client: &Client,
+    store: &Url,
+    id: u64,
+    options: &ScrapeOptions,
+    reporter: &dyn ProgressReporter,
+) -> Result<Option<ItchData>> {
+    let max_retries = options.max_retries;
+    let limiter = options.limiter.as_ref();
+    let bandwidth = options.bandwidth.as_ref();
+
+    let mut details_url = store.join("api/appdetails")?;
+    details_url
+        .query_pairs_mut()
+        .append_pair("appids", &id.to_string())
+        .append_pair("cc", "us")
+        .append_pair("l", "english");
+    let details = fetch_url(
+        client,
+        details_url.as_str(),
+        max_retries,
+        limiter,
+        bandwidth,
+        reporter,
+    )
+    .await?;
+    let mut details: HashMap<String, AppDetails> = serde_json::from_str(&details)?;
+    let app = match details.remove(&id.to_string()) {
+        Some(AppDetails {
+            success: true,
+            data: Some(app),
+        }) if app.kind == "game" => app,
+        _ => return Ok(None),
+    };
+
+    let mut reviews_url = store.join(&format!("appreviews/{}", id))?;
+    reviews_url
+        .query_pairs_mut()
+        .append_pair("json", "1")
+        .append_pair("language", "all")
+        .append_pair("purchase_type", "all")
+        .append_pair("num_per_page", "0");
+    let reviews = fetch_url(
+        client,
+        reviews_url.as_str(),
+        max_retries,
+        limiter,
+        bandwidth,
+        reporter,
+    )
+    .await?;
+    let reviews: Reviews = serde_json::from_str(&reviews)?;
+
+    Ok(Some(into_record(app, reviews.query_summary)))
+}
+
+fn into_record(app: App, reviews: ReviewSummary) -> ItchData {
+    let (price, price_amount, price_currency) = match (&app.price_overview, app.is_free) {
+        (_, true) => ("Free".to_string(), Some(0.0), String::new()),
+        (Some(overview), false) => (
+            overview.final_formatted.clone(),
+            Some(overview.final_price as f64 / 100.0),
+            overview.currency.clone(),
+        ),
+        (None, false) => Default::default(),
+    };
+    // midnight UTC, so --since and --until compare against the release day
+    let pub_date = release_day(&app.release_date.date)
+        .map(|day| day.format("%Y-%m-%d 00:00:00").to_string())
+        .unwrap_or_default();
+
+    ItchData {
+        title: app.name.clone(),
+        plain_title: app.name,
+        link: app_link(app.steam_appid),
+        release_date: app.release_date.date,
+        pub_date,
+        price_info: (!price.is_empty()).then_some(Price {
+            amount: price_amount,
+            currency: price_currency,
+            free: app.is_free,
+            pwyw: false,
+        }),
+        price,
+        description: app.short_description,
+        // the review summary as a 0-5 score, the same scale as itch star ratings
+        rating: (reviews.total_reviews > 0).then(|| ItchRating {
+            score: (reviews.total_positive as f32 / reviews.total_reviews as f32 * 500.0).round()
+                / 100.0,
+            count: reviews.total_reviews,
+        }),
+        authors: app
+            .developers
+            .into_iter()
+            .map(|name| Author {
+                url: search_url("developer", &name),
+                name,
+            })
+            .collect(),
+        genres: app
+            .genres
+            .into_iter()
+            .map(|genre| Genre {
+                url: format!(
+                    "https://store.steampowered.com/genre/{}/",
+                    genre.description.replace(' ', "%20")
+                ),
+                name: genre.description,
+            })
+            .collect(),
+        // appdetails has no user tags, its categories ("Single-player", "Steam Achievements")
+        // are the closest thing
+        tags: app
+            .categories
+            .into_iter()
+            .map(|category| {
+                let id = match category.id {
+                    Value::String(id) => id,
+                    id => id.to_string(),
+                };
+                Tag {
+                    url: search_url("category2", &id),
+                    slug: id,
+                    name: category.description,
+                }
+            })
+            .collect(),
+        languages: languages(&app.supported_languages),
+        links: app
+            .website
+            .filter(|website| !website.is_empty())
+            .map(|url| Link {
+                name: "Website".to_string(),
+                url,
+            })
+            .into_iter()
+            .collect(),
+        status: match app.release_date.coming_soon {
+            true => "Coming soon".to_string(),
+            false => "Released".to_string(),
+        },
+        platforms: [
+            (app.platforms.windows, "Windows"),
+            (app.platforms.mac, "macOS"),
+            (app.platforms.linux, "Linux"),
+        ]
+        .into_iter()
+        .filter(|(supported, _)| *supported)
+        .map(|(_, platform)| platform.to_string())
+        .collect(),
+        cover_image: app.header_image,
+        screenshots: app
+            .screenshots
+            .into_iter()
+            .map(|screenshot| screenshot.path_full)
+            .collect(),
+        ..ItchData::default()
+    }
+}
+
+// GetAppList nests {appid} objects under applist.apps, storefront listings nest {id} objects
+// under every category's items
+fn app_ids(list: &Value) -> Vec<u64> {
+    let mut ids = Vec::new();
+    collect_app_ids(list, &mut ids);
+    let mut seen = HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+    ids
+}
+
+fn collect_app_ids(value: &Value, ids: &mut Vec<u64>) {
+    match value {
+        Value::Object(fields) => {
+            if let Some(id) = fields.get("appid").and_then(Value::as_u64) {
+                ids.push(id);
+                return;
+            }
+            for (name, field) in fields {
+                match (name.as_str(), field) {
+                    ("items", Value::Array(items)) => ids.extend(
+                        items
+                            .iter()
+                            .filter_map(|item| item.get("id").and_then(Value::as_u64)),
+                    ),
+                    _ => collect_app_ids(field, ids),
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_app_ids(value, ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+// the app list may live on api.steampowered.com, the store endpoints never do
+fn store_url(url: &str) -> Result<Url> {
+    let mut store = Url::parse(url).with_context(|| format!("Invalid url {:?}", url))?;
+    if store.host_str() == Some("api.steampowered.com") {
+        store
+            .set_host(Some("store.steampowered.com"))
+            .map_err(|err| anyhow!("{}", err))?;
+    }
+    store.set_path("/");
+    store.set_query(None);
+    Ok(store)
+}
+
+fn app_link(id: u64) -> String {
+    format!("https://store.steampowered.com/app/{}/", id)
+}
+
+fn search_url(param: &str, value: &str) -> String {
+    Url::parse_with_params("https://store.steampowered.com/search/", [(param, value)])
+        .map(|url| url.to_string())
+        .unwrap_or_default()
+}
+
+fn release_day(date: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date.trim(), format).ok())
+}
+
+// e.g. "English<strong>*</strong>, French<br><strong>*</strong>languages with full audio support"
+fn languages(supported: &str) -> Vec<String> {
+    let listed = supported.split("<br>").next().unwrap_or_default();
+    Html::parse_fragment(listed)
+        .root_element()
+        .text()
+        .collect::<String>()
+        .split(',')
+        .map(|language| language.trim().trim_end_matches('*').trim().to_string())
+        .filter(|language| !language.is_empty())
+        .collect()
+}
@@ -0,0 +1,56 @@
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, Context, Result};
+use rhai::{Dynamic, Engine, Scope, AST};
+use std::fs;
+use std::path::Path;
+
+const TRANSFORM_FN: &str = "transform";
+
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+}
+
+impl Script {
+    pub fn from_file(path: &Path) -> Result<Script> {
+        let source =
+            fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let engine = Engine::new();
+        let ast = engine
+            .compile(source)
+            .map_err(|err| anyhow!("Invalid script {:?}: {}", path, err))?;
+
+        if !ast
+            .iter_functions()
+            .any(|function| function.name == TRANSFORM_FN && function.params.len() == 1)
+        {
+            return Err(anyhow!(
+                "Script {:?} does not define a transform(record) function",
+                path
+            ));
+        }
+
+        Ok(Script { engine, ast })
+    }
+
+    // transform(record) returns the changed record, or () to drop it from the output
+    pub fn apply(&self, record: ItchData) -> Result<Option<ItchData>> {
+        let link = record.link.clone();
+        let input = rhai::serde::to_dynamic(record)
+            .map_err(|err| anyhow!("Failed to pass {:?} to the script: {}", link, err))?;
+
+        let output: Dynamic = self
+            .engine
+            .call_fn(&mut Scope::new(), &self.ast, TRANSFORM_FN, (input,))
+            .map_err(|err| anyhow!("Script failed on {:?}: {}", link, err))?;
+        if output.is_unit() {
+            return Ok(None);
+        }
+
+        // going through JSON lets rhai's i64/f64 numbers narrow into the record's field types
+        serde_json::to_value(&output)
+            .and_then(serde_json::from_value)
+            .map(Some)
+            .map_err(|err| anyhow!("Script returned an invalid record for {:?}: {}", link, err))
+    }
+}
@@ -0,0 +1,55 @@
+use anyhow::{anyhow, Context, Result};
+use keyring::Entry;
+
+const SERVICE: &str = "game-data-scraper";
+
+// config strings of the form "keyring:<name>" are swapped for the secret stored under that name
+pub const REFERENCE_PREFIX: &str = "keyring:";
+
+pub fn store(name: &str, secret: &str) -> Result<()> {
+    Entry::new(SERVICE, name)?
+        .set_password(secret)
+        .with_context(|| format!("Failed to store {:?} in the OS keyring", name))
+}
+
+pub fn delete(name: &str) -> Result<()> {
+    Entry::new(SERVICE, name)?
+        .delete_credential()
+        .with_context(|| format!("Failed to remove {:?} from the OS keyring", name))
+}
+
+pub fn lookup(name: &str) -> Result<String> {
+    Entry::new(SERVICE, name)?
+        .get_password()
+        .map_err(|err| match err {
+            keyring::Error::NoEntry => anyhow!(
+                "No secret named {:?} in the OS keyring, store one with `auth login {}`",
+                name,
+                name
+            ),
+            err => anyhow!(err).context(format!("Failed to read {:?} from the OS keyring", name)),
+        })
+}
+
+pub fn resolve(value: &mut toml::Value) -> Result<()> {
+    match value {
+        toml::Value::String(text) => {
+            if let Some(name) = text.strip_prefix(REFERENCE_PREFIX) {
+                *text = lookup(name)?;
+            }
+        }
+        toml::Value::Array(values) => {
+            for value in values {
+                resolve(value)?;
+            }
+        }
+        toml::Value::Table(table) => {
+            for (_, value) in table.iter_mut() {
+                resolve(value)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
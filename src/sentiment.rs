@@ -0,0 +1,136 @@
+use crate::scrapers::itch_rss_scraper::ItchData;
+
+// AFINN-style word valences, trimmed to vocabulary common in game reviews and comments
+const LEXICON: &[(&str, f32)] = &[
+    ("addictive", 2.0),
+    ("amazing", 4.0),
+    ("awesome", 4.0),
+    ("beautiful", 3.0),
+    ("best", 3.0),
+    ("brilliant", 4.0),
+    ("charming", 3.0),
+    ("clever", 2.0),
+    ("cool", 1.0),
+    ("cute", 2.0),
+    ("enjoy", 2.0),
+    ("enjoyed", 2.0),
+    ("excellent", 3.0),
+    ("fantastic", 4.0),
+    ("favorite", 2.0),
+    ("favourite", 2.0),
+    ("fun", 4.0),
+    ("good", 3.0),
+    ("gorgeous", 3.0),
+    ("great", 3.0),
+    ("impressive", 3.0),
+    ("incredible", 4.0),
+    ("interesting", 2.0),
+    ("love", 3.0),
+    ("loved", 3.0),
+    ("lovely", 3.0),
+    ("masterpiece", 4.0),
+    ("nice", 3.0),
+    ("perfect", 3.0),
+    ("polished", 2.0),
+    ("recommend", 2.0),
+    ("relaxing", 2.0),
+    ("satisfying", 2.0),
+    ("smooth", 2.0),
+    ("solid", 2.0),
+    ("unique", 2.0),
+    ("well", 1.0),
+    ("wholesome", 3.0),
+    ("wonderful", 4.0),
+    ("wow", 4.0),
+    ("annoying", -2.0),
+    ("awful", -3.0),
+    ("bad", -3.0),
+    ("boring", -3.0),
+    ("broken", -3.0),
+    ("bug", -2.0),
+    ("buggy", -3.0),
+    ("bugs", -2.0),
+    ("clunky", -2.0),
+    ("confusing", -2.0),
+    ("crash", -3.0),
+    ("crashed", -3.0),
+    ("crashes", -3.0),
+    ("disappointed", -2.0),
+    ("disappointing", -2.0),
+    ("frustrating", -2.0),
+    ("glitch", -2.0),
+    ("glitchy", -2.0),
+    ("hate", -3.0),
+    ("horrible", -3.0),
+    ("lag", -2.0),
+    ("laggy", -2.0),
+    ("mediocre", -2.0),
+    ("meh", -1.0),
+    ("pointless", -2.0),
+    ("poor", -2.0),
+    ("refund", -2.0),
+    ("repetitive", -2.0),
+    ("scam", -4.0),
+    ("short", -1.0),
+    ("slow", -1.0),
+    ("stuck", -2.0),
+    ("tedious", -2.0),
+    ("terrible", -3.0),
+    ("ugly", -3.0),
+    ("unfair", -2.0),
+    ("unplayable", -4.0),
+    ("useless", -2.0),
+    ("waste", -3.0),
+    ("worse", -3.0),
+    ("worst", -3.0),
+];
+
+const NEGATIONS: &[&str] = &[
+    "not", "no", "never", "isnt", "wasnt", "dont", "doesnt", "didnt", "cant", "cannot", "wont",
+    "aint", "hardly",
+];
+
+const NEGATION_WINDOW: usize = 3;
+
+// squashes the raw valence sum into -1..1, the same normalization VADER uses
+const NORMALIZATION_ALPHA: f32 = 15.0;
+
+pub fn score_text(text: &str) -> f32 {
+    let mut total = 0.0;
+    let mut negated_for = 0;
+
+    for raw in text.split_whitespace() {
+        let word: String = raw
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+
+        if NEGATIONS.contains(&word.as_str()) {
+            negated_for = NEGATION_WINDOW;
+            continue;
+        }
+
+        if let Some((_, valence)) = LEXICON.iter().find(|(w, _)| *w == word) {
+            total += if negated_for > 0 { -valence } else { *valence };
+        }
+        negated_for = negated_for.saturating_sub(1);
+
+        // negation doesn't carry across clause boundaries
+        if raw.ends_with([',', '.', '!', '?', ';', ':']) {
+            negated_for = 0;
+        }
+    }
+
+    total / (total * total + NORMALIZATION_ALPHA).sqrt()
+}
+
+pub fn score_record(record: &mut ItchData) {
+    for comment in &mut record.comments {
+        comment.sentiment = Some(score_text(&comment.body));
+    }
+
+    let scores: Vec<f32> = record.comments.iter().filter_map(|c| c.sentiment).collect();
+    record.sentiment =
+        (!scores.is_empty()).then(|| scores.iter().sum::<f32>() / scores.len() as f32);
+}
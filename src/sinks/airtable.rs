@@ -0,0 +1,138 @@
+use super::{path_template, secret};
+use crate::columnar;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, bail, Result};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use reqwest::{Client, Url};
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+
+const AIRTABLE_API: &str = "https://api.airtable.com/v0";
+
+// Airtable takes 10 records per write and 5 requests a second per base
+const MAX_BATCH_RECORDS: usize = 10;
+const REQUEST_INTERVAL: Duration = Duration::from_millis(200);
+
+// airtable://<base id>/<table>[?fields=<column>:<field>,...&api_key=<key>], the key defaults to
+// AIRTABLE_API_KEY
+#[derive(Debug)]
+pub struct AirtableSink {
+    base: String,
+    table: String,
+    api_key: String,
+    // (column index, Airtable field name)
+    fields: Vec<(usize, String)>,
+    merge_on: String,
+}
+
+impl AirtableSink {
+    pub fn new(url: &Url) -> Result<AirtableSink> {
+        let base = url.host_str().ok_or_else(|| {
+            anyhow!("Airtable sink URI needs a base id, e.g. airtable://appXXXXXXXXXXXXXX/Games")
+        })?;
+        let table = path_template(url);
+        if table.is_empty() {
+            bail!(
+                "Airtable sink URI needs a table name, e.g. airtable://{}/Games",
+                base
+            );
+        }
+
+        let header = columnar::header();
+        let fields = match url.query_pairs().find(|(key, _)| key == "fields") {
+            Some((_, mapping)) => mapping
+                .split(',')
+                .map(|pair| {
+                    let (column, field) = pair.split_once(':').unwrap_or((pair, pair));
+                    let index = header
+                        .iter()
+                        .position(|name| *name == column.trim())
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "Unknown column {:?} in Airtable field mapping, expected one of {}",
+                                column,
+                                header.join(", ")
+                            )
+                        })?;
+                    Ok((index, field.trim().to_string()))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => header
+                .iter()
+                .enumerate()
+                .map(|(index, name)| (index, name.to_string()))
+                .collect(),
+        };
+
+        // the link is the feed's guid, so re-sent games update their row instead of duplicating it
+        let link = header.iter().position(|name| *name == "link");
+        let merge_on = fields
+            .iter()
+            .find(|(index, _)| Some(*index) == link)
+            .map(|(_, field)| field.clone())
+            .ok_or_else(|| anyhow!("Airtable field mapping has to include link to upsert on"))?;
+
+        Ok(AirtableSink {
+            base: base.to_string(),
+            table,
+            api_key: secret(url, "api_key", "AIRTABLE_API_KEY")?,
+            fields,
+            merge_on,
+        })
+    }
+
+    pub async fn send(&self, records: &[ItchData]) -> Result<()> {
+        let client = Client::new();
+        let url = format!(
+            "{}/{}/{}",
+            AIRTABLE_API,
+            self.base,
+            utf8_percent_encode(&self.table, NON_ALPHANUMERIC)
+        );
+
+        for (i, batch) in records.chunks(MAX_BATCH_RECORDS).enumerate() {
+            if i > 0 {
+                tokio::time::sleep(REQUEST_INTERVAL).await;
+            }
+
+            let rows: Vec<Value> = batch
+                .iter()
+                .map(|record| {
+                    let row = columnar::typed_row(record);
+                    let fields: Map<String, Value> = self
+                        .fields
+                        .iter()
+                        .map(|(index, field)| (field.clone(), row[*index].clone()))
+                        .collect();
+                    json!({ "fields": fields })
+                })
+                .collect();
+
+            // typecast lets Airtable add new options to single and multiple select fields
+            let response = client
+                .patch(&url)
+                .bearer_auth(&self.api_key)
+                .json(&json!({
+                    "performUpsert": { "fieldsToMergeOn": [self.merge_on] },
+                    "typecast": true,
+                    "records": rows,
+                }))
+                .send()
+                .await?;
+            let status = response.status();
+            if !status.is_success() {
+                let body: Value = response.json().await.unwrap_or_default();
+                bail!(
+                    "Airtable upsert failed with {}: {}",
+                    status,
+                    body["error"]["message"]
+                        .as_str()
+                        .or(body["error"].as_str())
+                        .unwrap_or_default()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
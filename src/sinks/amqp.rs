@@ -0,0 +1,78 @@
+use super::render_template;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, bail, Context, Result};
+use lapin::options::{BasicPublishOptions, ConfirmSelectOptions};
+use lapin::{BasicProperties, Connection, ConnectionProperties};
+use reqwest::Url;
+
+const DEFAULT_ROUTING_KEY: &str = "games.new";
+// persistent, so queued records survive a broker restart
+const DELIVERY_MODE_PERSISTENT: u8 = 2;
+
+// amqp[s]://[user:pass@]host[:port][/vhost][?exchange=<name>&routing_key=<template>]
+#[derive(Debug)]
+pub struct AmqpSink {
+    uri: String,
+    exchange: String,
+    routing_key: String,
+}
+
+impl AmqpSink {
+    pub fn new(url: &Url) -> Result<AmqpSink> {
+        url.host_str()
+            .ok_or_else(|| anyhow!("AMQP sink URI needs a broker host"))?;
+        let query = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+
+        // lapin reads the credentials, vhost and connection settings like ?heartbeat= from the
+        // uri and ignores the parameters meant for the sink
+        Ok(AmqpSink {
+            uri: url.to_string(),
+            // the default exchange routes straight to the queue named by the routing key
+            exchange: query("exchange").unwrap_or_default(),
+            routing_key: query("routing_key").unwrap_or_else(|| DEFAULT_ROUTING_KEY.to_string()),
+        })
+    }
+
+    pub async fn send(&self, records: &[ItchData]) -> Result<()> {
+        let connection = Connection::connect(&self.uri, ConnectionProperties::default())
+            .await
+            .context("Failed to connect to the AMQP broker")?;
+        let channel = connection.create_channel().await?;
+        channel
+            .confirm_select(ConfirmSelectOptions::default())
+            .await?;
+
+        // publishes are pipelined and the confirms awaited afterwards
+        let mut confirms = Vec::with_capacity(records.len());
+        for record in records {
+            let properties = BasicProperties::default()
+                .with_content_type("application/json".into())
+                .with_delivery_mode(DELIVERY_MODE_PERSISTENT)
+                .with_message_id(record.link.as_str().into());
+            let routing_key = render_template(&self.routing_key, record);
+            confirms.push(
+                channel
+                    .basic_publish(
+                        &self.exchange,
+                        &routing_key,
+                        BasicPublishOptions::default(),
+                        &serde_json::to_vec(record)?,
+                        properties,
+                    )
+                    .await?,
+            );
+        }
+        for (record, confirm) in records.iter().zip(confirms) {
+            if confirm.await?.is_nack() {
+                bail!("The broker refused {}", record.link);
+            }
+        }
+
+        connection.close(200, "OK").await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use super::path_template;
+use crate::columnar;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use reqwest::{Client, Url};
+use serde_json::{Map, Value};
+
+const DEFAULT_BATCH_SIZE: usize = 10_000;
+
+// clickhouse://[user:pass@]host[:port]/<database>.<table>[?secure=true&batch_size=<rows>], rows
+// carry a scraped_at timestamp so repeated scrapes build up a time series
+#[derive(Debug)]
+pub struct ClickHouseSink {
+    endpoint: Url,
+    user: Option<String>,
+    password: Option<String>,
+    table: String,
+    batch_size: usize,
+}
+
+impl ClickHouseSink {
+    pub fn new(url: &Url) -> Result<ClickHouseSink> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| anyhow!("ClickHouse sink URI needs a server host"))?;
+        let query = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+
+        let table = path_template(url);
+        let valid_name = |name: &str| {
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        };
+        if !valid_name(&table) {
+            bail!(
+                "ClickHouse sink URI needs a table such as clickhouse://{}/games.daily, got {:?}",
+                host,
+                table
+            );
+        }
+
+        let secure = query("secure").is_some_and(|secure| secure == "true");
+        let endpoint = Url::parse(&format!(
+            "{}://{}:{}/",
+            if secure { "https" } else { "http" },
+            host,
+            url.port().unwrap_or(if secure { 8443 } else { 8123 })
+        ))?;
+
+        Ok(ClickHouseSink {
+            endpoint,
+            user: Some(url.username().to_string()).filter(|user| !user.is_empty()),
+            password: url.password().map(str::to_string),
+            table,
+            batch_size: match query("batch_size") {
+                Some(size) => size
+                    .parse()
+                    .ok()
+                    .filter(|size| *size > 0)
+                    .with_context(|| format!("Invalid ClickHouse batch_size {:?}", size))?,
+                None => DEFAULT_BATCH_SIZE,
+            },
+        })
+    }
+
+    pub async fn send(&self, records: &[ItchData]) -> Result<()> {
+        let client = Client::new();
+        let header = columnar::header();
+        let scraped_at = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+        for batch in records.chunks(self.batch_size) {
+            let mut body = String::new();
+            for record in batch {
+                let mut row: Map<String, Value> = header
+                    .iter()
+                    .map(|name| name.to_string())
+                    .zip(columnar::typed_row(record))
+                    .collect();
+                row.insert("scraped_at".to_string(), scraped_at.clone().into());
+                body.push_str(&serde_json::to_string(&row)?);
+                body.push('\n');
+            }
+
+            // unknown fields are skipped so the table only needs the columns it cares about
+            let mut request = client
+                .post(self.endpoint.clone())
+                .query(&[
+                    (
+                        "query",
+                        format!("INSERT INTO {} FORMAT JSONEachRow", self.table).as_str(),
+                    ),
+                    ("input_format_skip_unknown_fields", "1"),
+                    ("date_time_input_format", "best_effort"),
+                ])
+                .body(body);
+            if let Some(user) = &self.user {
+                request = request.header("X-ClickHouse-User", user);
+            }
+            if let Some(password) = &self.password {
+                request = request.header("X-ClickHouse-Key", password);
+            }
+
+            let response = request.send().await?;
+            let status = response.status();
+            if !status.is_success() {
+                bail!(
+                    "ClickHouse insert into {} failed with {}: {}",
+                    self.table,
+                    status,
+                    response.text().await.unwrap_or_default().trim()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+use super::path_template;
+use crate::columnar;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, bail, Context, Result};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+const SHEETS_API: &str = "https://sheets.googleapis.com/v4/spreadsheets";
+const SCOPE: &str = "https://www.googleapis.com/auth/spreadsheets";
+
+// gsheets://<spreadsheet id>/<tab>[?mode=append|replace&credentials=<service account key file>],
+// the key file defaults to GOOGLE_APPLICATION_CREDENTIALS
+#[derive(Debug)]
+pub struct SheetsSink {
+    spreadsheet: String,
+    tab: String,
+    replace: bool,
+    credentials: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct ServiceAccount {
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+}
+
+#[derive(Serialize)]
+struct Claims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+}
+
+impl SheetsSink {
+    pub fn new(url: &Url) -> Result<SheetsSink> {
+        let spreadsheet = url
+            .host_str()
+            .ok_or_else(|| anyhow!("Google Sheets sink URI needs a spreadsheet id"))?;
+        let query = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+
+        let tab = path_template(url);
+        let credentials = query("credentials")
+            .or_else(|| env::var("GOOGLE_APPLICATION_CREDENTIALS").ok())
+            .ok_or_else(|| {
+                anyhow!("Google Sheets sink needs a service account key, pass ?credentials=<file> or set GOOGLE_APPLICATION_CREDENTIALS")
+            })?;
+
+        Ok(SheetsSink {
+            spreadsheet: spreadsheet.to_string(),
+            tab: match tab.as_str() {
+                "" => "Sheet1".to_string(),
+                _ => tab,
+            },
+            replace: match query("mode").as_deref() {
+                None | Some("append") => false,
+                Some("replace") => true,
+                Some(mode) => bail!(
+                    "Unknown Google Sheets sink mode {:?}, expected append or replace",
+                    mode
+                ),
+            },
+            credentials: PathBuf::from(credentials),
+        })
+    }
+
+    pub async fn send(&self, records: &[ItchData]) -> Result<()> {
+        let client = Client::new();
+        let token = self.access_token(&client).await?;
+        let authorized = |request: RequestBuilder| request.bearer_auth(&token);
+
+        let mut rows: Vec<Vec<String>> = records.iter().map(columnar::text_row).collect();
+        let header: Vec<String> = columnar::header().into_iter().map(str::to_string).collect();
+
+        if self.replace {
+            call(authorized(client.post(self.values_url("", ":clear")))).await?;
+            rows.insert(0, header);
+            call(
+                authorized(client.put(self.values_url("A1", "?valueInputOption=RAW")))
+                    .json(&json!({ "values": rows })),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        // a fresh tab gets the header row ahead of the first batch
+        let first_row = call(authorized(client.get(self.values_url("1:1", "")))).await?;
+        if first_row["values"].as_array().is_none_or(Vec::is_empty) {
+            rows.insert(0, header);
+        }
+        call(
+            authorized(client.post(self.values_url(
+                "A1",
+                ":append?valueInputOption=RAW&insertDataOption=INSERT_ROWS",
+            )))
+            .json(&json!({ "values": rows })),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    // tab names are quoted so ones with spaces or punctuation still parse as A1 ranges
+    fn values_url(&self, cells: &str, suffix: &str) -> String {
+        let mut range = format!("'{}'", self.tab.replace('\'', "''"));
+        if !cells.is_empty() {
+            range = format!("{}!{}", range, cells);
+        }
+
+        format!(
+            "{}/{}/values/{}{}",
+            SHEETS_API,
+            self.spreadsheet,
+            utf8_percent_encode(&range, NON_ALPHANUMERIC),
+            suffix
+        )
+    }
+
+    // exchanges a self-signed JWT for an OAuth access token, the service account flow
+    async fn access_token(&self, client: &Client) -> Result<String> {
+        let account: ServiceAccount = serde_json::from_str(
+            &fs::read_to_string(&self.credentials)
+                .with_context(|| format!("Failed to read {:?}", self.credentials))?,
+        )
+        .with_context(|| format!("{:?} is not a service account key file", self.credentials))?;
+
+        let now = chrono::Utc::now().timestamp();
+        let assertion = encode(
+            &Header::new(Algorithm::RS256),
+            &Claims {
+                iss: &account.client_email,
+                scope: SCOPE,
+                aud: &account.token_uri,
+                iat: now,
+                exp: now + 3600,
+            },
+            &EncodingKey::from_rsa_pem(account.private_key.as_bytes())?,
+        )?;
+
+        let response = call(client.post(&account.token_uri).form(&[
+            ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+            ("assertion", &assertion),
+        ]))
+        .await
+        .context("Failed to get a Google access token")?;
+
+        response["access_token"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Google token response has no access_token"))
+    }
+}
+
+async fn call(request: RequestBuilder) -> Result<Value> {
+    let response = request.send().await?;
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or_default();
+    if !status.is_success() {
+        let message = body["error"]["message"]
+            .as_str()
+            .or(body["error_description"].as_str())
+            .unwrap_or_default();
+        bail!("Request failed with {}: {}", status, message);
+    }
+
+    Ok(body)
+}
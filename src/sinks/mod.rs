@@ -0,0 +1,174 @@
+use crate::exit::Failure;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use crate::secrets;
+use anyhow::{anyhow, Context, Result};
+use percent_encoding::percent_decode_str;
+use reqwest::Url;
+use serde_json::Value;
+use std::env;
+
+pub mod airtable;
+#[cfg(feature = "amqp")]
+pub mod amqp;
+pub mod clickhouse;
+pub mod gsheets;
+pub mod nats;
+pub mod notion;
+pub mod postgrest;
+pub mod sqs;
+
+#[derive(Debug)]
+pub enum Sink {
+    Airtable(airtable::AirtableSink),
+    #[cfg(feature = "amqp")]
+    Amqp(amqp::AmqpSink),
+    ClickHouse(clickhouse::ClickHouseSink),
+    Sheets(gsheets::SheetsSink),
+    Nats(nats::NatsSink),
+    Notion(notion::NotionSink),
+    Postgrest(postgrest::PostgrestSink),
+    Sqs(sqs::SqsSink),
+}
+
+impl Sink {
+    pub fn parse(uri: &str) -> Result<Sink> {
+        let url = Url::parse(uri).with_context(|| format!("Invalid sink URI {:?}", uri))?;
+
+        match url.scheme() {
+            "airtable" => Ok(Sink::Airtable(airtable::AirtableSink::new(&url)?)),
+            #[cfg(feature = "amqp")]
+            "amqp" | "amqps" => Ok(Sink::Amqp(amqp::AmqpSink::new(&url)?)),
+            #[cfg(not(feature = "amqp"))]
+            "amqp" | "amqps" => Err(anyhow!(
+                "Built without the amqp feature, {:?} needs it",
+                uri
+            )),
+            "clickhouse" => Ok(Sink::ClickHouse(clickhouse::ClickHouseSink::new(&url)?)),
+            "gsheets" => Ok(Sink::Sheets(gsheets::SheetsSink::new(&url)?)),
+            "nats" | "tls" => Ok(Sink::Nats(nats::NatsSink::new(&url)?)),
+            "notion" => Ok(Sink::Notion(notion::NotionSink::new(&url)?)),
+            "postgrest" | "supabase" => Ok(Sink::Postgrest(postgrest::PostgrestSink::new(&url)?)),
+            "sqs" => Ok(Sink::Sqs(sqs::SqsSink::new(&url)?)),
+            scheme => Err(anyhow!("Unsupported sink {:?} in {:?}", scheme, uri)),
+        }
+    }
+
+    pub async fn send(&self, records: &[ItchData]) -> Result<()> {
+        match self {
+            Sink::Airtable(sink) => sink.send(records).await,
+            #[cfg(feature = "amqp")]
+            Sink::Amqp(sink) => sink.send(records).await,
+            Sink::ClickHouse(sink) => sink.send(records).await,
+            Sink::Sheets(sink) => sink.send(records).await,
+            Sink::Nats(sink) => sink.send(records).await,
+            Sink::Notion(sink) => sink.send(records).await,
+            Sink::Postgrest(sink) => sink.send(records).await,
+            Sink::Sqs(sink) => sink.send(records).await,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Sink::Airtable(_) => "Airtable",
+            #[cfg(feature = "amqp")]
+            Sink::Amqp(_) => "AMQP",
+            Sink::ClickHouse(_) => "ClickHouse",
+            Sink::Sheets(_) => "Google Sheets",
+            Sink::Nats(_) => "NATS",
+            Sink::Notion(_) => "Notion",
+            Sink::Postgrest(_) => "PostgREST",
+            Sink::Sqs(_) => "SQS",
+        }
+    }
+}
+
+pub fn parse_all(uris: &[String]) -> Result<Vec<Sink>> {
+    uris.iter()
+        .map(|uri| Sink::parse(uri).map_err(|err| Failure::Config(err).into()))
+        .collect()
+}
+
+pub async fn send_all(sinks: &[Sink], records: &[ItchData]) -> Result<()> {
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    for sink in sinks {
+        sink.send(records)
+            .await
+            .with_context(|| format!("Failed to send records to the {} sink", sink.name()))?;
+    }
+
+    Ok(())
+}
+
+// an API key from the URI's query, where "keyring:<name>" reads it from the OS keyring, or from
+// an environment variable
+pub fn secret(url: &Url, param: &str, var: &str) -> Result<String> {
+    let value = url
+        .query_pairs()
+        .find(|(key, _)| key == param)
+        .map(|(_, value)| value.into_owned());
+
+    match value {
+        Some(value) => match value.strip_prefix(secrets::REFERENCE_PREFIX) {
+            Some(name) => secrets::lookup(name),
+            None => Ok(value),
+        },
+        None => env::var(var)
+            .map_err(|_| anyhow!("Pass ?{}=<key> in the sink URI or set {}", param, var)),
+    }
+}
+
+// URL parsing escapes the braces of a template in the path
+pub fn path_template(url: &Url) -> String {
+    percent_decode_str(url.path().trim_start_matches('/'))
+        .decode_utf8_lossy()
+        .into_owned()
+}
+
+// fills {field} placeholders from the record's top-level fields, e.g. "games.{status}"; values
+// are squashed into a single subject/routing-key token
+pub fn render_template(template: &str, record: &ItchData) -> String {
+    let Ok(Value::Object(fields)) = serde_json::to_value(record) else {
+        return template.to_string();
+    };
+
+    let mut rendered = template.to_string();
+    for (name, value) in fields {
+        let placeholder = format!("{{{}}}", name);
+        if !rendered.contains(&placeholder) {
+            continue;
+        }
+        let value = match value {
+            Value::String(value) => value,
+            Value::Array(values) => values
+                .first()
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            value => value.to_string(),
+        };
+        rendered = rendered.replace(&placeholder, &token(&value));
+    }
+
+    rendered
+}
+
+fn token(value: &str) -> String {
+    let token: String = value
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            c if c.is_whitespace() => '-',
+            '.' | '*' | '>' | '#' => '-',
+            c => c,
+        })
+        .collect();
+
+    match token.is_empty() {
+        true => "none".to_string(),
+        false => token,
+    }
+}
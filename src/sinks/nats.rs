@@ -0,0 +1,106 @@
+use super::{path_template, render_template};
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, Result};
+use async_nats::HeaderMap;
+use reqwest::Url;
+
+const DEFAULT_SUBJECT: &str = "games.new";
+
+// nats://[user:pass@]host[:port]/<subject template>[?jetstream=false]
+#[derive(Debug)]
+pub struct NatsSink {
+    server: String,
+    subject: String,
+    jetstream: bool,
+}
+
+impl NatsSink {
+    pub fn new(url: &Url) -> Result<NatsSink> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| anyhow!("NATS sink URI needs a server host"))?;
+        let credentials = match (url.username(), url.password()) {
+            ("", _) => String::new(),
+            (user, Some(password)) => format!("{}:{}@", user, password),
+            (user, None) => format!("{}@", user),
+        };
+        let subject = path_template(url);
+
+        Ok(NatsSink {
+            server: format!(
+                "{}://{}{}:{}",
+                url.scheme(),
+                credentials,
+                host,
+                url.port().unwrap_or(4222)
+            ),
+            subject: match subject.as_str() {
+                "" => DEFAULT_SUBJECT.to_string(),
+                _ => subject,
+            },
+            jetstream: !url
+                .query_pairs()
+                .any(|(key, value)| key == "jetstream" && value == "false"),
+        })
+    }
+
+    pub async fn send(&self, records: &[ItchData]) -> Result<()> {
+        let client = async_nats::connect(&self.server).await?;
+
+        if !self.jetstream {
+            for record in records {
+                let subject = render_template(&self.subject, record);
+                client
+                    .publish(subject, serde_json::to_vec(record)?.into())
+                    .await?;
+            }
+            client.flush().await?;
+            return Ok(());
+        }
+
+        // publishes are pipelined and the acks awaited afterwards; the message id lets the
+        // stream drop a record that gets re-sent by a later run, but not one that was updated
+        let jetstream = async_nats::jetstream::new(client);
+        let mut acks = Vec::with_capacity(records.len());
+        for record in records {
+            let mut headers = HeaderMap::new();
+            headers.insert("Nats-Msg-Id", message_id(record).as_str());
+            let subject = render_template(&self.subject, record);
+            acks.push(
+                jetstream
+                    .publish_with_headers(subject, headers, serde_json::to_vec(record)?.into())
+                    .await?,
+            );
+        }
+        for ack in acks {
+            ack.await?;
+        }
+
+        Ok(())
+    }
+}
+
+fn message_id(record: &ItchData) -> String {
+    format!("{}@{}", record.link, record.update_date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_id_changes_when_the_game_is_updated() {
+        let record = ItchData {
+            link: "https://dev.itch.io/game".to_string(),
+            update_date: "2024-04-01 10:00:00".to_string(),
+            ..ItchData::default()
+        };
+        let updated = ItchData {
+            update_date: "2024-04-02 10:00:00".to_string(),
+            ..record.clone()
+        };
+
+        assert_eq!(message_id(&record), message_id(&record.clone()));
+        assert_ne!(message_id(&record), message_id(&updated));
+    }
+}
@@ -0,0 +1,141 @@
+use super::secret;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, bail, Result};
+use reqwest::{Client, RequestBuilder, Url};
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+
+const NOTION_API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+
+// Notion allows an average of 3 requests a second per integration
+const REQUEST_INTERVAL: Duration = Duration::from_millis(350);
+
+// notion://<database id>[?token=<key>&title=<property>&link=..&price=..&tags=..&rating=..], the
+// token defaults to NOTION_TOKEN; an empty property name leaves that value out
+#[derive(Debug)]
+pub struct NotionSink {
+    database: String,
+    token: String,
+    title: String,
+    link: String,
+    price: Option<String>,
+    tags: Option<String>,
+    rating: Option<String>,
+}
+
+impl NotionSink {
+    pub fn new(url: &Url) -> Result<NotionSink> {
+        let database = url
+            .host_str()
+            .ok_or_else(|| anyhow!("Notion sink URI needs a database id"))?;
+        let property = |name: &str, default: &str| {
+            let value = url
+                .query_pairs()
+                .find(|(key, _)| key == name)
+                .map_or(default.to_string(), |(_, value)| value.into_owned());
+            Some(value).filter(|value| !value.is_empty())
+        };
+
+        Ok(NotionSink {
+            database: database.to_string(),
+            token: secret(url, "token", "NOTION_TOKEN")?,
+            title: property("title", "Name")
+                .ok_or_else(|| anyhow!("Notion pages need a title property"))?,
+            // pages are matched on the link, so a re-sent game updates its page
+            link: property("link", "Link")
+                .ok_or_else(|| anyhow!("Notion sink needs a link property to match pages on"))?,
+            price: property("price", "Price"),
+            tags: property("tags", "Tags"),
+            rating: property("rating", "Rating"),
+        })
+    }
+
+    pub async fn send(&self, records: &[ItchData]) -> Result<()> {
+        let client = Client::new();
+
+        for record in records {
+            let existing = self
+                .call(
+                    client
+                        .post(format!("{}/databases/{}/query", NOTION_API, self.database))
+                        .json(&json!({
+                            "filter": { "property": self.link, "url": { "equals": record.link } },
+                            "page_size": 1,
+                        })),
+                )
+                .await?;
+            tokio::time::sleep(REQUEST_INTERVAL).await;
+
+            let properties = self.properties(record);
+            match existing["results"][0]["id"].as_str() {
+                Some(page) => {
+                    self.call(
+                        client
+                            .patch(format!("{}/pages/{}", NOTION_API, page))
+                            .json(&json!({ "properties": properties })),
+                    )
+                    .await?
+                }
+                None => {
+                    self.call(client.post(format!("{}/pages", NOTION_API)).json(&json!({
+                        "parent": { "database_id": self.database },
+                        "properties": properties,
+                    })))
+                    .await?
+                }
+            };
+            tokio::time::sleep(REQUEST_INTERVAL).await;
+        }
+
+        Ok(())
+    }
+
+    fn properties(&self, record: &ItchData) -> Map<String, Value> {
+        let mut properties = Map::new();
+        properties.insert(
+            self.title.clone(),
+            json!({ "title": [{ "text": { "content": record.plain_title } }] }),
+        );
+        properties.insert(self.link.clone(), json!({ "url": record.link }));
+        if let Some(price) = &self.price {
+            properties.insert(price.clone(), json!({ "number": record.price_amount() }));
+        }
+        // select option names can't contain commas
+        if let Some(tags) = &self.tags {
+            let options: Vec<Value> = record
+                .tag_names()
+                .iter()
+                .map(|tag| json!({ "name": tag.replace(',', " ") }))
+                .collect();
+            properties.insert(tags.clone(), json!({ "multi_select": options }));
+        }
+        if let Some(rating) = &self.rating {
+            properties.insert(
+                rating.clone(),
+                json!({ "number": record.rating.as_ref().map(|rating| rating.score) }),
+            );
+        }
+
+        properties
+    }
+
+    async fn call(&self, request: RequestBuilder) -> Result<Value> {
+        let response = request
+            .bearer_auth(&self.token)
+            .header("Notion-Version", NOTION_VERSION)
+            .send()
+            .await?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or_default();
+        if !status.is_success() {
+            bail!(
+                "Notion request failed with {}: {}",
+                status,
+                body["message"].as_str().unwrap_or_default()
+            );
+        }
+
+        Ok(body)
+    }
+}
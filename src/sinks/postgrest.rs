@@ -0,0 +1,124 @@
+use super::{path_template, secret};
+use crate::columnar;
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, bail, Result};
+use reqwest::{Client, Url};
+use serde_json::{Map, Value};
+
+const MAX_BATCH_ROWS: usize = 500;
+
+// postgrest://host[:port]/<path to table>[?secure=false&columns=<a,b,..>&key=<key>] or
+// supabase://<project ref>/<table>; the key defaults to SUPABASE_SERVICE_KEY and rows are upserted
+// on the table's link column
+#[derive(Debug)]
+pub struct PostgrestSink {
+    endpoint: Url,
+    key: String,
+    columns: Option<Vec<String>>,
+}
+
+impl PostgrestSink {
+    pub fn new(url: &Url) -> Result<PostgrestSink> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| anyhow!("PostgREST sink URI needs a host"))?;
+        let table = path_template(url);
+        if table.is_empty() {
+            bail!("PostgREST sink URI needs a table, e.g. supabase://<project ref>/games");
+        }
+        let query = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+
+        let endpoint = match url.scheme() {
+            "supabase" => format!("https://{}.supabase.co/rest/v1/{}", host, table),
+            _ => format!(
+                "{}://{}{}/{}",
+                match query("secure").as_deref() {
+                    Some("false") => "http",
+                    _ => "https",
+                },
+                host,
+                url.port()
+                    .map(|port| format!(":{}", port))
+                    .unwrap_or_default(),
+                table
+            ),
+        };
+
+        let header = columnar::header();
+        let columns = query("columns")
+            .map(|columns| {
+                columns
+                    .split(',')
+                    .map(|column| match header.contains(&column.trim()) {
+                        true => Ok(column.trim().to_string()),
+                        false => Err(anyhow!(
+                            "Unknown column {:?} for the PostgREST sink, expected one of {}",
+                            column,
+                            header.join(", ")
+                        )),
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+        if columns
+            .as_ref()
+            .is_some_and(|columns| !columns.iter().any(|column| column == "link"))
+        {
+            bail!("PostgREST sink columns have to include link to upsert on");
+        }
+
+        Ok(PostgrestSink {
+            endpoint: Url::parse(&endpoint)?,
+            key: secret(url, "key", "SUPABASE_SERVICE_KEY")?,
+            columns,
+        })
+    }
+
+    pub async fn send(&self, records: &[ItchData]) -> Result<()> {
+        let client = Client::new();
+        let header = columnar::header();
+
+        for batch in records.chunks(MAX_BATCH_ROWS) {
+            let rows: Vec<Map<String, Value>> = batch
+                .iter()
+                .map(|record| {
+                    header
+                        .iter()
+                        .zip(columnar::typed_row(record))
+                        .filter(|(name, _)| {
+                            self.columns
+                                .as_ref()
+                                .is_none_or(|columns| columns.iter().any(|column| column == *name))
+                        })
+                        .map(|(name, value)| (name.to_string(), value))
+                        .collect()
+                })
+                .collect();
+
+            let response = client
+                .post(self.endpoint.clone())
+                .query(&[("on_conflict", "link")])
+                .header("apikey", &self.key)
+                .bearer_auth(&self.key)
+                .header("Prefer", "resolution=merge-duplicates,return=minimal")
+                .json(&rows)
+                .send()
+                .await?;
+            let status = response.status();
+            if !status.is_success() {
+                let body: Value = response.json().await.unwrap_or_default();
+                bail!(
+                    "PostgREST upsert failed with {}: {}",
+                    status,
+                    body["message"].as_str().unwrap_or_default()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
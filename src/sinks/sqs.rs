@@ -0,0 +1,317 @@
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{anyhow, bail, Context, Result};
+use aws_config::{BehaviorVersion, Region};
+use aws_credential_types::provider::ProvideCredentials;
+use aws_credential_types::Credentials;
+use aws_sigv4::http_request::{sign, SignableBody, SignableRequest, SigningSettings};
+use aws_sigv4::sign::v4;
+use reqwest::{Client, Url};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::time::SystemTime;
+
+// SendMessageBatch takes at most 10 messages and 256 KiB of message bodies per call
+const MAX_BATCH_MESSAGES: usize = 10;
+const MAX_BATCH_BYTES: usize = 262_144;
+// a record over the limit is split into parts, leaving room for the attributes that say how to
+// put it back together since those count towards the limit too
+const MAX_PART_BYTES: usize = MAX_BATCH_BYTES - 1024;
+
+// sqs://<region>/<account id>/<queue name>[?endpoint=<url>], credentials come from the default
+// AWS chain: environment variables, profiles, SSO, or the instance or container role
+#[derive(Debug)]
+pub struct SqsSink {
+    region: String,
+    endpoint: Url,
+    queue_url: String,
+    fifo: bool,
+}
+
+// one SendMessageBatch entry, a whole record or one part of a split one
+struct Message<'a> {
+    record: &'a ItchData,
+    body: String,
+    dedup_id: String,
+    part: Option<(usize, usize)>,
+}
+
+impl SqsSink {
+    pub fn new(url: &Url) -> Result<SqsSink> {
+        let region = url.host_str().ok_or_else(|| {
+            anyhow!("SQS sink URI needs a region, e.g. sqs://us-east-1/<account id>/<queue name>")
+        })?;
+        let Some((account, queue)) = url.path().trim_start_matches('/').split_once('/') else {
+            bail!("SQS sink URI needs an account id and queue name, e.g. sqs://us-east-1/123456789012/new-games");
+        };
+
+        // e.g. ?endpoint=http://localhost:4566 for LocalStack
+        let endpoint = match url.query_pairs().find(|(key, _)| key == "endpoint") {
+            Some((_, endpoint)) => Url::parse(&endpoint)
+                .with_context(|| format!("Invalid SQS endpoint {:?}", endpoint))?,
+            None => Url::parse(&format!("https://sqs.{}.amazonaws.com/", region))?,
+        };
+
+        Ok(SqsSink {
+            region: region.to_string(),
+            queue_url: endpoint
+                .join(&format!("{}/{}", account, queue))?
+                .to_string(),
+            endpoint,
+            fifo: queue.ends_with(".fifo"),
+        })
+    }
+
+    pub async fn send(&self, records: &[ItchData]) -> Result<()> {
+        let config = aws_config::defaults(BehaviorVersion::latest())
+            .region(Region::new(self.region.clone()))
+            .load()
+            .await;
+        let credentials = config
+            .credentials_provider()
+            .ok_or_else(|| anyhow!("No AWS credentials found for the SQS sink"))?
+            .provide_credentials()
+            .await
+            .context("Failed to load AWS credentials for the SQS sink")?;
+        let client = Client::new();
+
+        for batch in self.batches(records)? {
+            let entries: Vec<Value> = batch
+                .iter()
+                .enumerate()
+                .map(|(id, message)| {
+                    let mut entry = json!({ "Id": id.to_string(), "MessageBody": message.body });
+                    // consumers join the parts sharing a record attribute in part order
+                    if let Some((part, parts)) = message.part {
+                        entry["MessageAttributes"] = json!({
+                            "record": { "DataType": "String", "StringValue": message.record.link },
+                            "part": { "DataType": "Number", "StringValue": part.to_string() },
+                            "parts": { "DataType": "Number", "StringValue": parts.to_string() },
+                        });
+                    }
+                    // FIFO queues need a group and drop a record re-sent within the dedup window,
+                    // an update changes the id so it still goes through
+                    if self.fifo {
+                        entry["MessageGroupId"] = json!("games");
+                        entry["MessageDeduplicationId"] = json!(message.dedup_id);
+                    }
+                    entry
+                })
+                .collect();
+            let payload = serde_json::to_vec(&json!({
+                "QueueUrl": self.queue_url,
+                "Entries": entries,
+            }))?;
+
+            let response: Value = self
+                .request(&client, &credentials, "SendMessageBatch", payload)
+                .await?;
+            if let Some(failed) = response["Failed"]
+                .as_array()
+                .filter(|failed| !failed.is_empty())
+            {
+                let reasons: Vec<String> = failed
+                    .iter()
+                    .map(|entry| {
+                        let id = entry["Id"].as_str().and_then(|id| id.parse::<usize>().ok());
+                        format!(
+                            "{}: {}",
+                            id.map_or("?", |id| batch[id].record.link.as_str()),
+                            entry["Message"].as_str().unwrap_or("unknown error")
+                        )
+                    })
+                    .collect();
+                bail!(
+                    "SQS rejected {} message(s): {}",
+                    failed.len(),
+                    reasons.join("; ")
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    // packs records into batches by message count and total body size
+    fn batches<'a>(&self, records: &'a [ItchData]) -> Result<Vec<Vec<Message<'a>>>> {
+        let mut batches = Vec::new();
+        let mut batch: Vec<Message> = Vec::new();
+        let mut batch_bytes = 0;
+
+        for record in records {
+            for message in messages(record)? {
+                if batch.len() == MAX_BATCH_MESSAGES
+                    || batch_bytes + message.body.len() > MAX_PART_BYTES
+                {
+                    batches.push(std::mem::take(&mut batch));
+                    batch_bytes = 0;
+                }
+                batch_bytes += message.body.len();
+                batch.push(message);
+            }
+        }
+        if !batch.is_empty() {
+            batches.push(batch);
+        }
+
+        Ok(batches)
+    }
+
+    async fn request(
+        &self,
+        client: &Client,
+        credentials: &Credentials,
+        action: &str,
+        payload: Vec<u8>,
+    ) -> Result<Value> {
+        let target = format!("AmazonSQS.{}", action);
+        let headers = [
+            ("content-type", "application/x-amz-json-1.0"),
+            ("x-amz-target", target.as_str()),
+        ];
+        let identity = credentials.clone().into();
+        let params = v4::SigningParams::builder()
+            .identity(&identity)
+            .region(&self.region)
+            .name("sqs")
+            .time(SystemTime::now())
+            .settings(SigningSettings::default())
+            .build()?
+            .into();
+        let signable = SignableRequest::new(
+            "POST",
+            self.endpoint.as_str(),
+            headers.into_iter(),
+            SignableBody::Bytes(&payload),
+        )?;
+        let (instructions, _) = sign(signable, &params)?.into_parts();
+
+        let mut request = client.post(self.endpoint.clone());
+        for (name, value) in headers.into_iter().chain(instructions.headers()) {
+            request = request.header(name, value);
+        }
+
+        let response = request.body(payload).send().await?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or_default();
+        if !status.is_success() {
+            bail!(
+                "SQS {} failed with {}: {}",
+                action,
+                status,
+                body["message"]
+                    .as_str()
+                    .or(body["Message"].as_str())
+                    .unwrap_or_default()
+            );
+        }
+
+        Ok(body)
+    }
+}
+
+// the dedup id covers the update date, so a re-scraped record is dropped but an updated one isn't
+fn messages(record: &ItchData) -> Result<Vec<Message<'_>>> {
+    let body = serde_json::to_string(record)?;
+    let dedup_id = hex::encode(Sha256::digest(
+        format!("{}\n{}", record.link, record.update_date).as_bytes(),
+    ));
+    if body.len() <= MAX_PART_BYTES {
+        return Ok(vec![Message {
+            record,
+            body,
+            dedup_id,
+            part: None,
+        }]);
+    }
+
+    let chunks = split_body(&body, MAX_PART_BYTES);
+    let parts = chunks.len();
+    Ok(chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, chunk)| Message {
+            record,
+            body: chunk.to_string(),
+            dedup_id: hex::encode(Sha256::digest(
+                format!("{}\n{}", dedup_id, index + 1).as_bytes(),
+            )),
+            part: Some((index + 1, parts)),
+        })
+        .collect())
+}
+
+// cuts on char boundaries so every part is still valid utf-8
+fn split_body(body: &str, max_bytes: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = body;
+    while rest.len() > max_bytes {
+        let mut end = max_bytes;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks.push(rest);
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(update_date: &str, description: String) -> ItchData {
+        ItchData {
+            link: "https://dev.itch.io/game".to_string(),
+            update_date: update_date.to_string(),
+            description,
+            ..ItchData::default()
+        }
+    }
+
+    #[test]
+    fn dedup_id_changes_with_the_update_date() {
+        let games = [
+            record("2024-04-01 10:00:00", String::new()),
+            record("2024-04-01 10:00:00", String::new()),
+            record("2024-04-02 10:00:00", String::new()),
+        ];
+        let first = messages(&games[0]).unwrap();
+        let again = messages(&games[1]).unwrap();
+        let updated = messages(&games[2]).unwrap();
+
+        assert_eq!(first[0].dedup_id, again[0].dedup_id);
+        assert_ne!(first[0].dedup_id, updated[0].dedup_id);
+        assert_eq!(first[0].part, None);
+    }
+
+    #[test]
+    fn oversized_record_is_split_into_parts_that_join_back() {
+        let game = record("2024-04-01 10:00:00", "é".repeat(MAX_BATCH_BYTES));
+        let parts = messages(&game).unwrap();
+
+        assert_eq!(parts.len(), 3);
+        assert!(parts.iter().all(|part| part.body.len() <= MAX_PART_BYTES));
+        assert_eq!(parts[2].part, Some((3, 3)));
+        assert_ne!(parts[0].dedup_id, parts[1].dedup_id);
+        let joined: String = parts.iter().map(|part| part.body.as_str()).collect();
+        assert_eq!(joined, serde_json::to_string(&game).unwrap());
+    }
+
+    #[test]
+    fn batches_stay_under_the_request_limit() {
+        let sink =
+            SqsSink::new(&Url::parse("sqs://us-east-1/123456789012/games").unwrap()).unwrap();
+        let records: Vec<ItchData> = (0..4)
+            .map(|_| record("2024-04-01 10:00:00", "x".repeat(100_000)))
+            .collect();
+        let batches = sink.batches(&records).unwrap();
+
+        assert_eq!(batches.len(), 2);
+        for batch in batches {
+            let bytes: usize = batch.iter().map(|message| message.body.len()).sum();
+            assert!(bytes <= MAX_BATCH_BYTES);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::scrapers::itch_rss_scraper::ItchData;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// a hash of the whole record plus one per field, so a change can be pinned to the fields that
+// caused it without keeping the previous record around
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+struct Snapshot {
+    hash: String,
+    fields: BTreeMap<String, String>,
+}
+
+pub struct SnapshotStore {
+    path: PathBuf,
+    // keyed by record link
+    snapshots: HashMap<String, Snapshot>,
+}
+
+#[derive(Debug, Default)]
+pub struct ChangeSummary {
+    pub new: Vec<String>,
+    pub changed: Vec<(String, Vec<String>)>,
+    pub unchanged: usize,
+}
+
+impl SnapshotStore {
+    pub fn load(path: &Path) -> Result<SnapshotStore> {
+        let snapshots = if path.exists() {
+            let contents =
+                fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+            serde_json::from_str(&contents)
+                .with_context(|| format!("Invalid snapshot file {:?}", path))?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(SnapshotStore {
+            path: path.to_path_buf(),
+            snapshots,
+        })
+    }
+
+    pub fn take_changed(
+        &mut self,
+        records: Vec<ItchData>,
+    ) -> Result<(Vec<ItchData>, ChangeSummary)> {
+        let mut changed = Vec::new();
+        let mut summary = ChangeSummary::default();
+
+        for record in records {
+            let snapshot = snapshot(&record)?;
+            match self.snapshots.get(&record.link) {
+                Some(previous) if previous.hash == snapshot.hash => {
+                    summary.unchanged += 1;
+                    continue;
+                }
+                Some(previous) => {
+                    let fields = snapshot
+                        .fields
+                        .iter()
+                        .filter(|(field, hash)| previous.fields.get(*field) != Some(hash))
+                        .map(|(field, _)| field.clone())
+                        .collect();
+                    summary.changed.push((record.plain_title.clone(), fields));
+                }
+                None => summary.new.push(record.plain_title.clone()),
+            }
+
+            self.snapshots.insert(record.link.clone(), snapshot);
+            changed.push(record);
+        }
+
+        Ok((changed, summary))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string(&self.snapshots)?)
+            .with_context(|| format!("Failed to write {:?}", self.path))
+    }
+}
+
+fn snapshot(record: &ItchData) -> Result<Snapshot> {
+    let value = serde_json::to_value(record)?;
+    let fields = match &value {
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(field, value)| (field.clone(), hash(&value.to_string())))
+            .collect(),
+        _ => BTreeMap::new(),
+    };
+
+    Ok(Snapshot {
+        hash: hash(&value.to_string()),
+        fields,
+    })
+}
+
+fn hash(contents: &str) -> String {
+    hex::encode(Sha256::digest(contents.as_bytes()))
+}
+
+impl ChangeSummary {
+    pub fn render(&self) -> String {
+        let mut output = format!(
+            "{} new, {} changed, {} unchanged",
+            self.new.len(),
+            self.changed.len(),
+            self.unchanged
+        );
+        for title in &self.new {
+            let _ = write!(output, "\n  + {}", title);
+        }
+        for (title, fields) in &self.changed {
+            let _ = write!(output, "\n  ~ {} ({})", title, fields.join(", "));
+        }
+        output
+    }
+}
@@ -0,0 +1,209 @@
+use crate::parsers::itch_game_info_parser::{Genre, Tag};
+use crate::scrapers::itch_rss_scraper::ItchData;
+use crate::Site;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const OTHER_GENRE: &str = "Other";
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Taxonomy {
+    #[serde(default)]
+    tags: HashMap<String, String>,
+    #[serde(default)]
+    genres: GenreTaxonomy,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct GenreTaxonomy {
+    #[serde(default)]
+    vocabulary: Vec<String>,
+    #[serde(default)]
+    sites: HashMap<String, HashMap<String, String>>,
+}
+
+impl Taxonomy {
+    pub fn from_file(path: &Path) -> Result<Taxonomy> {
+        let contents =
+            fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let taxonomy: Taxonomy = toml::from_str(&contents)
+            .with_context(|| format!("Invalid taxonomy file {:?}", path))?;
+
+        Ok(Taxonomy {
+            tags: lowercase_keys(taxonomy.tags),
+            genres: GenreTaxonomy {
+                vocabulary: taxonomy.genres.vocabulary,
+                sites: taxonomy
+                    .genres
+                    .sites
+                    .into_iter()
+                    .map(|(site, mapping)| (site.to_lowercase(), lowercase_keys(mapping)))
+                    .collect(),
+            },
+        })
+    }
+
+    pub fn apply(&self, site: &Site, record: &mut ItchData) {
+        if !self.tags.is_empty() {
+            let names: Vec<String> = record.tag_names().into_iter().map(str::to_string).collect();
+            let canonical = canonicalize(&names, &self.tags);
+            if canonical != names {
+                // a renamed tag keeps the slug and url of the first raw tag mapped onto it
+                let mut tags: Vec<Tag> = Vec::new();
+                for tag in &record.tags {
+                    let name = self
+                        .tags
+                        .get(&tag.name.trim().to_lowercase())
+                        .unwrap_or(&tag.name);
+                    if !tags
+                        .iter()
+                        .any(|known| known.name.eq_ignore_ascii_case(name))
+                    {
+                        tags.push(Tag {
+                            name: name.clone(),
+                            ..tag.clone()
+                        });
+                    }
+                }
+                record.tags = tags;
+                record.raw_tags = names;
+            }
+        }
+
+        let mapping = self.genres.sites.get(site.name());
+        if mapping.is_none() && self.genres.vocabulary.is_empty() {
+            return;
+        }
+
+        let empty = HashMap::new();
+        let mapping = mapping.unwrap_or(&empty);
+        // like tags, a renamed genre keeps the url of the first raw genre mapped onto it
+        let mut genres: Vec<Genre> = Vec::new();
+        for genre in &record.genres {
+            let mut name = mapping
+                .get(&genre.name.trim().to_lowercase())
+                .unwrap_or(&genre.name)
+                .clone();
+            if !self.genres.vocabulary.is_empty() {
+                name = self.genres.restrict(&name);
+            }
+
+            if !genres
+                .iter()
+                .any(|known| known.name.eq_ignore_ascii_case(&name))
+            {
+                genres.push(Genre {
+                    name,
+                    ..genre.clone()
+                });
+            }
+        }
+        if genres != record.genres {
+            record.raw_genres = record
+                .genre_names()
+                .into_iter()
+                .map(str::to_string)
+                .collect();
+            record.genres = genres;
+        }
+    }
+}
+
+impl Taxonomy {
+    // what a wanted tag is called once records have been through apply
+    pub fn canonical_tag(&self, tag: &str) -> String {
+        self.tags
+            .get(&tag.trim().to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| tag.to_string())
+    }
+
+    // the itch tag to filter the feed url by; a feed filters by one raw tag per path segment, so
+    // a canonical tag more than one raw tag is folded into can only be matched locally
+    pub fn raw_tag(&self, tag: &str) -> Option<String> {
+        let canonical = self.canonical_tag(tag);
+        let mut raw: Vec<String> = self
+            .tags
+            .iter()
+            .filter(|(_, mapped)| mapped.eq_ignore_ascii_case(&canonical))
+            .map(|(raw, _)| raw.clone())
+            .collect();
+        // itch's own tag by that name is a source too unless the taxonomy renames it
+        let own = canonical.trim().to_lowercase();
+        if !self.tags.contains_key(&own) {
+            raw.push(own);
+        }
+
+        match raw.as_slice() {
+            [only] => Some(only.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl GenreTaxonomy {
+    // anything outside the controlled vocabulary collapses into a single catch-all genre
+    fn restrict(&self, genre: &str) -> String {
+        self.vocabulary
+            .iter()
+            .find(|v| v.eq_ignore_ascii_case(genre))
+            .cloned()
+            .unwrap_or_else(|| OTHER_GENRE.to_string())
+    }
+}
+
+fn lowercase_keys(mapping: HashMap<String, String>) -> HashMap<String, String> {
+    mapping
+        .into_iter()
+        .map(|(raw, canonical)| (raw.trim().to_lowercase(), canonical))
+        .collect()
+}
+
+fn canonicalize(values: &[String], mapping: &HashMap<String, String>) -> Vec<String> {
+    let mut canonical: Vec<String> = Vec::new();
+    for value in values {
+        let mapped = mapping
+            .get(&value.trim().to_lowercase())
+            .unwrap_or(value)
+            .clone();
+
+        if !canonical.iter().any(|c| c.eq_ignore_ascii_case(&mapped)) {
+            canonical.push(mapped);
+        }
+    }
+
+    canonical
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_pushed_down_as_their_only_raw_tag() {
+        let taxonomy: Taxonomy = toml::from_str(
+            r#"
+            [tags]
+            "rogue-like" = "Roguelike"
+            "roguelite" = "Roguelike"
+            "pixelart" = "Pixel Art"
+            "metroidvania" = "Metroidvania"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(taxonomy.canonical_tag("PixelArt"), "Pixel Art");
+        assert_eq!(
+            taxonomy.raw_tag("Metroidvania").as_deref(),
+            Some("metroidvania")
+        );
+        // itch's own Pixel Art tag and pixelart both end up as Pixel Art, which one url segment
+        // can't ask for
+        assert_eq!(taxonomy.raw_tag("Pixel Art"), None);
+        assert_eq!(taxonomy.raw_tag("Roguelike"), None);
+        assert_eq!(taxonomy.raw_tag("Horror").as_deref(), Some("horror"));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::scrapers::progress::{ProgressReporter, ScrapeEvent};
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Gauge, List, ListItem, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+const FEED_LENGTH: usize = 50;
+
+pub struct ChannelReporter {
+    tx: UnboundedSender<ScrapeEvent>,
+}
+
+impl ProgressReporter for ChannelReporter {
+    fn report(&self, event: ScrapeEvent) {
+        let _ = self.tx.send(event);
+    }
+}
+
+pub fn channel() -> (ChannelReporter, UnboundedReceiver<ScrapeEvent>) {
+    let (tx, rx) = unbounded_channel();
+    (ChannelReporter { tx }, rx)
+}
+
+#[derive(Default)]
+struct Dashboard {
+    started: Option<Instant>,
+    pages: u64,
+    pages_done: u64,
+    items: u64,
+    errors: u64,
+    rate_limits: u64,
+    last_rate_limit: Option<(String, Duration)>,
+    recent_items: VecDeque<String>,
+    recent_errors: VecDeque<String>,
+}
+
+impl Dashboard {
+    fn apply(&mut self, event: ScrapeEvent) {
+        match event {
+            ScrapeEvent::Started { pages } => {
+                self.started = Some(Instant::now());
+                self.pages = pages;
+            }
+            ScrapeEvent::PageFinished { .. } => self.pages_done += 1,
+            ScrapeEvent::Item { title, link } => {
+                self.items += 1;
+                push_bounded(&mut self.recent_items, format!("{}  {}", title, link));
+            }
+            ScrapeEvent::Error { message, .. } => {
+                self.errors += 1;
+                push_bounded(&mut self.recent_errors, message);
+            }
+            ScrapeEvent::Warning { message, .. } => {
+                push_bounded(&mut self.recent_errors, format!("warning: {}", message));
+            }
+            ScrapeEvent::RateLimited { url, retry_in } => {
+                self.rate_limits += 1;
+                self.last_rate_limit = Some((url, retry_in));
+            }
+            ScrapeEvent::Downloaded { .. } | ScrapeEvent::Finished => {}
+        }
+    }
+
+    fn items_per_minute(&self) -> f64 {
+        match self.started {
+            Some(started) if started.elapsed().as_secs_f64() > 0.0 => {
+                self.items as f64 / started.elapsed().as_secs_f64() * 60.0
+            }
+            _ => 0.0,
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(5),
+                Constraint::Length(3),
+            ])
+            .split(frame.area());
+
+        let ratio = if self.pages == 0 {
+            0.0
+        } else {
+            (self.pages_done as f64 / self.pages as f64).min(1.0)
+        };
+        frame.render_widget(
+            Gauge::default()
+                .block(Block::default().borders(Borders::ALL).title("Pages"))
+                .gauge_style(Style::default().fg(Color::Cyan))
+                .ratio(ratio)
+                .label(format!("{}/{}", self.pages_done, self.pages)),
+            rows[0],
+        );
+
+        let elapsed = self.started.map(|s| s.elapsed()).unwrap_or_default();
+        frame.render_widget(
+            Paragraph::new(format!(
+                "elapsed {}s | {} items | {:.1} items/min | {} errors",
+                elapsed.as_secs(),
+                self.items,
+                self.items_per_minute(),
+                self.errors
+            ))
+            .block(Block::default().borders(Borders::ALL).title("Throughput")),
+            rows[1],
+        );
+
+        let feeds = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(rows[2]);
+        frame.render_widget(
+            feed_list("Recent items", &self.recent_items, Color::White),
+            feeds[0],
+        );
+        frame.render_widget(
+            feed_list("Errors", &self.recent_errors, Color::Red),
+            feeds[1],
+        );
+
+        let rate_limit_status = match &self.last_rate_limit {
+            Some((url, retry_in)) => format!(
+                "{} rate limit(s), last on {} (backed off {}s)",
+                self.rate_limits,
+                url,
+                retry_in.as_secs()
+            ),
+            None => "no rate limiting so far".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(rate_limit_status)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Rate limit (q to quit)"),
+            ),
+            rows[3],
+        );
+    }
+}
+
+fn push_bounded(feed: &mut VecDeque<String>, entry: String) {
+    feed.push_front(entry);
+    feed.truncate(FEED_LENGTH);
+}
+
+fn feed_list<'a>(title: &'a str, feed: &'a VecDeque<String>, color: Color) -> List<'a> {
+    List::new(feed.iter().map(|entry| ListItem::new(entry.as_str())))
+        .style(Style::default().fg(color))
+        .block(Block::default().borders(Borders::ALL).title(title))
+}
+
+pub fn run_dashboard<T>(
+    mut events: UnboundedReceiver<ScrapeEvent>,
+    scrape: &JoinHandle<T>,
+) -> Result<()> {
+    let mut terminal = ratatui::init();
+    let result = dashboard_loop(&mut terminal, &mut events, scrape);
+    ratatui::restore();
+    result
+}
+
+fn dashboard_loop<T>(
+    terminal: &mut DefaultTerminal,
+    events: &mut UnboundedReceiver<ScrapeEvent>,
+    scrape: &JoinHandle<T>,
+) -> Result<()> {
+    let mut dashboard = Dashboard::default();
+
+    while !scrape.is_finished() {
+        while let Ok(event) = events.try_recv() {
+            dashboard.apply(event);
+        }
+
+        terminal.draw(|frame| dashboard.draw(frame))?;
+
+        if event::poll(Duration::from_millis(200))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && key.code == KeyCode::Char('q') {
+                    scrape.abort();
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+// decimal units, as shown by download tools and metered connection plans
+const UNITS: [(&str, u64); 4] = [
+    ("GB", 1_000_000_000),
+    ("MB", 1_000_000),
+    ("KB", 1_000),
+    ("B", 1),
+];
+
+// "500MB", "1.5 GB" or a plain number of bytes
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let upper = value.to_uppercase();
+    let (number, multiplier) = UNITS
+        .iter()
+        .find_map(|(unit, multiplier)| {
+            upper
+                .strip_suffix(unit)
+                .map(|number| (number.trim(), *multiplier))
+        })
+        .unwrap_or((value, 1));
+
+    match number.parse::<f64>() {
+        Ok(number) if number >= 0.0 => Ok((number * multiplier as f64) as u64),
+        _ => Err(format!(
+            "invalid size {:?}, expected a number with an optional B, KB, MB or GB unit",
+            value
+        )),
+    }
+}
+
+pub fn format_size(bytes: u64) -> String {
+    let (unit, multiplier) = UNITS
+        .iter()
+        .find(|(_, multiplier)| bytes >= *multiplier)
+        .unwrap_or(&UNITS[3]);
+
+    if *multiplier == 1 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", bytes as f64 / *multiplier as f64, unit)
+    }
+}
+
+// "10MB/s", or a size read as per second
+pub fn parse_rate(value: &str) -> Result<u64, String> {
+    let size = value.trim().trim_end_matches("/s");
+    match parse_size(size)? {
+        0 => Err("the rate must be above 0".to_string()),
+        rate => Ok(rate),
+    }
+}
@@ -0,0 +1,88 @@
+use game_data_scraper::checkpoint::{Checkpoint, ResumeMode};
+use std::fs;
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::Duration;
+
+const FEED: &str = "https://itch.io/games/newest.xml";
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "game-data-scraper-checkpoint-{}-{}",
+        name,
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// a templated outfile names a new file every run, so the last run's output is the one that says
+// whether the checkpoint was written out
+#[test]
+fn written_out_checkpoint_is_judged_by_the_outfile_it_recorded() {
+    let dir = temp_dir("written");
+    let path = dir.join("scrape.checkpoint");
+    let first_outfile = dir.join("newest-1.json");
+    let next_outfile = dir.join("newest-2.json");
+
+    let first = Checkpoint::open(
+        &path,
+        FEED,
+        1..=3,
+        ResumeMode::Never,
+        false,
+        Some(&first_outfile),
+    )
+    .unwrap();
+    first.save(2, &[]).unwrap();
+    sleep(Duration::from_millis(20));
+    fs::write(&first_outfile, "[]").unwrap();
+
+    assert!(Checkpoint::open(
+        &path,
+        FEED,
+        1..=3,
+        ResumeMode::Never,
+        false,
+        Some(&next_outfile),
+    )
+    .is_ok());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn unwritten_checkpoint_is_kept_without_force() {
+    let dir = temp_dir("unwritten");
+    let path = dir.join("scrape.checkpoint");
+    let first_outfile = dir.join("newest-1.json");
+    let next_outfile = dir.join("newest-2.json");
+    fs::write(&next_outfile, "[]").unwrap();
+    sleep(Duration::from_millis(20));
+
+    let first = Checkpoint::open(
+        &path,
+        FEED,
+        1..=3,
+        ResumeMode::Never,
+        false,
+        Some(&first_outfile),
+    )
+    .unwrap();
+    first.save(2, &[]).unwrap();
+
+    let open = |force| {
+        Checkpoint::open(
+            &path,
+            FEED,
+            1..=3,
+            ResumeMode::Never,
+            force,
+            Some(&next_outfile),
+        )
+    };
+    assert!(open(false).is_err());
+    assert!(open(true).is_ok());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,27 @@
+use std::process::Command;
+
+fn binary() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_game-data-scraper"))
+}
+
+#[test]
+fn scrape_variables_dont_apply_to_subcommands() {
+    for (key, value) in [("GDS_JOBS", "4"), ("GDS_QUIET", "true")] {
+        let output = binary().arg("proto").env(key, value).output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}={} proto failed: {}",
+            key,
+            value,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(String::from_utf8_lossy(&output.stdout).contains("message"));
+    }
+}
+
+#[test]
+fn scrape_variables_still_configure_scrapes() {
+    let output = binary().env("GDS_JOBS", "0").output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--jobs"));
+}
@@ -0,0 +1,29 @@
+use game_data_scraper::parsers::corpus::{self, CORPUS_DIR};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn parser_output_matches_golden_files() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(CORPUS_DIR);
+    let cases = corpus::cases(&dir).unwrap();
+    assert!(!cases.is_empty(), "no pages found in {:?}", dir);
+
+    let mut mismatched = Vec::new();
+    for case in &cases {
+        let expected = fs::read_to_string(&case.golden).unwrap_or_default();
+        let actual = case.render().unwrap();
+        if expected != actual {
+            eprintln!(
+                "--- {} expected\n{}\n+++ {} actual\n{}",
+                case.name, expected, case.name, actual
+            );
+            mismatched.push(case.name.as_str());
+        }
+    }
+
+    assert!(
+        mismatched.is_empty(),
+        "parser output changed for {}; if intended, run `game-data-scraper test-fixtures update` and review the diff",
+        mismatched.join(", ")
+    );
+}
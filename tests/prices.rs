@@ -0,0 +1,152 @@
+use game_data_scraper::filters::Filter;
+use game_data_scraper::parsers::price::{is_free, price_amount, Price};
+use game_data_scraper::scrapers::itch_rss_scraper::ItchData;
+
+#[test]
+fn price_amount_reads_any_separator_style() {
+    let cases = [
+        ("$4.99", Some(4.99)),
+        ("$5.00 USD", Some(5.0)),
+        ("$0 USD or more", Some(0.0)),
+        ("$1,299", Some(1299.0)),
+        ("$1,299.50", Some(1299.5)),
+        ("4,99 €", Some(4.99)),
+        ("€3,00", Some(3.0)),
+        ("1.299,00 €", Some(1299.0)),
+        ("1 299,95 kr", Some(1299.95)),
+        ("CHF 1'299.00", Some(1299.0)),
+        ("$1,000,000", Some(1_000_000.0)),
+        ("0.999", Some(0.999)),
+        ("Free", None),
+        ("", None),
+    ];
+    for (price, expected) in cases {
+        assert_eq!(price_amount(price), expected, "{:?}", price);
+    }
+}
+
+#[test]
+fn is_free_needs_free_text_or_a_zero_amount() {
+    for price in ["Free", "free to play", "$0", "$0.00 USD", "0,00 €"] {
+        assert!(is_free(price), "{:?}", price);
+    }
+    for price in ["", "N/A", "Name your own price", "$4.99", "$10"] {
+        assert!(!is_free(price), "{:?}", price);
+    }
+}
+
+// a notification rule for free games shouldn't announce a game whose price never loaded
+#[test]
+fn free_filter_skips_records_without_a_price() {
+    let free = Filter {
+        free: Some(true),
+        ..Filter::default()
+    };
+    let record = |price: &str| ItchData {
+        price: price.to_string(),
+        price_info: Price::parse(price),
+        ..ItchData::default()
+    };
+
+    assert!(free.matches(&record("$0.00")));
+    assert!(!free.matches(&record("")));
+    assert!(!free.matches(&record("N/A")));
+}
+
+// browse cells without a price and discovered games only have the game page's price
+#[test]
+fn price_filters_read_the_parsed_price() {
+    let page_only = |page: &str| ItchData {
+        price_info: Price::from_sources("", page),
+        ..ItchData::default()
+    };
+    let free = Filter {
+        free: Some(true),
+        ..Filter::default()
+    };
+    let cheap = Filter {
+        max_price: Some(5.0),
+        ..Filter::default()
+    };
+
+    assert!(free.matches(&page_only("Free")));
+    assert!(cheap.matches(&page_only("Free")));
+    assert!(cheap.matches(&page_only("$4.99 USD")));
+    assert!(!cheap.matches(&page_only("$10.00 USD")));
+}
+
+#[test]
+fn price_parse_reads_each_itch_format() {
+    let price = |amount, currency: &str, free, pwyw| Price {
+        amount,
+        currency: currency.to_string(),
+        free,
+        pwyw,
+    };
+    let cases = [
+        ("$4.99", price(Some(4.99), "USD", false, false)),
+        ("Free", price(Some(0.0), "", true, false)),
+        ("€3,00", price(Some(3.0), "EUR", false, false)),
+        ("4,99 €", price(Some(4.99), "EUR", false, false)),
+        ("$0 USD or more", price(Some(0.0), "USD", false, true)),
+        ("Name your own price", price(Some(0.0), "", false, true)),
+    ];
+    for (text, expected) in cases {
+        assert_eq!(Price::parse(text), Some(expected), "{:?}", text);
+    }
+    assert_eq!(Price::parse(""), None);
+}
+
+#[test]
+fn price_from_sources_prefers_the_feed_and_fills_gaps_from_the_page() {
+    let both = Price::from_sources("$4.99", "€3,00").unwrap();
+    assert_eq!((both.amount, both.currency.as_str()), (Some(4.99), "USD"));
+
+    // the page names the currency the feed left out
+    let filled = Price::from_sources("Name your own price", "$2.00 USD or more").unwrap();
+    assert_eq!(
+        (filled.amount, filled.currency.as_str()),
+        (Some(0.0), "USD")
+    );
+    assert!(filled.pwyw);
+
+    let page_only = Price::from_sources("", "€3,00").unwrap();
+    assert_eq!(page_only.amount, Some(3.0));
+    assert_eq!(Price::from_sources("", ""), None);
+}
+
+#[test]
+fn upgrade_moves_split_price_fields_onto_price_info() {
+    let mut record: ItchData = serde_json::from_value(serde_json::json!({
+        "title": "Cave Run",
+        "plain_title": "Cave Run",
+        "link": "https://alice.itch.io/cave-run",
+        "create_date": "",
+        "update_date": "",
+        "release_date": "",
+        "pub_date": "",
+        "price": "$2.00 USD or more",
+        "price_amount": 2.0,
+        "price_currency": "USD",
+        "price_or_more": true,
+        "description": "",
+        "authors": [],
+        "genres": [],
+        "made_with": [],
+        "tags": [],
+        "average_session": "",
+        "languages": [],
+        "inputs": [],
+        "links": [],
+        "status": "",
+        "platforms": [],
+        "accessibility": []
+    }))
+    .unwrap();
+    record.upgrade();
+
+    assert!(record.extra.is_empty());
+    let price = record.price_info.unwrap();
+    assert_eq!((price.amount, price.currency.as_str()), (Some(2.0), "USD"));
+    assert!(price.pwyw && !price.free);
+}
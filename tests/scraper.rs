@@ -0,0 +1,719 @@
+use futures_util::StreamExt;
+use game_data_scraper::filters::Filter;
+use game_data_scraper::scrapers::bandwidth::Bandwidth;
+use game_data_scraper::scrapers::fixtures::{sanitize, FixtureRecorder, Manifest};
+use game_data_scraper::scrapers::images::{download_images, ImageKind, ImageStore};
+use game_data_scraper::scrapers::itch_rss_scraper::{
+    push_down_filters, scrape_itch_rss_feed, scrape_itch_rss_feed_stream, ItchData,
+};
+use game_data_scraper::scrapers::options::{Concurrency, DateWindow, ScrapeOptions};
+use game_data_scraper::scrapers::progress::{CountingReporter, SilentReporter};
+use game_data_scraper::scrapers::rate_limit::RateLimiter;
+use game_data_scraper::scrapers::scraper::{ItchBrowseScraper, ItchScraper, SteamScraper};
+use game_data_scraper::scrapers::session::ScrapeSession;
+use game_data_scraper::{GameRecord, Scraper};
+use reqwest::Url;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::sleep;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const FEED_PATH: &str = "/games/newest.xml";
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/itch")
+}
+
+fn options(pages: Vec<i32>) -> ScrapeOptions {
+    ScrapeOptions {
+        max_retries: 0,
+        pages,
+        fail_on_error: false,
+        comments: false,
+        window: DateWindow::default(),
+        limiter: Arc::new(RateLimiter::default()),
+        bandwidth: Arc::new(Bandwidth::default()),
+        checkpoint: None,
+        concurrency: Concurrency::default(),
+        plugin: None,
+        recorder: None,
+    }
+}
+
+// serves the recorded feed pages at FEED_PATH?page=N and every game page under /pages, with the
+// feed links pointed at the mock server instead of itch.io
+async fn serve_fixtures() -> MockServer {
+    let server = MockServer::start().await;
+    let dir = fixtures_dir();
+    let manifest = Manifest::load(&dir).unwrap();
+
+    for (page, file) in &manifest.feeds {
+        let mut feed = fs::read_to_string(dir.join(file)).unwrap();
+        for (url, page_file) in &manifest.pages {
+            feed = feed.replace(url, &format!("{}/pages/{}", server.uri(), page_file));
+        }
+        Mock::given(method("GET"))
+            .and(path(FEED_PATH))
+            .and(query_param("page", page.to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_string(feed))
+            .mount(&server)
+            .await;
+    }
+    for file in manifest.pages.values() {
+        Mock::given(method("GET"))
+            .and(path(format!("/pages/{}", file)))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(fs::read_to_string(dir.join(file)).unwrap()),
+            )
+            .mount(&server)
+            .await;
+    }
+
+    server
+}
+
+// takes priority over the fixture, so the page 404s
+async fn fail_game_page(server: &MockServer, file: &str) {
+    Mock::given(method("GET"))
+        .and(path(format!("/pages/{}", file)))
+        .respond_with(ResponseTemplate::new(404))
+        .with_priority(1)
+        .mount(server)
+        .await;
+}
+
+fn feed_url(server: &MockServer) -> String {
+    format!("{}{}", server.uri(), FEED_PATH)
+}
+
+#[tokio::test]
+async fn parses_feed_items_and_game_pages() {
+    let server = serve_fixtures().await;
+
+    let options = ScrapeOptions {
+        comments: true,
+        ..options(vec![1])
+    };
+    let records = scrape_itch_rss_feed(feed_url(&server), &options, &SilentReporter)
+        .await
+        .unwrap();
+
+    assert_eq!(records.len(), 2);
+    let cave_run = &records[0];
+    assert_eq!(cave_run.plain_title, "Cave Run");
+    assert_eq!(cave_run.price, "$0.00");
+    let price = cave_run.price_info.as_ref().unwrap();
+    assert_eq!(price.amount, Some(0.0));
+    assert!(price.free && !price.pwyw);
+    assert_eq!(price.currency, "USD");
+    assert_eq!(cave_run.pub_date, "Mon, 01 Apr 2024 10:00:00 GMT");
+    assert_eq!(cave_run.status, "Released");
+    assert_eq!(cave_run.updated_date, "2024-04-02 10:00:00");
+    assert_eq!(cave_run.platforms, ["Windows", "Linux"]);
+    let rating = cave_run.rating.as_ref().unwrap();
+    assert_eq!(rating.score, 4.5);
+    assert_eq!(rating.count, 20);
+    assert_eq!(cave_run.authors[0].name, "alice");
+    assert_eq!(cave_run.authors[0].url, "https://alice.itch.io");
+    assert_eq!(cave_run.genre_names(), ["Action"]);
+    assert_eq!(cave_run.genres[0].url, "https://itch.io/games/genre-action");
+    assert_eq!(cave_run.made_with, ["Godot"]);
+    assert_eq!(cave_run.tag_names(), ["Roguelike", "Pixel Art"]);
+    assert_eq!(cave_run.tags[1].slug, "pixel-art");
+    assert_eq!(cave_run.average_session, "A few minutes");
+    assert_eq!(cave_run.links[0].url, "https://discord.gg/caverun");
+    assert_eq!(cave_run.comments[0].author, "miner42");
+
+    let sky_bound = &records[1];
+    assert_eq!(sky_bound.plain_title, "Sky Bound");
+    let price = sky_bound.price_info.as_ref().unwrap();
+    assert_eq!(price.amount, Some(4.99));
+    assert_eq!(price.currency, "USD");
+    assert!(!price.pwyw);
+    assert_eq!(sky_bound.inputs, ["Keyboard", "Gamepad (any)"]);
+    assert!(sky_bound.links.is_empty());
+}
+
+#[tokio::test]
+async fn leaves_comments_out_unless_asked_for() {
+    let server = serve_fixtures().await;
+
+    let records = scrape_itch_rss_feed(feed_url(&server), &options(vec![1]), &SilentReporter)
+        .await
+        .unwrap();
+
+    assert_eq!(records.len(), 2);
+    assert!(records.iter().all(|record| record.comments.is_empty()));
+}
+
+// `--platform mac` asks itch for the platform-osx listing and keeps the games whose page lists
+// macOS, so pushing the filter down doesn't drop what the local filter would keep
+#[tokio::test]
+async fn platform_pushdown_agrees_with_the_local_filter() {
+    let server = MockServer::start().await;
+    let dir = fixtures_dir();
+    let manifest = Manifest::load(&dir).unwrap();
+    let filter = Filter {
+        platforms: vec!["mac".to_string()],
+        ..Filter::default()
+    };
+    let pushed = Url::parse(&push_down_filters(
+        "https://itch.io/games/newest.xml",
+        &filter,
+    ))
+    .unwrap();
+    assert_eq!(pushed.path(), "/games/newest/platform-osx.xml");
+
+    let mut feed = fs::read_to_string(dir.join(&manifest.feeds[&1])).unwrap();
+    for (url, page_file) in &manifest.pages {
+        feed = feed.replace(url, &format!("{}/pages/{}", server.uri(), page_file));
+        Mock::given(method("GET"))
+            .and(path(format!("/pages/{}", page_file)))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(fs::read_to_string(dir.join(page_file)).unwrap()),
+            )
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path(pushed.path()))
+        .respond_with(ResponseTemplate::new(200).set_body_string(feed))
+        .mount(&server)
+        .await;
+
+    let records = scrape_itch_rss_feed(
+        format!("{}{}", server.uri(), pushed.path()),
+        &options(vec![1]),
+        &SilentReporter,
+    )
+    .await
+    .unwrap();
+
+    let kept: Vec<&str> = records
+        .iter()
+        .filter(|record| filter.matches(record))
+        .map(|record| record.plain_title.as_str())
+        .collect();
+    assert_eq!(kept, ["Sky Bound"]);
+}
+
+#[tokio::test]
+async fn follows_pages_in_order() {
+    let server = serve_fixtures().await;
+
+    let records = scrape_itch_rss_feed(feed_url(&server), &options(vec![1, 2]), &SilentReporter)
+        .await
+        .unwrap();
+
+    let titles: Vec<&str> = records
+        .iter()
+        .map(|record| record.plain_title.as_str())
+        .collect();
+    assert_eq!(titles, ["Cave Run", "Sky Bound", "Tiny Farm"]);
+    // no rating row on the page
+    assert!(records[2].rating.is_none());
+    let price = records[2].price_info.as_ref().unwrap();
+    assert_eq!(price.amount, Some(2.0));
+    assert!(price.pwyw && !price.free);
+}
+
+#[tokio::test]
+async fn scrapes_through_the_scraper_trait() {
+    let server = serve_fixtures().await;
+
+    async fn titles(scraper: &impl Scraper, options: &ScrapeOptions) -> Vec<String> {
+        let records: Vec<GameRecord> = scraper.scrape(options, &SilentReporter).await.unwrap();
+        records
+            .into_iter()
+            .map(|record| record.plain_title)
+            .collect()
+    }
+
+    let scraper = ItchScraper::new(feed_url(&server));
+    let options = ScrapeOptions {
+        max_retries: 0,
+        pages: vec![1, 2],
+        ..ScrapeOptions::default()
+    };
+    assert_eq!(
+        titles(&scraper, &options).await,
+        ["Cave Run", "Sky Bound", "Tiny Farm"]
+    );
+}
+
+#[tokio::test]
+async fn retries_rate_limited_requests() {
+    let server = serve_fixtures().await;
+    // mounted last and with a higher priority, so it answers the first request before the fixture
+    Mock::given(method("GET"))
+        .and(path(FEED_PATH))
+        .respond_with(ResponseTemplate::new(429))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+
+    let mut options = options(vec![1]);
+    options.max_retries = 1;
+    let records = scrape_itch_rss_feed(feed_url(&server), &options, &SilentReporter)
+        .await
+        .unwrap();
+
+    assert_eq!(records.len(), 2);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_retries() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(FEED_PATH))
+        .respond_with(ResponseTemplate::new(429))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = scrape_itch_rss_feed(feed_url(&server), &options(vec![1]), &SilentReporter).await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn skips_unparseable_pages_unless_failing_on_error() {
+    let server = serve_fixtures().await;
+    let broken = "<div class=\"game_info_panel_widget\"><table><tbody><tr><td>Status</td></tr></tbody></table></div>";
+    Mock::given(method("GET"))
+        .and(path("/pages/page-bob.itch.io-sky-bound.html"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(broken))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+
+    let records = scrape_itch_rss_feed(feed_url(&server), &options(vec![1]), &SilentReporter)
+        .await
+        .unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].plain_title, "Cave Run");
+
+    let mut options = options(vec![1]);
+    options.fail_on_error = true;
+    let result = scrape_itch_rss_feed(feed_url(&server), &options, &SilentReporter).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn skips_game_pages_that_fail_to_fetch() {
+    let server = serve_fixtures().await;
+    fail_game_page(&server, "page-alice.itch.io-cave-run.html").await;
+
+    let reporter = CountingReporter::new(SilentReporter);
+    let records = scrape_itch_rss_feed(feed_url(&server), &options(vec![1, 2]), &reporter)
+        .await
+        .unwrap();
+
+    let titles: Vec<_> = records.iter().map(|r| r.plain_title.as_str()).collect();
+    assert_eq!(titles, ["Sky Bound", "Tiny Farm"]);
+    assert_eq!(reporter.errors(), 1);
+}
+
+#[tokio::test]
+async fn streams_page_errors_between_records() {
+    let server = serve_fixtures().await;
+    fail_game_page(&server, "page-alice.itch.io-cave-run.html").await;
+
+    let items: Vec<_> = scrape_itch_rss_feed_stream(feed_url(&server), options(vec![1, 2]))
+        .collect()
+        .await;
+
+    assert_eq!(items.len(), 3);
+    assert!(items[0]
+        .as_ref()
+        .unwrap_err()
+        .to_string()
+        .contains("cave-run"));
+    assert_eq!(items[1].as_ref().unwrap().plain_title, "Sky Bound");
+    assert_eq!(items[2].as_ref().unwrap().plain_title, "Tiny Farm");
+}
+
+#[tokio::test]
+async fn dropping_the_stream_stops_the_scrape() {
+    let server = serve_fixtures().await;
+
+    let mut stream = Box::pin(scrape_itch_rss_feed_stream(
+        feed_url(&server),
+        options(vec![1, 2, 3]),
+    ));
+    let first = stream.next().await.unwrap().unwrap();
+    assert_eq!(first.plain_title, "Cave Run");
+    drop(stream);
+
+    // one more record can already be waiting in the channel, so the scrape may get through the
+    // next page, but never to the one after
+    sleep(Duration::from_millis(200)).await;
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests
+        .iter()
+        .all(|request| request.url.query() != Some("page=3")));
+}
+
+#[tokio::test]
+async fn session_calls_each_hook_per_event() {
+    let server = serve_fixtures().await;
+    fail_game_page(&server, "page-alice.itch.io-cave-run.html").await;
+
+    let items = Arc::new(Mutex::new(Vec::new()));
+    let pages = Arc::new(Mutex::new(Vec::new()));
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let session = ScrapeSession::new(feed_url(&server), options(vec![1, 2]))
+        .on_item({
+            let items = items.clone();
+            move |record| items.lock().unwrap().push(record.plain_title.clone())
+        })
+        .on_page({
+            let pages = pages.clone();
+            move |page| pages.lock().unwrap().push(page)
+        })
+        .on_error({
+            let errors = errors.clone();
+            move |url, _| errors.lock().unwrap().push(url.to_string())
+        });
+    session.run().await.unwrap();
+
+    assert_eq!(*items.lock().unwrap(), ["Sky Bound", "Tiny Farm"]);
+    assert_eq!(*pages.lock().unwrap(), [1, 2]);
+    let errors = errors.lock().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].ends_with("/pages/page-alice.itch.io-cave-run.html"));
+}
+
+#[tokio::test]
+async fn counts_downloaded_bytes_per_host() {
+    let server = serve_fixtures().await;
+    let options = options(vec![1]);
+
+    scrape_itch_rss_feed(feed_url(&server), &options, &SilentReporter)
+        .await
+        .unwrap();
+
+    let hosts = options.bandwidth.hosts();
+    assert_eq!(hosts.len(), 1);
+    assert!(hosts["127.0.0.1"] > 0);
+    assert_eq!(options.bandwidth.total(), hosts["127.0.0.1"]);
+}
+
+#[tokio::test]
+async fn records_fixtures_that_replay_the_scrape() {
+    let server = serve_fixtures().await;
+    let dir =
+        std::env::temp_dir().join(format!("game-data-scraper-fixtures-{}", std::process::id()));
+
+    let mut options = options(vec![1, 2]);
+    options.recorder = Some(Arc::new(FixtureRecorder::new(&dir).unwrap()));
+    let records = scrape_itch_rss_feed(feed_url(&server), &options, &SilentReporter)
+        .await
+        .unwrap();
+
+    let manifest = Manifest::load(&dir).unwrap();
+    assert_eq!(manifest.feeds.len(), 2);
+    assert_eq!(manifest.pages.len(), records.len());
+    for record in &records {
+        let file = &manifest.pages[&record.link];
+        assert!(dir.join(file).exists());
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn scrapes_a_game_page_passed_instead_of_a_feed() {
+    let server = serve_fixtures().await;
+    let url = format!("{}/pages/page-alice.itch.io-cave-run.html", server.uri());
+
+    let records = scrape_itch_rss_feed(url.clone(), &options(vec![1, 2]), &SilentReporter)
+        .await
+        .unwrap();
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].link, url);
+    assert_eq!(records[0].authors[0].name, "alice");
+}
+
+#[tokio::test]
+async fn follows_the_feed_advertised_by_a_browse_page() {
+    let server = serve_fixtures().await;
+    let browse = format!(
+        "<html><head><link rel=\"alternate\" type=\"application/rss+xml\" href=\"{}\"></head></html>",
+        FEED_PATH
+    );
+    Mock::given(method("GET"))
+        .and(path("/games/newest"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(browse))
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/games/newest", server.uri());
+    let records = scrape_itch_rss_feed(url, &options(vec![1]), &SilentReporter)
+        .await
+        .unwrap();
+
+    assert_eq!(records.len(), 2);
+}
+
+#[tokio::test]
+async fn stores_each_downloaded_image_once() {
+    let server = MockServer::start().await;
+    for image in ["/cover.png", "/shot.png"] {
+        Mock::given(method("GET"))
+            .and(path(image))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"same bytes".to_vec()))
+            .mount(&server)
+            .await;
+    }
+    let dir = std::env::temp_dir().join(format!("game-data-scraper-images-{}", std::process::id()));
+
+    let mut records = vec![ItchData {
+        cover_image: format!("{}/cover.png", server.uri()),
+        screenshots: vec![
+            format!("{}/shot.png", server.uri()),
+            format!("{}/missing.png", server.uri()),
+        ],
+        ..Default::default()
+    }];
+    let store = ImageStore::open(&dir).unwrap();
+    download_images(
+        &mut records,
+        &[ImageKind::Covers, ImageKind::Screenshots],
+        &store,
+        &options(vec![1]),
+        &SilentReporter,
+    )
+    .await
+    .unwrap();
+
+    let images = &records[0].images;
+    assert_eq!(images.len(), 2);
+    assert_eq!(images[0].kind, "cover");
+    assert_eq!(images[1].kind, "screenshot");
+    assert_eq!(images[0].path, images[1].path);
+    assert_eq!(fs::read(&images[0].path).unwrap(), b"same bytes");
+    assert!(dir.join("index.json").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn generates_thumbnails_of_downloaded_images() {
+    let server = MockServer::start().await;
+    let mut png = std::io::Cursor::new(Vec::new());
+    image::RgbImage::new(400, 200)
+        .write_to(&mut png, image::ImageFormat::Png)
+        .unwrap();
+    Mock::given(method("GET"))
+        .and(path("/cover.png"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(png.into_inner()))
+        .mount(&server)
+        .await;
+    let dir = std::env::temp_dir().join(format!("game-data-scraper-thumbs-{}", std::process::id()));
+
+    let mut records = vec![ItchData {
+        cover_image: format!("{}/cover.png", server.uri()),
+        ..Default::default()
+    }];
+    let store = ImageStore::open(&dir).unwrap().with_thumbnails(&[800, 100]);
+    download_images(
+        &mut records,
+        &[ImageKind::Covers],
+        &store,
+        &options(vec![1]),
+        &SilentReporter,
+    )
+    .await
+    .unwrap();
+
+    let image = &records[0].images[0];
+    assert_eq!(image.thumbnails.len(), 2);
+    assert_eq!(image.thumbnails[0].size, 100);
+    let small = image::open(&image.thumbnails[0].path).unwrap();
+    assert_eq!((small.width(), small.height()), (100, 50));
+    // never upscaled
+    assert_eq!(image.thumbnails[1].path, image.path);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn scrapes_steam_games_and_skips_other_apps() {
+    let server = MockServer::start().await;
+    let app_list = serde_json::json!({
+        "applist": { "apps": [
+            { "appid": 10, "name": "Cave Run" },
+            { "appid": 20, "name": "Cave Run Soundtrack" },
+            { "appid": 30, "name": "Delisted" },
+        ] }
+    });
+    Mock::given(method("GET"))
+        .and(path("/ISteamApps/GetAppList/v2/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(app_list))
+        .mount(&server)
+        .await;
+
+    let details = [
+        (
+            "10",
+            serde_json::json!({ "success": true, "data": {
+                "type": "game",
+                "name": "Cave Run",
+                "steam_appid": 10,
+                "short_description": "Run through caves.",
+                "is_free": false,
+                "price_overview": { "currency": "USD", "final": 499, "final_formatted": "$4.99" },
+                "developers": ["alice"],
+                "platforms": { "windows": true, "mac": false, "linux": true },
+                "categories": [{ "id": 2, "description": "Single-player" }],
+                "genres": [{ "id": "1", "description": "Action" }],
+                "release_date": { "coming_soon": false, "date": "1 Apr, 2024" },
+                "supported_languages": "English<strong>*</strong>, French<br><strong>*</strong>languages with full audio support",
+            } }),
+        ),
+        (
+            "20",
+            serde_json::json!({ "success": true, "data": { "type": "music", "name": "Cave Run Soundtrack" } }),
+        ),
+        ("30", serde_json::json!({ "success": false })),
+    ];
+    for (id, app) in details {
+        Mock::given(method("GET"))
+            .and(path("/api/appdetails"))
+            .and(query_param("appids", id))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ id: app })))
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/appreviews/10"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "query_summary": { "total_positive": 90, "total_reviews": 100 }
+        })))
+        .mount(&server)
+        .await;
+
+    let scraper = SteamScraper::new(format!("{}/ISteamApps/GetAppList/v2/", server.uri()));
+    let records = scraper
+        .scrape(&options(vec![1]), &SilentReporter)
+        .await
+        .unwrap();
+
+    assert_eq!(records.len(), 1);
+    let cave_run = &records[0];
+    assert_eq!(cave_run.plain_title, "Cave Run");
+    assert_eq!(cave_run.link, "https://store.steampowered.com/app/10/");
+    let price = cave_run.price_info.as_ref().unwrap();
+    assert_eq!(price.amount, Some(4.99));
+    assert_eq!(price.currency, "USD");
+    assert_eq!(cave_run.pub_date, "2024-04-01 00:00:00");
+    assert_eq!(cave_run.platforms, ["Windows", "Linux"]);
+    assert_eq!(cave_run.languages, ["English", "French"]);
+    assert_eq!(cave_run.genre_names(), ["Action"]);
+    assert_eq!(cave_run.tags[0].name, "Single-player");
+    assert_eq!(cave_run.authors[0].name, "alice");
+    let rating = cave_run.rating.as_ref().unwrap();
+    assert_eq!(rating.score, 4.5);
+    assert_eq!(rating.count, 100);
+}
+
+#[tokio::test]
+async fn scrapes_itch_browse_pages_until_they_run_out() {
+    let server = serve_fixtures().await;
+    let cell = |file: &str, title: &str, price: &str| {
+        format!(
+            concat!(
+                "<div class=\"game_cell\"><a class=\"game_thumb\" href=\"/pages/{file}\">",
+                "<img data-lazy_src=\"https://img.itch.zone/{file}.png\"></a>",
+                "<a class=\"title game_link\" href=\"/pages/{file}\">{title}</a>{price}",
+                "<div class=\"game_text\" title=\"Browse blurb\">Browse blurb</div></div>"
+            ),
+            file = file,
+            title = title,
+            price = price,
+        )
+    };
+    let content = [
+        cell(
+            "page-alice.itch.io-cave-run.html",
+            "Cave Run",
+            "<div class=\"price_value\">$3.00</div>",
+        ),
+        cell("page-bob.itch.io-sky-bound.html", "Sky Bound", ""),
+    ]
+    .concat();
+    for (page, content) in [("1", content), ("2", String::new())] {
+        Mock::given(method("GET"))
+            .and(path("/games/newest"))
+            .and(query_param("page", page))
+            .and(query_param("format", "json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "page": page,
+                "num_items": if content.is_empty() { 0 } else { 2 },
+                "content": content,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    // the feed url is accepted and read as the listing it belongs to
+    let records = ItchBrowseScraper::new(feed_url(&server))
+        .scrape(&options(vec![1, 2, 3]), &SilentReporter)
+        .await
+        .unwrap();
+
+    let titles: Vec<&str> = records.iter().map(|r| r.plain_title.as_str()).collect();
+    assert_eq!(titles, ["Cave Run", "Sky Bound"]);
+    let cave_run = &records[0];
+    assert_eq!(cave_run.price, "$3.00");
+    assert_eq!(cave_run.price_info.as_ref().unwrap().amount, Some(3.0));
+    assert_eq!(cave_run.description, "Browse blurb");
+    assert_eq!(
+        cave_run.cover_image,
+        "https://img.itch.zone/page-alice.itch.io-cave-run.html.png"
+    );
+    assert!(!cave_run.platforms.is_empty());
+    assert!(records[1].price.is_empty());
+}
+
+#[tokio::test]
+async fn global_rate_spaces_requests_across_hosts() {
+    let limiter = RateLimiter::default().with_global_rate(20.0).unwrap();
+
+    let started = std::time::Instant::now();
+    for url in [
+        "https://itch.io/a",
+        "https://example.com/b",
+        "https://store.steampowered.com/c",
+        "https://itch.io/d",
+        "https://example.com/e",
+    ] {
+        limiter.acquire(url).await;
+    }
+    // the first request is free, the other four wait 50ms each
+    assert!(started.elapsed() >= std::time::Duration::from_millis(190));
+    assert!(RateLimiter::default().with_global_rate(0.0).is_err());
+}
+
+#[test]
+fn sanitize_drops_scripts_and_csrf_tokens() {
+    let html = concat!(
+        "<head><meta name=\"csrf_token\" value=\"abc123\"/><title>Game</title>",
+        "<SCRIPT type=\"text/javascript\">var I = {user_id: 42};</SCRIPT></head>",
+        "<body><p>csrf_token in text stays</p><script src=\"/tracker.js\"></script></body>"
+    );
+
+    assert_eq!(
+        sanitize(html),
+        "<head><title>Game</title></head><body><p>csrf_token in text stays</p></body>"
+    );
+}